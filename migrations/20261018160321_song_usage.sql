@@ -0,0 +1,10 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS 'song_usage' (
+'id' INTEGER NOT NULL,
+'song_id' INTEGER,
+'title' TEXT NOT NULL,
+'ccli' TEXT,
+'used_at' TEXT NOT NULL,
+'service_file' TEXT,
+PRIMARY KEY(id)
+);
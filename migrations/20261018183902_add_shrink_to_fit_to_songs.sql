@@ -0,0 +1,3 @@
+-- Add migration script here
+ALTER TABLE songs
+ADD COLUMN shrink_to_fit TEXT;
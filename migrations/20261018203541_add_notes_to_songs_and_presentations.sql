@@ -0,0 +1,6 @@
+-- Add migration script here
+ALTER TABLE songs
+ADD COLUMN notes TEXT;
+
+ALTER TABLE presentations
+ADD COLUMN notes TEXT;
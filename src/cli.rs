@@ -0,0 +1,395 @@
+use std::fmt::Display;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use miette::{IntoDiagnostic, Result, miette};
+use resvg::usvg::fontdb;
+use sqlx::types::chrono::{Local, NaiveDate};
+
+use crate::core::kinds::ServiceItemKind;
+use crate::core::model::Model;
+use crate::core::openlp::Skipped;
+use crate::core::pdf_export::{self, PdfLayout};
+use crate::core::service_items::ServiceItem;
+use crate::core::songs::{CreditDefaults, Song};
+use crate::core::{file, render, songs, usage};
+use crate::lisp;
+use crate::ui::library;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ItemSummary {
+    pub title: String,
+    pub kind: String,
+    pub slides: usize,
+    pub missing: Vec<PathBuf>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ServiceSummary {
+    pub file: PathBuf,
+    pub items: Vec<ItemSummary>,
+}
+
+impl ServiceSummary {
+    #[must_use]
+    pub fn total_slides(&self) -> usize {
+        self.items.iter().map(|item| item.slides).sum()
+    }
+
+    #[must_use]
+    pub fn missing(&self) -> usize {
+        self.items.iter().map(|item| item.missing.len()).sum()
+    }
+
+    #[must_use]
+    pub fn errors(&self) -> usize {
+        self.items
+            .iter()
+            .filter(|item| item.error.is_some())
+            .count()
+    }
+
+    #[must_use]
+    pub fn is_ok(&self) -> bool {
+        self.missing() == 0 && self.errors() == 0
+    }
+}
+
+impl Display for ServiceSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", self.file.display())?;
+        for (index, item) in self.items.iter().enumerate() {
+            writeln!(
+                f,
+                "{:>3}. [{}] {} ({} slides)",
+                index + 1,
+                item.kind,
+                item.title,
+                item.slides
+            )?;
+            for path in &item.missing {
+                writeln!(f, "       missing: {}", path.display())?;
+            }
+            if let Some(error) = &item.error {
+                writeln!(f, "       error: {error}")?;
+            }
+        }
+        write!(
+            f,
+            "{} items, {} slides, {} missing media, {} errors",
+            self.items.len(),
+            self.total_slides(),
+            self.missing(),
+            self.errors()
+        )
+    }
+}
+
+impl From<&ServiceItem> for ItemSummary {
+    fn from(item: &ServiceItem) -> Self {
+        let (slides, error) = match item.to_slides() {
+            // Songs are already built with the credits from the settings.
+            Ok(_) if !item.slides.is_empty() => (item.slides.clone(), None),
+            Ok(slides) => (slides, None),
+            Err(e) => (vec![], Some(e.to_string())),
+        };
+
+        let mut media = match &item.kind {
+            ServiceItemKind::Song(song) => {
+                let mut media = vec![];
+                if let Some(background) = &song.background {
+                    media.push(background.path.clone());
+                }
+                if let Some(audio) = &song.audio {
+                    media.push(audio.clone());
+                }
+                media
+            }
+            ServiceItemKind::Video(video) => vec![video.path.clone()],
+            ServiceItemKind::Image(image) => vec![image.path.clone()],
+            ServiceItemKind::Presentation(presentation) => {
+                vec![presentation.path.clone()]
+            }
+            ServiceItemKind::Content(slide) => vec![slide.background().path.clone()],
+        };
+        for slide in &slides {
+            media.push(slide.background().path.clone());
+            if let Some(audio) = slide.audio() {
+                media.push(audio);
+            }
+        }
+
+        let mut missing: Vec<PathBuf> = media
+            .into_iter()
+            .filter(|path| !path.as_os_str().is_empty())
+            .filter(|path| !expand_path(path).exists())
+            .collect();
+        missing.sort();
+        missing.dedup();
+
+        Self {
+            title: item.title.clone(),
+            kind: item.kind.to_string(),
+            slides: slides.len(),
+            missing,
+            error,
+        }
+    }
+}
+
+fn expand_path(path: &Path) -> PathBuf {
+    let path = path.to_string_lossy();
+    let path = path.trim_start_matches("file://");
+    match (path.strip_prefix('~'), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest.trim_start_matches('/')),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Loads the service with its library songs looked up and the song slides
+/// built the way the app builds them.
+fn load_service(
+    path: impl AsRef<Path>,
+    credits: &CreditDefaults,
+    fontdb: &fontdb::Database,
+) -> Result<Vec<ServiceItem>> {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .into_diagnostic()?;
+    Ok(runtime
+        .block_on(file::load_service_with_library(path))?
+        .into_iter()
+        .map(|item| item.with_credits(credits, fontdb))
+        .collect())
+}
+
+pub fn summarize(
+    path: impl AsRef<Path>,
+    credits: &CreditDefaults,
+    fontdb: &fontdb::Database,
+) -> Result<ServiceSummary> {
+    let path = path.as_ref();
+    let items = load_service(path, credits, fontdb)?;
+    Ok(ServiceSummary {
+        file: path.to_path_buf(),
+        items: items.iter().map(ItemSummary::from).collect(),
+    })
+}
+
+pub fn check(
+    path: impl AsRef<Path>,
+    credits: &CreditDefaults,
+    fontdb: &fontdb::Database,
+) -> Result<()> {
+    let summary = summarize(path, credits, fontdb)?;
+    println!("{summary}");
+    if summary.is_ok() {
+        Ok(())
+    } else {
+        Err(miette!(
+            "{} has {} missing media and {} errors",
+            summary.file.display(),
+            summary.missing(),
+            summary.errors()
+        ))
+    }
+}
+
+pub fn render(
+    path: impl AsRef<Path>,
+    directory: impl AsRef<Path>,
+    width: u32,
+    height: u32,
+    credits: &CreditDefaults,
+    fontdb: &Arc<fontdb::Database>,
+) -> Result<()> {
+    let items = load_service(path, credits, fontdb)?;
+    let rendered = render::render_service(&items, &directory, width, height, fontdb)?;
+    println!(
+        "rendered {} slides to {}",
+        rendered.len(),
+        directory.as_ref().display()
+    );
+    Ok(())
+}
+
+pub fn export_pdf(
+    path: impl AsRef<Path>,
+    pdf: impl AsRef<Path>,
+    layout: PdfLayout,
+    width: u32,
+    height: u32,
+    credits: &CreditDefaults,
+    fontdb: &Arc<fontdb::Database>,
+) -> Result<()> {
+    let items = load_service(path, credits, fontdb)?;
+    pdf_export::export_pdf(&items, &pdf, layout, width, height, fontdb)?;
+    println!("exported {}", pdf.as_ref().display());
+    Ok(())
+}
+
+pub fn import(files: &[PathBuf]) -> Result<()> {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .into_diagnostic()?;
+    runtime.block_on(async {
+        let db = Arc::new(library::add_db().await?);
+        sqlx::migrate!().run(&*db).await.into_diagnostic()?;
+        let existing = Model::<Song>::new_song_model(Arc::clone(&db)).await.items;
+
+        let (imported, skipped) = songs::import_files(files, &existing).await;
+        for skipped in &skipped {
+            match skipped {
+                Skipped::Duplicate { title, existing } => {
+                    println!("skipped {title}, it's a duplicate of {existing}");
+                }
+                Skipped::Unreadable { title, error } => {
+                    println!("skipped {title}, it couldn't be read: {error}");
+                }
+            }
+        }
+        if imported.is_empty() {
+            return Err(miette!("There were no songs to import"));
+        }
+        let count = imported.len();
+        songs::add_songs(imported, existing, db).await?;
+        println!("imported {count} songs, skipped {}", skipped.len());
+        Ok(())
+    })
+}
+
+pub fn report(from: NaiveDate, to: Option<NaiveDate>, csv: Option<&Path>) -> Result<()> {
+    let to = to.unwrap_or_else(|| Local::now().date_naive());
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .into_diagnostic()?;
+    let report = runtime.block_on(async {
+        let db = library::add_db().await?;
+        sqlx::migrate!().run(&db).await.into_diagnostic()?;
+        usage::usage_report(from, to, Arc::new(db)).await
+    })?;
+
+    if let Some(csv) = csv {
+        fs::write(csv, usage::report_csv(&report)).into_diagnostic()?;
+        println!("wrote {} songs to {}", report.len(), csv.display());
+        return Ok(());
+    }
+    println!("song usage from {from} to {to}");
+    for usage in &report {
+        println!(
+            "{:>4}  {:<10}  {}",
+            usage.count,
+            usage.ccli.as_deref().unwrap_or("-"),
+            usage.title
+        );
+    }
+    println!("{} songs", report.len());
+    Ok(())
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WatchedService {
+    pub path: PathBuf,
+    modified: Vec<(PathBuf, Option<(SystemTime, u64)>)>,
+}
+
+impl WatchedService {
+    #[must_use]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let modified = Self::modified_times(&path);
+        Self { path, modified }
+    }
+
+    fn modified_times(path: &Path) -> Vec<(PathBuf, Option<(SystemTime, u64)>)> {
+        let files = if path.extension().is_some_and(|ext| ext == "lisp") {
+            lisp::lisp_includes(path)
+        } else {
+            vec![path.to_path_buf()]
+        };
+        files
+            .into_iter()
+            .map(|file| {
+                let modified = fs::metadata(&file).ok().and_then(|metadata| {
+                    Some((metadata.modified().ok()?, metadata.len()))
+                });
+                (file, modified)
+            })
+            .collect()
+    }
+
+    /// Returns true if the service file or anything it loads has
+    /// changed since the last check.
+    pub fn changed(&mut self) -> bool {
+        let modified = Self::modified_times(&self.path);
+        if modified == self.modified {
+            false
+        } else {
+            self.modified = modified;
+            true
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::core::images::Image;
+
+    #[test]
+    fn test_missing_media() {
+        let image = Image {
+            title: "nothing".into(),
+            path: PathBuf::from("/this/does/not/exist.png"),
+            ..Default::default()
+        };
+        let summary = ItemSummary::from(&ServiceItem::from(&image));
+        assert!(
+            summary
+                .missing
+                .contains(&PathBuf::from("/this/does/not/exist.png"))
+        );
+    }
+
+    #[test]
+    fn test_expand_path() {
+        let home = dirs::home_dir().expect("Should have a home");
+        assert_eq!(
+            expand_path(Path::new("~/pics/frodo.jpg")),
+            home.join("pics/frodo.jpg")
+        );
+        assert_eq!(
+            expand_path(Path::new("file:///tmp/frodo.jpg")),
+            PathBuf::from("/tmp/frodo.jpg")
+        );
+    }
+
+    #[test]
+    fn test_watch_includes() {
+        let dir = std::env::temp_dir().join("lumina_watch_test");
+        fs::create_dir_all(&dir).expect("Should create a temp dir");
+        let include = dir.join("include.lisp");
+        let service = dir.join("service.lisp");
+        fs::write(&include, "(slide (text \"one\"))").expect("Should write");
+        fs::write(
+            &service,
+            format!("(slide (text \"zero\"))\n(load \"{}\")", include.display()),
+        )
+        .expect("Should write");
+
+        let mut watched = WatchedService::new(&service);
+        assert_eq!(watched.modified.len(), 2);
+        assert!(!watched.changed());
+
+        fs::write(&include, "(slide (text \"one and two\"))").expect("Should write");
+        assert!(watched.changed());
+        assert!(!watched.changed());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
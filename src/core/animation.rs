@@ -1,12 +1,15 @@
 use std::time::{Duration, Instant};
 
 use cosmic::iced::{Point, Rectangle, Size, animation};
+use crisp::types::Value;
 use serde::{
     Deserialize, Serialize,
     de::{self, Visitor},
     ser::SerializeStruct,
 };
 
+use crate::lisp::{self, Form};
+
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
 pub struct SlideProps {
     opacity: f32,
@@ -96,6 +99,25 @@ impl Animation {
         }
     }
 
+    /// Written as `(cross-fade :duration 500 :easing ease-out)` with the
+    /// duration in milliseconds.
+    pub fn lisp_form(&self) -> Form {
+        let (name, duration, easing) = match self {
+            Animation::CrossFade { duration, easing } => ("cross-fade", duration, easing),
+            Animation::SlideUp { duration, easing } => ("slide-up", duration, easing),
+            Animation::SlideLeft { duration, easing } => ("slide-left", duration, easing),
+            Animation::ScrollUp { duration, easing } => ("scroll-up", duration, easing),
+        };
+        let mut form = Form::new(name);
+        if let Some(duration) = duration {
+            form = form.atom("duration", duration.as_millis());
+        }
+        if let Some(easing) = easing {
+            form = form.atom("easing", easing.lisp_name());
+        }
+        form
+    }
+
     pub fn duration(self, new_duration: Duration) -> Self {
         match self {
             Animation::CrossFade { easing, .. } => Animation::CrossFade {
@@ -154,7 +176,76 @@ pub enum Easing {
     EaseInOutBounce,
 }
 
+pub fn lisp_to_animation(value: &Value) -> Option<Animation> {
+    let Value::List(list) = value else {
+        return None;
+    };
+    let duration = lisp::keyword(list, "duration").map(|duration| {
+        Duration::from_millis(u64::try_from(i32::from(duration)).unwrap_or_default())
+    });
+    let easing = lisp::keyword(list, "easing")
+        .and_then(lisp::symbol)
+        .and_then(|name| {
+            Easing::ALL
+                .into_iter()
+                .find(|easing| easing.lisp_name() == name)
+        });
+    match list.first().and_then(lisp::symbol)? {
+        "cross-fade" => Some(Animation::CrossFade { duration, easing }),
+        "slide-up" => Some(Animation::SlideUp { duration, easing }),
+        "slide-left" => Some(Animation::SlideLeft { duration, easing }),
+        "scroll-up" => Some(Animation::ScrollUp { duration, easing }),
+        _ => None,
+    }
+}
+
 impl Easing {
+    pub const ALL: [Self; 31] = [
+        Easing::Linear,
+        Easing::EaseIn,
+        Easing::EaseOut,
+        Easing::EaseInOut,
+        Easing::EaseInQuad,
+        Easing::EaseOutQuad,
+        Easing::EaseInOutQuad,
+        Easing::EaseInCubic,
+        Easing::EaseOutCubic,
+        Easing::EaseInOutCubic,
+        Easing::EaseInQuart,
+        Easing::EaseOutQuart,
+        Easing::EaseInOutQuart,
+        Easing::EaseInQuint,
+        Easing::EaseOutQuint,
+        Easing::EaseInOutQuint,
+        Easing::EaseInExpo,
+        Easing::EaseOutExpo,
+        Easing::EaseInOutExpo,
+        Easing::EaseInCirc,
+        Easing::EaseOutCirc,
+        Easing::EaseInOutCirc,
+        Easing::EaseInBack,
+        Easing::EaseOutBack,
+        Easing::EaseInOutBack,
+        Easing::EaseInElastic,
+        Easing::EaseOutElastic,
+        Easing::EaseInOutElastic,
+        Easing::EaseInBounce,
+        Easing::EaseOutBounce,
+        Easing::EaseInOutBounce,
+    ];
+
+    /// The kebab case name used in lisp, `ease-in-out-cubic`.
+    pub fn lisp_name(&self) -> String {
+        let mut name = String::new();
+        for c in format!("{self:?}").chars() {
+            if c.is_uppercase() && !name.is_empty() {
+                name.push('-');
+            }
+            name.push(c.to_ascii_lowercase());
+        }
+        name
+    }
+
     pub fn ease(&self) -> animation::Easing {
         match self {
             Easing::Linear => animation::Easing::Linear,
@@ -284,3 +375,31 @@ impl<'de> Deserialize<'de> for Rect {
         deserializer.deserialize_struct("Rect", FIELDS, RectVisitor)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_lisp_animation() {
+        assert_eq!(Easing::EaseInOutCubic.lisp_name(), "ease-in-out-cubic");
+        let animation = Animation::SlideUp {
+            duration: Some(Duration::from_millis(750)),
+            easing: Some(Easing::EaseOutBounce),
+        };
+        let lisp = animation.lisp_form().to_string();
+        assert_eq!(lisp, "(slide-up :duration 750 :easing ease-out-bounce)");
+        assert_eq!(
+            lisp_to_animation(&crisp::reader::read(&lisp)),
+            Some(animation)
+        );
+        assert_eq!(
+            lisp_to_animation(&crisp::reader::read("(cross-fade)")),
+            Some(Animation::CrossFade {
+                duration: None,
+                easing: None
+            })
+        );
+    }
+}
@@ -20,6 +20,9 @@ pub fn save(
     fontdb: &Arc<fontdb::Database>,
 ) -> Result<()> {
     let path = path.as_ref();
+    if path.extension().is_some_and(|ext| ext == "lisp") {
+        return fs::write(path, crate::lisp::service_to_lisp(list)).into_diagnostic();
+    }
     if overwrite && path.exists() {
         fs::remove_file(path).into_diagnostic()?;
     }
@@ -227,8 +230,49 @@ pub fn find_fonts(path: impl AsRef<Path>) -> Option<Vec<PathBuf>> {
     if fonts.len() > 0 { Some(fonts) } else { None }
 }
 
+pub fn load_service(path: impl AsRef<Path>) -> Result<Vec<ServiceItem>> {
+    let path = path.as_ref();
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("lisp") => crate::lisp::load_lisp(path),
+        Some("org") => crate::org::load_org(path),
+        Some("md" | "markdown") => crate::markdown::load_markdown(path),
+        Some("ron") => {
+            let ron_string = fs::read_to_string(path).into_diagnostic()?;
+            ron::de::from_str::<Vec<ServiceItem>>(&ron_string).into_diagnostic()
+        }
+        _ => load(path),
+    }
+}
+
+/// Loads a service and looks up the songs it only points at in the
+/// library, an org file can refer to them by id.
+pub async fn load_service_with_library(
+    path: impl AsRef<Path>,
+) -> Result<Vec<ServiceItem>> {
+    let items = load_service(path)?;
+    if !items.iter().any(crate::org::is_library_reference) {
+        return Ok(items);
+    }
+    let db = Arc::new(crate::ui::library::add_db().await?);
+    Ok(crate::org::resolve_library_songs(items, db).await)
+}
+
+/// Files dropped on the service list, service files bring all of their
+/// items along.
+pub async fn items_from_file(path: PathBuf) -> Result<Vec<ServiceItem>> {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("lisp" | "org" | "md" | "markdown") => load_service_with_library(path).await,
+        _ => ServiceItem::try_from(path).map(|item| vec![item]),
+    }
+}
+
 #[allow(clippy::too_many_lines)]
 pub fn load(path: impl AsRef<Path>) -> Result<Vec<ServiceItem>> {
+    let path = if path.as_ref().is_file() {
+        unpack_load_file(&path)?
+    } else {
+        path.as_ref().to_path_buf()
+    };
     let mut dir = fs::read_dir(&path).into_diagnostic()?;
     let ron_file = dir
         .find_map(|file| {
@@ -238,7 +282,7 @@ pub fn load(path: impl AsRef<Path>) -> Result<Vec<ServiceItem>> {
                 None
             }
         })
-        .expect("Should have a ron file");
+        .ok_or_else(|| miette!("There is no ron file in {}", path.display()))?;
 
     let ron_string = fs::read_to_string(ron_file).into_diagnostic()?;
 
@@ -316,7 +360,7 @@ pub fn load(path: impl AsRef<Path>) -> Result<Vec<ServiceItem>> {
                         }
                     }
                 }
-                ServiceItemKind::Content(_slide) => todo!(),
+                ServiceItemKind::Content(_slide) => (),
             }
         }
     }
@@ -389,6 +433,7 @@ mod test {
                 title: "Death was Arrested".into(),
                 slides: slides.clone(),
                 animation: None,
+                notes: String::new(),
             },
             ServiceItem {
                 database_id: 7,
@@ -397,6 +442,7 @@ mod test {
                 title: "Death was Arrested".into(),
                 slides,
                 animation: None,
+                notes: String::new(),
             },
         ];
         items
@@ -1,11 +1,12 @@
 use crate::core::model::{Sort, SortDirection};
+use crate::lisp::{self, Form};
 use crate::{Background, Slide, SlideBuilder, TextAlignment};
 
 use super::content::Content;
 use super::kinds::ServiceItemKind;
 use super::model::{LibraryKind, Model};
 use super::service_items::ServiceTrait;
-use crisp::types::{Keyword, Symbol, Value};
+use crisp::types::{Keyword, Value};
 use itertools::Itertools;
 use miette::{IntoDiagnostic, Result, miette};
 use serde::{Deserialize, Serialize};
@@ -52,8 +53,22 @@ impl From<&Path> for Image {
 }
 
 impl From<&Image> for Value {
-    fn from(_value: &Image) -> Self {
-        Self::List(vec![Self::Symbol(Symbol("image".into()))])
+    fn from(value: &Image) -> Self {
+        value.lisp_form().into()
+    }
+}
+
+impl Image {
+    #[must_use]
+    pub fn lisp_form(&self) -> Form {
+        let form = Form::new("image")
+            .string("source", self.path.display().to_string())
+            .string("title", &self.title);
+        if self.id == 0 {
+            form
+        } else {
+            form.atom("id", self.id)
+        }
     }
 }
 
@@ -102,17 +117,21 @@ impl From<&Value> for Image {
                     .position(|v| v == &Value::Keyword(Keyword::from("source")))
                 {
                     let pos = path_pos + 1;
-                    list.get(pos).map(|p| PathBuf::from(String::from(p)))
+                    list.get(pos).map(|p| PathBuf::from(lisp::string(p)))
                 } else {
                     None
                 };
 
-                let title = path.clone().map(|p| {
-                    let path = p.to_str().unwrap_or_default().to_string();
-                    let title = path.rsplit_once('/').unwrap_or_default().1;
-                    title.to_string()
-                });
+                let title =
+                    lisp::keyword(list, "title").map(lisp::string).or_else(|| {
+                        path.clone().map(|p| {
+                            let path = p.to_str().unwrap_or_default().to_string();
+                            let title = path.rsplit_once('/').unwrap_or_default().1;
+                            title.to_string()
+                        })
+                    });
                 Self {
+                    id: lisp::keyword(list, "id").map(i32::from).unwrap_or_default(),
                     title: title.unwrap_or_default(),
                     path: path.unwrap_or_default(),
                     ..Default::default()
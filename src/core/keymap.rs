@@ -0,0 +1,474 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use cosmic::iced::keyboard::key::Named;
+use cosmic::iced::keyboard::{Key, Modifiers};
+use cosmic::widget::menu::KeyBind;
+use cosmic::widget::menu::key_bind::Modifier;
+use serde::{Deserialize, Serialize};
+
+/// Where a command can run. Chords only conflict when their scopes can be
+/// active at the same time, and the library stays open beside the presenter
+/// and the editors.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Scope {
+    Global,
+    Presenter,
+    Library,
+    SongEditor,
+}
+
+impl Scope {
+    #[must_use]
+    pub fn overlaps(self, other: Self) -> bool {
+        !matches!(
+            (self, other),
+            (Self::Presenter, Self::SongEditor) | (Self::SongEditor, Self::Presenter)
+        )
+    }
+}
+
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+)]
+pub enum Command {
+    New,
+    Open,
+    Save,
+    SaveAs,
+    OpenSettings,
+    ToggleLowerThird,
+    ToggleStageDisplay,
+    Search,
+    NextSlide,
+    PreviousSlide,
+    PlayPauseVideo,
+    Black,
+    ClearText,
+    Freeze,
+    Logo,
+    Live,
+    ToggleLibrary,
+    AddLibraryItem,
+    DeleteLibraryItem,
+    ImportSongs,
+    CloseEditor,
+    SearchSongsOnline,
+    PlayPauseSongAudio,
+}
+
+impl Command {
+    pub const ALL: [Self; 23] = [
+        Self::New,
+        Self::Open,
+        Self::Save,
+        Self::SaveAs,
+        Self::OpenSettings,
+        Self::ToggleLowerThird,
+        Self::ToggleStageDisplay,
+        Self::Search,
+        Self::NextSlide,
+        Self::PreviousSlide,
+        Self::PlayPauseVideo,
+        Self::Black,
+        Self::ClearText,
+        Self::Freeze,
+        Self::Logo,
+        Self::Live,
+        Self::ToggleLibrary,
+        Self::AddLibraryItem,
+        Self::DeleteLibraryItem,
+        Self::ImportSongs,
+        Self::CloseEditor,
+        Self::SearchSongsOnline,
+        Self::PlayPauseSongAudio,
+    ];
+
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::New => "New Service",
+            Self::Open => "Open Service",
+            Self::Save => "Save Service",
+            Self::SaveAs => "Save Service As",
+            Self::OpenSettings => "Open Settings",
+            Self::ToggleLowerThird => "Toggle Lower Third",
+            Self::ToggleStageDisplay => "Toggle Stage Display",
+            Self::Search => "Search",
+            Self::NextSlide => "Next Slide",
+            Self::PreviousSlide => "Previous Slide",
+            Self::PlayPauseVideo => "Play or Pause Video",
+            Self::Black => "Black Screen",
+            Self::ClearText => "Clear Text",
+            Self::Freeze => "Freeze",
+            Self::Logo => "Show Logo",
+            Self::Live => "Back to Live",
+            Self::ToggleLibrary => "Toggle Library",
+            Self::AddLibraryItem => "Add Library Item",
+            Self::DeleteLibraryItem => "Delete Library Item",
+            Self::ImportSongs => "Import Songs",
+            Self::CloseEditor => "Close Editor",
+            Self::SearchSongsOnline => "Search Songs Online",
+            Self::PlayPauseSongAudio => "Play or Pause Song Audio",
+        }
+    }
+
+    #[must_use]
+    pub const fn scope(self) -> Scope {
+        match self {
+            Self::New
+            | Self::Open
+            | Self::Save
+            | Self::SaveAs
+            | Self::OpenSettings
+            | Self::ToggleLowerThird
+            | Self::ToggleStageDisplay
+            | Self::Search
+            | Self::ToggleLibrary => Scope::Global,
+            Self::NextSlide
+            | Self::PreviousSlide
+            | Self::PlayPauseVideo
+            | Self::Black
+            | Self::ClearText
+            | Self::Freeze
+            | Self::Logo
+            | Self::Live => Scope::Presenter,
+            Self::AddLibraryItem | Self::DeleteLibraryItem | Self::ImportSongs => {
+                Scope::Library
+            }
+            Self::CloseEditor | Self::SearchSongsOnline | Self::PlayPauseSongAudio => {
+                Scope::SongEditor
+            }
+        }
+    }
+}
+
+/// The named keys a chord can use, with how they are saved and shown.
+const NAMED_KEYS: [(Named, &str, &str); 26] = [
+    (Named::ArrowLeft, "arrow-left", "Left"),
+    (Named::ArrowRight, "arrow-right", "Right"),
+    (Named::ArrowUp, "arrow-up", "Up"),
+    (Named::ArrowDown, "arrow-down", "Down"),
+    (Named::Space, "space", "Space"),
+    (Named::Enter, "enter", "Enter"),
+    (Named::Escape, "escape", "Esc"),
+    (Named::Tab, "tab", "Tab"),
+    (Named::Backspace, "backspace", "Backspace"),
+    (Named::Delete, "delete", "Delete"),
+    (Named::Home, "home", "Home"),
+    (Named::End, "end", "End"),
+    (Named::PageUp, "page-up", "Page Up"),
+    (Named::PageDown, "page-down", "Page Down"),
+    (Named::F1, "f1", "F1"),
+    (Named::F2, "f2", "F2"),
+    (Named::F3, "f3", "F3"),
+    (Named::F4, "f4", "F4"),
+    (Named::F5, "f5", "F5"),
+    (Named::F6, "f6", "F6"),
+    (Named::F7, "f7", "F7"),
+    (Named::F8, "f8", "F8"),
+    (Named::F9, "f9", "F9"),
+    (Named::F10, "f10", "F10"),
+    (Named::F11, "f11", "F11"),
+    (Named::F12, "f12", "F12"),
+];
+
+/// A key with the modifiers held down, like `Ctrl+Shift+S`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Chord {
+    /// A lowercase character or one of the named keys, like `arrow-left`.
+    pub key: String,
+    #[serde(default)]
+    pub ctrl: bool,
+    #[serde(default)]
+    pub alt: bool,
+    #[serde(default)]
+    pub shift: bool,
+    #[serde(default)]
+    pub logo: bool,
+}
+
+impl Chord {
+    fn new(key: &str) -> Self {
+        Self {
+            key: key.to_string(),
+            ctrl: false,
+            alt: false,
+            shift: false,
+            logo: false,
+        }
+    }
+
+    const fn ctrl(mut self) -> Self {
+        self.ctrl = true;
+        self
+    }
+
+    const fn shift(mut self) -> Self {
+        self.shift = true;
+        self
+    }
+
+    /// The chord for a key press, or `None` for keys that can't be bound
+    /// like a lone modifier.
+    #[must_use]
+    pub fn from_key(key: &Key, modifiers: Modifiers) -> Option<Self> {
+        let key = match key {
+            Key::Character(character) if character.as_str() == " " => "space".to_string(),
+            Key::Character(character) => character.to_lowercase(),
+            Key::Named(named) => NAMED_KEYS
+                .iter()
+                .find(|(key, _, _)| key == named)
+                .map(|(_, name, _)| (*name).to_string())?,
+            Key::Unidentified => return None,
+        };
+        Some(Self {
+            key,
+            ctrl: modifiers.control(),
+            alt: modifiers.alt(),
+            shift: modifiers.shift(),
+            logo: modifiers.logo(),
+        })
+    }
+
+    fn key(&self) -> Key {
+        NAMED_KEYS
+            .iter()
+            .find(|(_, name, _)| *name == self.key)
+            .map_or_else(
+                || Key::Character(self.key.as_str().into()),
+                |(named, _, _)| Key::Named(*named),
+            )
+    }
+
+    #[must_use]
+    pub fn key_bind(&self) -> KeyBind {
+        let mut modifiers = vec![];
+        if self.ctrl {
+            modifiers.push(Modifier::Ctrl);
+        }
+        if self.alt {
+            modifiers.push(Modifier::Alt);
+        }
+        if self.shift {
+            modifiers.push(Modifier::Shift);
+        }
+        if self.logo {
+            modifiers.push(Modifier::Super);
+        }
+        KeyBind {
+            modifiers,
+            key: self.key(),
+        }
+    }
+}
+
+impl Display for Chord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (held, name) in [
+            (self.logo, "Super"),
+            (self.ctrl, "Ctrl"),
+            (self.alt, "Alt"),
+            (self.shift, "Shift"),
+        ] {
+            if held {
+                write!(f, "{name}+")?;
+            }
+        }
+        let label = NAMED_KEYS
+            .iter()
+            .find(|(_, name, _)| *name == self.key)
+            .map_or_else(
+                || self.key.to_uppercase(),
+                |(_, _, label)| (*label).to_string(),
+            );
+        write!(f, "{label}")
+    }
+}
+
+/// Which chords run which commands.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Keymap(BTreeMap<Command, Vec<Chord>>);
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let bindings = [
+            (Command::New, vec![Chord::new("n").ctrl()]),
+            (Command::Open, vec![Chord::new("o").ctrl()]),
+            (Command::Save, vec![Chord::new("s").ctrl()]),
+            (Command::SaveAs, vec![Chord::new("s").ctrl().shift()]),
+            (Command::OpenSettings, vec![Chord::new(",").ctrl()]),
+            (
+                Command::Search,
+                vec![
+                    Chord::new("k").ctrl(),
+                    Chord::new("f").ctrl(),
+                    Chord::new("/"),
+                ],
+            ),
+            (
+                Command::NextSlide,
+                vec![
+                    Chord::new("arrow-right"),
+                    Chord::new("space"),
+                    Chord::new("j"),
+                    Chord::new("l"),
+                ],
+            ),
+            (
+                Command::PreviousSlide,
+                vec![Chord::new("arrow-left"), Chord::new("k"), Chord::new("h")],
+            ),
+            (Command::Black, vec![Chord::new("b")]),
+            (Command::ClearText, vec![Chord::new("c")]),
+            (Command::Freeze, vec![Chord::new("f")]),
+            (Command::Logo, vec![Chord::new("o")]),
+            (Command::Live, vec![Chord::new("escape")]),
+        ];
+        Self(bindings.into_iter().collect())
+    }
+}
+
+impl Keymap {
+    #[must_use]
+    pub fn chords(&self, command: Command) -> &[Chord] {
+        self.0.get(&command).map_or(&[], Vec::as_slice)
+    }
+
+    /// The command a chord runs in the scopes that are active right now.
+    #[must_use]
+    pub fn command(
+        &self,
+        chord: &Chord,
+        active: impl Fn(Scope) -> bool,
+    ) -> Option<Command> {
+        self.0.iter().find_map(|(command, chords)| {
+            (active(command.scope()) && chords.contains(chord)).then_some(*command)
+        })
+    }
+
+    /// The other command already using a chord where this one could run.
+    #[must_use]
+    pub fn conflict(&self, command: Command, chord: &Chord) -> Option<Command> {
+        self.0.iter().find_map(|(other, chords)| {
+            (*other != command
+                && other.scope().overlaps(command.scope())
+                && chords.contains(chord))
+            .then_some(*other)
+        })
+    }
+
+    /// Every pair of commands sharing a chord in overlapping scopes.
+    #[must_use]
+    pub fn conflicts(&self) -> Vec<(Chord, Command, Command)> {
+        self.0
+            .iter()
+            .flat_map(|(command, chords)| {
+                chords.iter().filter_map(|chord| {
+                    self.conflict(*command, chord)
+                        .filter(|other| command < other)
+                        .map(|other| (chord.clone(), *command, other))
+                })
+            })
+            .collect()
+    }
+
+    /// Adds a chord to a command, taking it away from any command it
+    /// conflicts with.
+    pub fn bind(&mut self, command: Command, chord: Chord) {
+        while let Some(other) = self.conflict(command, &chord) {
+            self.unbind(other, &chord);
+        }
+        let chords = self.0.entry(command).or_default();
+        if !chords.contains(&chord) {
+            chords.push(chord);
+        }
+    }
+
+    pub fn unbind(&mut self, command: Command, chord: &Chord) {
+        if let Some(chords) = self.0.get_mut(&command) {
+            chords.retain(|bound| bound != chord);
+        }
+    }
+
+    pub fn clear(&mut self, command: Command) {
+        self.0.remove(&command);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_chord_from_key() {
+        let space = Chord::from_key(&Key::Character(" ".into()), Modifiers::empty());
+        let named_space = Chord::from_key(&Key::Named(Named::Space), Modifiers::empty());
+        assert_eq!(space, named_space);
+        assert_eq!(space, Some(Chord::new("space")));
+
+        let save_as = Chord::from_key(
+            &Key::Character("S".into()),
+            Modifiers::CTRL | Modifiers::SHIFT,
+        )
+        .expect("Should be a chord");
+        assert_eq!(save_as, Chord::new("s").ctrl().shift());
+        assert_eq!(save_as.to_string(), "Ctrl+Shift+S");
+        assert_eq!(Chord::new("arrow-right").to_string(), "Right");
+        assert_eq!(
+            Chord::from_key(&Key::Named(Named::Control), Modifiers::CTRL),
+            None
+        );
+    }
+
+    #[test]
+    fn test_default_keymap() {
+        let keymap = Keymap::default();
+        assert_eq!(keymap.conflicts(), vec![]);
+        let presenting = |scope: Scope| matches!(scope, Scope::Global | Scope::Presenter);
+        assert_eq!(
+            keymap.command(&Chord::new("f"), presenting),
+            Some(Command::Freeze)
+        );
+        assert_eq!(
+            keymap.command(&Chord::new("f").ctrl(), presenting),
+            Some(Command::Search)
+        );
+        let editing = |scope: Scope| matches!(scope, Scope::Global | Scope::SongEditor);
+        assert_eq!(keymap.command(&Chord::new("f"), editing), None);
+    }
+
+    #[test]
+    fn test_rebinding() {
+        let mut keymap = Keymap::default();
+        assert_eq!(
+            keymap.conflict(Command::PlayPauseVideo, &Chord::new("space")),
+            Some(Command::NextSlide)
+        );
+        assert_eq!(
+            keymap.conflict(Command::PlayPauseSongAudio, &Chord::new("space")),
+            None
+        );
+
+        keymap.bind(Command::PlayPauseVideo, Chord::new("space"));
+        assert_eq!(
+            keymap.chords(Command::NextSlide),
+            [Chord::new("arrow-right"), Chord::new("j"), Chord::new("l")]
+        );
+        assert_eq!(
+            keymap.chords(Command::PlayPauseVideo),
+            [Chord::new("space")]
+        );
+        assert_eq!(keymap.conflicts(), vec![]);
+    }
+
+    #[test]
+    fn test_serialization() {
+        let keymap = Keymap::default();
+        let ron = ron::to_string(&keymap).expect("Should serialize");
+        let read: Keymap = ron::from_str(&ron).expect("Should deserialize");
+        assert_eq!(read, keymap);
+    }
+}
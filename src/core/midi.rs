@@ -0,0 +1,275 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::keymap::Command;
+
+/// Controllers count as pressed from this value up, like a sustain pedal.
+const PRESSED: u8 = 64;
+
+/// The presenter commands MIDI can run, besides jumping to a verse.
+pub const COMMANDS: [Command; 8] = [
+    Command::NextSlide,
+    Command::PreviousSlide,
+    Command::Black,
+    Command::ClearText,
+    Command::Freeze,
+    Command::Logo,
+    Command::Live,
+    Command::PlayPauseVideo,
+];
+
+/// The choices for a new binding, ending with jumping to a verse.
+pub const ACTION_LABELS: [&str; 9] = [
+    COMMANDS[0].label(),
+    COMMANDS[1].label(),
+    COMMANDS[2].label(),
+    COMMANDS[3].label(),
+    COMMANDS[4].label(),
+    COMMANDS[5].label(),
+    COMMANDS[6].label(),
+    COMMANDS[7].label(),
+    "Jump to Verse",
+];
+
+/// A note or controller press read from a MIDI message. Channels count
+/// from 1 like keyboards show them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Event {
+    Note {
+        channel: u8,
+        note: u8,
+    },
+    Controller {
+        channel: u8,
+        controller: u8,
+        pressed: bool,
+    },
+}
+
+impl Event {
+    /// Reads note on and control change messages, ignoring the rest.
+    #[must_use]
+    pub fn parse(bytes: &[u8]) -> Option<Self> {
+        let [status, data, value, ..] = *bytes else {
+            return None;
+        };
+        let channel = (status & 0x0f) + 1;
+        match status & 0xf0 {
+            0x90 if value > 0 => Some(Self::Note {
+                channel,
+                note: data,
+            }),
+            0xb0 => Some(Self::Controller {
+                channel,
+                controller: data,
+                pressed: value >= PRESSED,
+            }),
+            _ => None,
+        }
+    }
+
+    /// The trigger this event would bind to when learning.
+    #[must_use]
+    pub const fn trigger(self) -> Trigger {
+        match self {
+            Self::Note { channel, note } => Trigger::Note {
+                channel: Some(channel),
+                note,
+            },
+            Self::Controller {
+                channel,
+                controller,
+                ..
+            } => Trigger::Controller {
+                channel: Some(channel),
+                controller,
+            },
+        }
+    }
+}
+
+/// A note or controller that runs an action. Without a channel it listens
+/// on all of them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Trigger {
+    Note { channel: Option<u8>, note: u8 },
+    Controller { channel: Option<u8>, controller: u8 },
+}
+
+impl Trigger {
+    #[must_use]
+    pub fn matches(self, event: Event) -> bool {
+        let on_channel = |channel: Option<u8>, played: u8| {
+            channel.is_none_or(|channel| channel == played)
+        };
+        match (self, event) {
+            (
+                Self::Note { channel, note },
+                Event::Note {
+                    channel: played,
+                    note: played_note,
+                },
+            ) => note == played_note && on_channel(channel, played),
+            (
+                Self::Controller {
+                    channel,
+                    controller,
+                },
+                Event::Controller {
+                    channel: played,
+                    controller: played_controller,
+                    pressed,
+                },
+            ) => {
+                pressed && controller == played_controller && on_channel(channel, played)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Display for Trigger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let channel = match self {
+            Self::Note { note, channel } => {
+                write!(f, "Note {note}")?;
+                channel
+            }
+            Self::Controller {
+                controller,
+                channel,
+            } => {
+                write!(f, "CC {controller}")?;
+                channel
+            }
+        };
+        match channel {
+            Some(channel) => write!(f, " on channel {channel}"),
+            None => write!(f, " on any channel"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Action {
+    Command(Command),
+    /// Jumps to the next verse in the live song with this name, like
+    /// `Chorus 1` or just `Chorus`.
+    Verse(String),
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Command(command) => write!(f, "{}", command.label()),
+            Self::Verse(label) => write!(f, "Jump to {label}"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Binding {
+    pub trigger: Trigger,
+    pub action: Action,
+}
+
+/// The MIDI keyboards and pedals that drive the presenter.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MidiControl {
+    pub enabled: bool,
+    pub bindings: Vec<Binding>,
+}
+
+impl MidiControl {
+    #[must_use]
+    pub fn action(&self, event: Event) -> Option<&Action> {
+        self.bindings
+            .iter()
+            .find(|binding| binding.trigger.matches(event))
+            .map(|binding| &binding.action)
+    }
+
+    /// Binds a trigger, replacing whatever it ran before.
+    pub fn bind(&mut self, trigger: Trigger, action: Action) {
+        self.bindings.retain(|binding| binding.trigger != trigger);
+        self.bindings.push(Binding { trigger, action });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            Event::parse(&[0x90, 60, 100]),
+            Some(Event::Note {
+                channel: 1,
+                note: 60
+            })
+        );
+        // A note on with no velocity is a note off.
+        assert_eq!(Event::parse(&[0x90, 60, 0]), None);
+        assert_eq!(Event::parse(&[0x80, 60, 64]), None);
+        assert_eq!(
+            Event::parse(&[0xb3, 64, 127]),
+            Some(Event::Controller {
+                channel: 4,
+                controller: 64,
+                pressed: true
+            })
+        );
+        assert_eq!(Event::parse(&[0xf8]), None);
+    }
+
+    #[test]
+    fn test_bindings() {
+        let mut midi = MidiControl::default();
+        midi.bind(
+            Trigger::Controller {
+                channel: None,
+                controller: 67,
+            },
+            Action::Command(Command::NextSlide),
+        );
+        midi.bind(
+            Trigger::Note {
+                channel: Some(10),
+                note: 36,
+            },
+            Action::Verse("Chorus".into()),
+        );
+
+        let pedal =
+            |value| Event::parse(&[0xb5, 67, value]).expect("Should be a controller");
+        assert_eq!(
+            midi.action(pedal(127)),
+            Some(&Action::Command(Command::NextSlide))
+        );
+        assert_eq!(midi.action(pedal(0)), None);
+
+        let pad = |status| Event::parse(&[status, 36, 90]).expect("Should be a note");
+        assert_eq!(
+            midi.action(pad(0x99)),
+            Some(&Action::Verse("Chorus".into()))
+        );
+        assert_eq!(midi.action(pad(0x90)), None);
+
+        midi.bind(
+            Trigger::Note {
+                channel: Some(10),
+                note: 36,
+            },
+            Action::Command(Command::Black),
+        );
+        assert_eq!(midi.bindings.len(), 2);
+        assert_eq!(
+            midi.action(pad(0x99)),
+            Some(&Action::Command(Command::Black))
+        );
+    }
+}
@@ -2,15 +2,23 @@ pub mod animation;
 pub mod content;
 pub mod file;
 pub mod images;
+pub mod keymap;
 pub mod kinds;
+pub mod midi;
 pub mod model;
+pub mod openlp;
+pub mod openlyrics;
+pub mod pdf_export;
 pub mod presentations;
+pub mod render;
 pub mod service_items;
 pub mod settings;
 pub mod slide;
 pub mod slide_actions;
 pub mod song_search;
 pub mod songs;
+pub mod songselect;
 pub mod thumbnail;
+pub mod usage;
 pub mod videos;
 pub mod ytdl;
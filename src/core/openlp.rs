@@ -0,0 +1,438 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+use miette::{IntoDiagnostic, Result, miette};
+use roxmltree::Document;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteRow};
+use sqlx::{Row, SqlitePool, query};
+use tracing::{debug, warn};
+
+use crate::core::openlyrics;
+use crate::core::songs::{Song, Songbook, VerseName};
+
+/// A song that was left out of an import.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Skipped {
+    /// The song is already in the library as `existing`.
+    Duplicate { title: String, existing: String },
+    /// The song's row couldn't be read.
+    Unreadable { title: String, error: String },
+}
+
+impl Display for Skipped {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Duplicate { title, .. } => {
+                write!(f, "{title} (already in the library)")
+            }
+            Self::Unreadable { title, .. } => write!(f, "{title} (couldn't be read)"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct OpenLpImport {
+    pub songs: Vec<Song>,
+    pub skipped: Vec<Skipped>,
+}
+
+/// Reads every song out of an OpenLP `songs.sqlite` and splits out the
+/// ones that are already in the library or can't be read.
+pub async fn import(path: impl AsRef<Path>, existing: &[Song]) -> Result<OpenLpImport> {
+    let (songs, unreadable) = read_songs(path).await?;
+    let mut import = find_duplicates(songs, existing);
+    for skipped in &import.skipped {
+        if let Skipped::Duplicate { title, existing } = skipped {
+            warn!(%title, %existing, "skipping a song that is already in the library");
+        }
+    }
+    import.skipped.extend(unreadable);
+    Ok(import)
+}
+
+/// Reads the songs that can be read, a bad row is skipped instead of
+/// stopping the rest.
+pub async fn read_songs(path: impl AsRef<Path>) -> Result<(Vec<Song>, Vec<Skipped>)> {
+    let path = path.as_ref();
+    let options = SqliteConnectOptions::new().filename(path).read_only(true);
+    let db = SqlitePool::connect_with(options).await.into_diagnostic()?;
+
+    let rows = query(
+        "SELECT id, title, lyrics, verse_order, copyright, ccli_number FROM songs ORDER BY id",
+    )
+    .fetch_all(&db)
+    .await
+    .into_diagnostic()?;
+
+    let mut songs = Vec::with_capacity(rows.len());
+    let mut unreadable = vec![];
+    for row in rows {
+        match read_song(&db, &row, path).await {
+            Ok(song) => {
+                debug!(title = %song.title, "read song from openlp");
+                songs.push(song);
+            }
+            Err(e) => {
+                let title = row.try_get("title").unwrap_or_else(|_| {
+                    format!("Song {}", row.try_get::<i32, _>("id").unwrap_or_default())
+                });
+                warn!(?e, %title, "skipping a song that can't be read");
+                unreadable.push(Skipped::Unreadable {
+                    title,
+                    error: e.to_string(),
+                });
+            }
+        }
+    }
+    db.close().await;
+    Ok((songs, unreadable))
+}
+
+async fn read_song(db: &SqlitePool, row: &SqliteRow, path: &Path) -> Result<Song> {
+    let id: i32 = row.try_get("id").into_diagnostic()?;
+    let mut song = song_from_row(row)?;
+
+    let authors: Vec<String> = query(
+        "SELECT authors.display_name FROM authors JOIN authors_songs ON authors.id = authors_songs.author_id WHERE authors_songs.song_id = $1",
+    )
+    .bind(id)
+    .fetch_all(db)
+    .await
+    .into_diagnostic()?
+    .iter()
+    .filter_map(|row| row.try_get("display_name").ok())
+    .collect();
+    if !authors.is_empty() {
+        song.author = Some(authors.join(", "));
+    }
+
+    song.songbooks = songbooks(db, id).await;
+
+    song.audio = query("SELECT * FROM media_files WHERE song_id = $1")
+        .bind(id)
+        .fetch_all(db)
+        .await
+        .into_diagnostic()?
+        .iter()
+        .find_map(|row| media_path(row, path));
+    Ok(song)
+}
+
+/// OpenLP 2.4 moved songbooks into their own table so a song can be in
+/// more than one, older versions keep a single book on the song itself.
+async fn songbooks(db: &SqlitePool, id: i32) -> Vec<Songbook> {
+    let rows = match query(
+        "SELECT song_books.name, songs_songbooks.entry FROM song_books JOIN songs_songbooks ON song_books.id = songs_songbooks.songbook_id WHERE songs_songbooks.song_id = $1",
+    )
+    .bind(id)
+    .fetch_all(db)
+    .await
+    {
+        Ok(rows) => rows,
+        Err(e) => {
+            debug!(?e, "reading songbooks the old way");
+            query(
+                "SELECT song_books.name, songs.song_number AS entry FROM song_books JOIN songs ON song_books.id = songs.song_book_id WHERE songs.id = $1",
+            )
+            .bind(id)
+            .fetch_all(db)
+            .await
+            .unwrap_or_default()
+        }
+    };
+    rows.iter()
+        .filter_map(|row| {
+            let name: String = row.try_get("name").ok()?;
+            let entry = row
+                .try_get::<Option<String>, _>("entry")
+                .ok()
+                .flatten()
+                .map(|entry| entry.trim().to_string())
+                .filter(|entry| !entry.is_empty());
+            Some(Songbook {
+                name: name.trim().to_string(),
+                entry,
+            })
+        })
+        .filter(|songbook| !songbook.name.is_empty())
+        .collect()
+}
+
+fn song_from_row(row: &SqliteRow) -> Result<Song> {
+    let title: String = row.try_get("title").into_diagnostic()?;
+    let lyrics: String = row.try_get("lyrics").into_diagnostic()?;
+    let order: Option<String> = row.try_get("verse_order").into_diagnostic()?;
+    let text = |column: &str| {
+        row.try_get::<Option<String>, _>(column)
+            .ok()
+            .flatten()
+            .map(|text| text.trim().to_string())
+            .filter(|text| !text.is_empty())
+    };
+
+    let (names, document_order, verse_map) = parse_lyrics(&lyrics)
+        .map_err(|e| miette!("Couldn't read the lyrics of {title}: {e}"))?;
+    let verses =
+        openlyrics::verse_order(order.as_deref(), &names, document_order, &verse_map);
+
+    Ok(Song {
+        title: title.trim().to_string(),
+        ccli: text("ccli_number"),
+        copyright: text("copyright"),
+        lyrics: ron::ser::to_string(&Some(&verse_map)).ok(),
+        verse_order: Some(verses.iter().map(VerseName::get_name).collect()),
+        verses: Some(verses),
+        verse_map: Some(verse_map),
+        ..Default::default()
+    })
+}
+
+type Verses = (
+    HashMap<String, VerseName>,
+    Vec<VerseName>,
+    HashMap<VerseName, String>,
+);
+
+/// OpenLP keeps the lyrics as xml with a verse element per part of the
+/// song, named by a `type` like `v` and a `label` like `1`.
+fn parse_lyrics(xml: &str) -> Result<Verses> {
+    let document = Document::parse(xml).into_diagnostic()?;
+    let mut names = HashMap::new();
+    let mut document_order = vec![];
+    let mut verse_map: HashMap<VerseName, String> = HashMap::new();
+
+    for verse in document
+        .descendants()
+        .filter(|node| node.has_tag_name("verse"))
+    {
+        let name = format!(
+            "{}{}",
+            verse.attribute("type").unwrap_or("v"),
+            verse.attribute("label").unwrap_or("1")
+        )
+        .to_lowercase();
+        let verse_name = openlyrics::verse_name_from_openlyrics(&name);
+        let text = clean_text(verse.text().unwrap_or_default());
+        names.insert(name, verse_name);
+        if !document_order.contains(&verse_name) {
+            document_order.push(verse_name);
+        }
+        verse_map
+            .entry(verse_name)
+            .and_modify(|lyric| {
+                lyric.push_str("\n\n");
+                lyric.push_str(&text);
+            })
+            .or_insert(text);
+    }
+
+    Ok((names, document_order, verse_map))
+}
+
+/// Drops OpenLP's formatting tags like `{r}` and `{/r}` and turns its
+/// optional splits into slide breaks.
+fn clean_text(text: &str) -> String {
+    let text = text.replace("[---]", "\n\n").replace("[--}{--]", "\n\n");
+    let mut cleaned = String::with_capacity(text.len());
+    let mut rest = text.as_str();
+    while let Some(start) = rest.find('{') {
+        cleaned.push_str(&rest[..start]);
+        let after = &rest[start..];
+        match after.find('}') {
+            Some(end)
+                if end > 1
+                    && after[1..end]
+                        .trim_start_matches('/')
+                        .chars()
+                        .all(char::is_alphanumeric) =>
+            {
+                rest = &after[end + 1..];
+            }
+            _ => {
+                cleaned.push('{');
+                rest = &after[1..];
+            }
+        }
+    }
+    cleaned.push_str(rest);
+
+    cleaned
+        .split("\n\n")
+        .map(|slide| {
+            slide
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .collect::<Vec<&str>>()
+                .join("\n")
+        })
+        .filter(|slide| !slide.is_empty())
+        .collect::<Vec<String>>()
+        .join("\n\n")
+}
+
+/// Older versions of OpenLP store a plain `file_name` while newer ones
+/// store a json encoded `file_path`.
+fn media_path(row: &SqliteRow, db_path: &Path) -> Option<PathBuf> {
+    let raw: String = row
+        .try_get::<Option<String>, _>("file_path")
+        .ok()
+        .flatten()
+        .or_else(|| row.try_get::<Option<String>, _>("file_name").ok().flatten())?;
+    let path = if raw.trim_start().starts_with('{') {
+        let json: serde_json::Value = serde_json::from_str(&raw).ok()?;
+        json.get("parts")?
+            .as_array()?
+            .iter()
+            .filter_map(serde_json::Value::as_str)
+            .collect::<PathBuf>()
+    } else {
+        PathBuf::from(raw)
+    };
+    if path.is_relative() {
+        db_path.parent().map(|parent| parent.join(&path))
+    } else {
+        Some(path)
+    }
+}
+
+fn normalize(title: &str) -> String {
+    title
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Songs are duplicates if they share a CCLI number or, when either is
+/// missing one, the same title.
+fn is_duplicate(song: &Song, other: &Song) -> bool {
+    match (&song.ccli, &other.ccli) {
+        (Some(ccli), Some(other_ccli)) => ccli.trim() == other_ccli.trim(),
+        _ => normalize(&song.title) == normalize(&other.title),
+    }
+}
+
+#[must_use]
+pub fn find_duplicates(songs: Vec<Song>, existing: &[Song]) -> OpenLpImport {
+    let mut import = OpenLpImport::default();
+    for song in songs {
+        let found = existing
+            .iter()
+            .chain(import.songs.iter())
+            .find(|other| is_duplicate(&song, other));
+        if let Some(other) = found {
+            import.skipped.push(Skipped::Duplicate {
+                title: song.title.clone(),
+                existing: other.title.clone(),
+            });
+        } else {
+            import.songs.push(song);
+        }
+    }
+    import
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    async fn openlp_db(path: &Path) -> Result<()> {
+        let _ = std::fs::remove_file(path);
+        let options = SqliteConnectOptions::new()
+            .filename(path)
+            .create_if_missing(true);
+        let db = SqlitePool::connect_with(options).await.into_diagnostic()?;
+        for statement in [
+            "CREATE TABLE songs (id INTEGER PRIMARY KEY, title VARCHAR(255) NOT NULL, lyrics TEXT NOT NULL, verse_order VARCHAR(128), copyright VARCHAR(255), ccli_number VARCHAR(64))",
+            "CREATE TABLE authors (id INTEGER PRIMARY KEY, first_name VARCHAR(128), last_name VARCHAR(128), display_name VARCHAR(255) NOT NULL)",
+            "CREATE TABLE authors_songs (author_id INTEGER, song_id INTEGER, author_type VARCHAR(255))",
+            "CREATE TABLE song_books (id INTEGER PRIMARY KEY, name VARCHAR(128) NOT NULL, publisher VARCHAR(128))",
+            "CREATE TABLE songs_songbooks (songbook_id INTEGER, song_id INTEGER, entry VARCHAR(255))",
+            "CREATE TABLE media_files (id INTEGER PRIMARY KEY, song_id INTEGER, file_path TEXT, type VARCHAR(64), weight INTEGER)",
+            r#"INSERT INTO songs VALUES (1, 'Death Was Arrested', '<?xml version=''1.0'' encoding=''UTF-8''?>
+<song version="1.0"><lyrics><verse type="v" label="1"><![CDATA[Alone in my sorrow
+{r}And dead in my sin{/r}
+[---]
+Lost without hope]]></verse><verse type="c" label="1"><![CDATA[Oh, Your grace so free]]></verse><verse type="b" label="1"><![CDATA[Released from my chains]]></verse></lyrics></song>', 'v1 c1 b1 c1', 'Public Domain', '7016417')"#,
+            "INSERT INTO songs VALUES (2, 'Amazing Grace', '<song version=\"1.0\"><lyrics><verse type=\"v\" label=\"1\">Amazing grace</verse></lyrics></song>', '', NULL, NULL)",
+            "INSERT INTO songs VALUES (3, 'Broken', '<song><lyrics><verse type=\"v\">', NULL, NULL, NULL)",
+            "INSERT INTO authors VALUES (1, 'Paul', 'Taylor', 'Paul Taylor')",
+            "INSERT INTO authors VALUES (2, 'Adam', 'Kersh', 'Adam Kersh')",
+            "INSERT INTO authors_songs VALUES (1, 1, 'words')",
+            "INSERT INTO authors_songs VALUES (2, 1, 'music')",
+            "INSERT INTO song_books VALUES (1, 'Worship Together', NULL)",
+            "INSERT INTO songs_songbooks VALUES (1, 1, '42')",
+            r#"INSERT INTO media_files VALUES (1, 1, '{"parts": ["/", "tmp", "arrested.mp3"], "json_meta": {"class": "Path", "version": 1}}', 'audio', 0)"#,
+        ] {
+            query(statement).execute(&db).await.into_diagnostic()?;
+        }
+        db.close().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_openlp_import() -> Result<()> {
+        let path = std::env::temp_dir().join("lumina_openlp_test.sqlite");
+        openlp_db(&path).await?;
+
+        let existing = vec![Song {
+            title: "amazing grace!".into(),
+            ..Default::default()
+        }];
+        let import = import(&path, &existing).await?;
+        assert_eq!(import.skipped.len(), 2);
+        assert_eq!(
+            import.skipped[0],
+            Skipped::Duplicate {
+                title: "Amazing Grace".into(),
+                existing: "amazing grace!".into(),
+            }
+        );
+        assert!(matches!(
+            &import.skipped[1],
+            Skipped::Unreadable { title, .. } if title == "Broken"
+        ));
+        assert_eq!(import.songs.len(), 1);
+
+        let song = &import.songs[0];
+        assert_eq!(song.title, "Death Was Arrested");
+        assert_eq!(song.author.as_deref(), Some("Paul Taylor, Adam Kersh"));
+        assert_eq!(song.ccli.as_deref(), Some("7016417"));
+        assert_eq!(song.copyright.as_deref(), Some("Public Domain"));
+        assert_eq!(
+            song.songbooks,
+            vec![Songbook {
+                name: "Worship Together".into(),
+                entry: Some("42".into()),
+            }]
+        );
+        assert_eq!(song.audio, Some(PathBuf::from("/tmp/arrested.mp3")));
+        assert_eq!(
+            song.verses,
+            Some(vec![
+                VerseName::Verse { number: 1 },
+                VerseName::Chorus { number: 1 },
+                VerseName::Bridge { number: 1 },
+                VerseName::Chorus { number: 1 },
+            ])
+        );
+        assert_eq!(
+            song.get_lyric(&VerseName::Verse { number: 1 }).as_deref(),
+            Some("Alone in my sorrow\nAnd dead in my sin\n\nLost without hope")
+        );
+        let _ = std::fs::remove_file(&path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_clean_text() {
+        assert_eq!(
+            clean_text("{y}Hi{/y} there {not a tag}"),
+            "Hi there {not a tag}"
+        );
+        assert_eq!(clean_text("one\n[---]\ntwo"), "one\n\ntwo");
+    }
+}
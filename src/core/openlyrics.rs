@@ -0,0 +1,543 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as _;
+use std::fs::{self, File};
+use std::path::Path;
+
+use miette::{IntoDiagnostic, Result, miette};
+use roxmltree::{Document, Node};
+use tar::{Builder, Header};
+use tracing::error;
+
+use crate::core::songs::{Song, Songbook, VerseName};
+
+pub fn song_from_file(path: impl AsRef<Path>) -> Result<Song> {
+    let xml = fs::read_to_string(path).into_diagnostic()?;
+    song_from_str(&xml)
+}
+
+pub fn song_from_str(xml: &str) -> Result<Song> {
+    let document = Document::parse(xml).into_diagnostic()?;
+    let root = document.root_element();
+    if !root.has_tag_name("song") {
+        return Err(miette!("This isn't an OpenLyrics song"));
+    }
+
+    let properties = child(root, "properties");
+    let property = |name: &str| properties.and_then(|properties| child(properties, name));
+    let property_text = |name: &str| {
+        property(name)
+            .and_then(|node| node.text())
+            .map(str::trim)
+            .filter(|text| !text.is_empty())
+            .map(ToString::to_string)
+    };
+
+    let title = property("titles")
+        .and_then(|titles| child(titles, "title"))
+        .and_then(|title| title.text())
+        .map(str::trim)
+        .ok_or_else(|| miette!("This song doesn't have a title"))?
+        .to_string();
+
+    let authors = property("authors")
+        .map(|authors| {
+            authors
+                .children()
+                .filter(|node| node.has_tag_name("author"))
+                .filter_map(|author| author.text())
+                .map(str::trim)
+                .collect::<Vec<&str>>()
+                .join(", ")
+        })
+        .filter(|authors| !authors.is_empty());
+
+    let songbooks = property("songbooks")
+        .map(|songbooks| {
+            songbooks
+                .children()
+                .filter(|node| node.has_tag_name("songbook"))
+                .filter_map(|songbook| {
+                    Some(Songbook {
+                        name: songbook.attribute("name")?.trim().to_string(),
+                        entry: songbook
+                            .attribute("entry")
+                            .map(str::trim)
+                            .filter(|entry| !entry.is_empty())
+                            .map(ToString::to_string),
+                    })
+                })
+                .filter(|songbook| !songbook.name.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut names: HashMap<String, VerseName> = HashMap::new();
+    let mut document_order: Vec<VerseName> = vec![];
+    let mut verse_map: HashMap<VerseName, String> = HashMap::new();
+    if let Some(lyrics) = child(root, "lyrics") {
+        for verse in lyrics.children().filter(|node| node.has_tag_name("verse")) {
+            let raw_name = verse.attribute("name").unwrap_or("v1").to_lowercase();
+            let name = verse_name_from_openlyrics(&raw_name);
+            let text = verse
+                .children()
+                .filter(|node| node.has_tag_name("lines"))
+                .map(lines_text)
+                .collect::<Vec<String>>()
+                .join("\n\n");
+            names.insert(raw_name, name);
+            if !document_order.contains(&name) {
+                document_order.push(name);
+            }
+            verse_map
+                .entry(name)
+                .and_modify(|lyric| {
+                    lyric.push_str("\n\n");
+                    lyric.push_str(&text);
+                })
+                .or_insert(text);
+        }
+    }
+
+    let verses = verse_order(
+        property_text("verseOrder").as_deref(),
+        &names,
+        document_order,
+        &verse_map,
+    );
+
+    Ok(Song {
+        title,
+        author: authors,
+        ccli: property_text("ccliNo"),
+        copyright: property_text("copyright"),
+        songbooks,
+        lyrics: ron::ser::to_string(&Some(&verse_map)).ok(),
+        verse_order: Some(verses.iter().map(VerseName::get_name).collect()),
+        verses: Some(verses),
+        verse_map: Some(verse_map),
+        ..Default::default()
+    })
+}
+
+/// Maps the names in a verse order like `v1 c1 v2` onto the verses that
+/// were found, falling back to the order they showed up in the document.
+pub(crate) fn verse_order(
+    order: Option<&str>,
+    names: &HashMap<String, VerseName>,
+    document_order: Vec<VerseName>,
+    verse_map: &HashMap<VerseName, String>,
+) -> Vec<VerseName> {
+    let verses: Vec<VerseName> = order
+        .unwrap_or_default()
+        .split_whitespace()
+        .map(|name| {
+            let name = name.to_lowercase();
+            names
+                .get(&name)
+                .copied()
+                .unwrap_or_else(|| verse_name_from_openlyrics(&name))
+        })
+        .filter(|name| verse_map.contains_key(name))
+        .collect();
+    if verses.is_empty() {
+        document_order
+    } else {
+        verses
+    }
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|child| child.has_tag_name(name))
+}
+
+/// Lines hold text broken up by `<br/>` along with chords and comments,
+/// older versions of the format used a `<line>` element per line.
+fn lines_text(lines: Node) -> String {
+    fn push_text(node: Node, text: &mut String) {
+        for child in node.children() {
+            if child.is_text() {
+                let collapsed = child
+                    .text()
+                    .unwrap_or_default()
+                    .split_whitespace()
+                    .collect::<Vec<&str>>()
+                    .join(" ");
+                if child
+                    .text()
+                    .is_some_and(|t| t.starts_with(char::is_whitespace))
+                {
+                    text.push(' ');
+                }
+                text.push_str(&collapsed);
+                if child
+                    .text()
+                    .is_some_and(|t| t.ends_with(char::is_whitespace))
+                {
+                    text.push(' ');
+                }
+            } else if child.has_tag_name("br") {
+                text.push('\n');
+            } else if child.has_tag_name("comment") {
+                continue;
+            } else if child.has_tag_name("line") {
+                push_text(child, text);
+                text.push('\n');
+            } else {
+                push_text(child, text);
+            }
+        }
+    }
+
+    let mut text = String::new();
+    push_text(lines, &mut text);
+    text.lines()
+        .map(str::trim)
+        .collect::<Vec<&str>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+pub(crate) fn verse_name_from_openlyrics(name: &str) -> VerseName {
+    let name = name.to_lowercase();
+    let prefix: String = name
+        .chars()
+        .take_while(|c| c.is_alphabetic() || *c == '-')
+        .collect();
+    let number = name[prefix.len()..]
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect::<String>()
+        .parse()
+        .unwrap_or(1);
+    match prefix.as_str() {
+        "v" | "verse" => VerseName::Verse { number },
+        "p" | "pre" | "prechorus" | "pre-chorus" => VerseName::PreChorus { number },
+        "c" | "chorus" => VerseName::Chorus { number },
+        "postchorus" | "post-chorus" => VerseName::PostChorus { number },
+        "b" | "bridge" => VerseName::Bridge { number },
+        "i" | "intro" => VerseName::Intro { number },
+        "e" | "ending" | "outro" => VerseName::Outro { number },
+        "instrumental" => VerseName::Instrumental { number },
+        "blank" => VerseName::Blank,
+        _ => VerseName::Other { number },
+    }
+}
+
+/// OpenLyrics only has short names for the common parts of a song,
+/// the rest are spelled out so that they survive an import.
+pub(crate) fn verse_name_to_openlyrics(name: &VerseName) -> String {
+    match name {
+        VerseName::Verse { number } => format!("v{number}"),
+        VerseName::PreChorus { number } => format!("p{number}"),
+        VerseName::Chorus { number } => format!("c{number}"),
+        VerseName::PostChorus { number } => format!("postchorus{number}"),
+        VerseName::Bridge { number } => format!("b{number}"),
+        VerseName::Intro { number } => format!("i{number}"),
+        VerseName::Outro { number } => format!("e{number}"),
+        VerseName::Instrumental { number } => format!("instrumental{number}"),
+        VerseName::Other { number } => format!("o{number}"),
+        VerseName::Blank => "blank".into(),
+    }
+}
+
+pub(crate) fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+pub fn song_to_xml(song: &Song) -> Result<String> {
+    let Some(verse_map) = &song.verse_map else {
+        return Err(miette!("{} doesn't have any lyrics", song.title));
+    };
+    let order = song.verses.clone().unwrap_or_default();
+    let mut verses: Vec<VerseName> = vec![];
+    for name in &order {
+        if verse_map.contains_key(name) && !verses.contains(name) {
+            verses.push(*name);
+        }
+    }
+    let mut rest: Vec<VerseName> = verse_map
+        .keys()
+        .filter(|name| !verses.contains(name))
+        .copied()
+        .collect();
+    rest.sort();
+    verses.extend(rest);
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        "<song xmlns=\"http://openlyrics.info/namespace/2009/song\" version=\"0.9\" createdIn=\"Lumina {}\">",
+        env!("CARGO_PKG_VERSION")
+    );
+    xml.push_str("  <properties>\n");
+    let _ = writeln!(
+        xml,
+        "    <titles>\n      <title>{}</title>\n    </titles>",
+        escape(&song.title)
+    );
+    if let Some(author) = song.author.as_ref().filter(|author| !author.is_empty()) {
+        xml.push_str("    <authors>\n");
+        for author in author.split(',').map(str::trim).filter(|a| !a.is_empty()) {
+            let _ = writeln!(xml, "      <author>{}</author>", escape(author));
+        }
+        xml.push_str("    </authors>\n");
+    }
+    if let Some(copyright) = &song.copyright {
+        let _ = writeln!(xml, "    <copyright>{}</copyright>", escape(copyright));
+    }
+    if let Some(ccli) = &song.ccli {
+        let _ = writeln!(xml, "    <ccliNo>{}</ccliNo>", escape(ccli));
+    }
+    if !song.songbooks.is_empty() {
+        xml.push_str("    <songbooks>\n");
+        for songbook in &song.songbooks {
+            let entry = songbook
+                .entry
+                .as_ref()
+                .map(|entry| format!(" entry=\"{}\"", escape(entry)))
+                .unwrap_or_default();
+            let _ = writeln!(
+                xml,
+                "      <songbook name=\"{}\"{entry}/>",
+                escape(&songbook.name)
+            );
+        }
+        xml.push_str("    </songbooks>\n");
+    }
+    if !order.is_empty() {
+        let order = order
+            .iter()
+            .filter(|name| verse_map.contains_key(name))
+            .map(verse_name_to_openlyrics)
+            .collect::<Vec<String>>()
+            .join(" ");
+        let _ = writeln!(xml, "    <verseOrder>{order}</verseOrder>");
+    }
+    xml.push_str("  </properties>\n  <lyrics>\n");
+    for name in verses {
+        let _ = writeln!(
+            xml,
+            "    <verse name=\"{}\">",
+            verse_name_to_openlyrics(&name)
+        );
+        for lines in verse_map[&name].split("\n\n") {
+            let lines = lines
+                .trim()
+                .lines()
+                .map(|line| escape(line.trim()))
+                .collect::<Vec<String>>()
+                .join("<br/>");
+            let _ = writeln!(xml, "      <lines>{lines}</lines>");
+        }
+        xml.push_str("    </verse>\n");
+    }
+    xml.push_str("  </lyrics>\n</song>\n");
+    Ok(xml)
+}
+
+pub fn export_song(song: &Song, path: impl AsRef<Path>) -> Result<()> {
+    fs::write(path, song_to_xml(song)?).into_diagnostic()
+}
+
+/// Bundles every song into a tar with an xml file per song. Songs that
+/// can't be exported are logged and left out.
+pub fn export_songs(songs: &[Song], path: impl AsRef<Path>) -> Result<usize> {
+    let mut tar = Builder::new(File::create(path).into_diagnostic()?);
+    let mut names = HashSet::new();
+    let mut exported = 0;
+    for song in songs {
+        let xml = match song_to_xml(song) {
+            Ok(xml) => xml,
+            Err(e) => {
+                error!(?e, title = %song.title, "couldn't export song");
+                continue;
+            }
+        };
+        let title: String = song
+            .title
+            .chars()
+            .map(|c| {
+                if c.is_alphanumeric() || matches!(c, ' ' | '-' | '_') {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        let mut name = format!("{}.xml", title.trim());
+        if !names.insert(name.clone()) {
+            name = format!("{} ({}).xml", title.trim(), song.id);
+            names.insert(name.clone());
+        }
+        let mut header = Header::new_gnu();
+        header.set_size(xml.len() as u64);
+        header.set_mode(0o644);
+        tar.append_data(&mut header, name, xml.as_bytes())
+            .into_diagnostic()?;
+        exported += 1;
+    }
+    tar.finish().into_diagnostic()?;
+    Ok(exported)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const SONG: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<song xmlns="http://openlyrics.info/namespace/2009/song" version="0.9" createdIn="OpenLP 3.0">
+  <properties>
+    <titles>
+      <title>Amazing Grace</title>
+    </titles>
+    <authors>
+      <author>John Newton</author>
+      <author type="music">Traditional</author>
+    </authors>
+    <copyright>Public Domain</copyright>
+    <ccliNo>4755360</ccliNo>
+    <songbooks>
+      <songbook name="Hymns of Faith" entry="12"/>
+      <songbook name="Campfire Songs"/>
+    </songbooks>
+    <verseOrder>v1 c v2 c</verseOrder>
+  </properties>
+  <lyrics>
+    <verse name="v1">
+      <lines>Amazing grace how <chord name="D"/>sweet the sound<br/>That saved a wretch like me</lines>
+    </verse>
+    <verse name="c">
+      <lines>
+        <comment>all together</comment>
+        My chains are gone<br/>
+        I've been set free
+      </lines>
+      <lines>My God, my Savior<br/>has ransomed me</lines>
+    </verse>
+    <verse name="v2">
+      <lines>
+        <line>'Twas grace that taught</line>
+        <line>my heart to fear</line>
+      </lines>
+    </verse>
+  </lyrics>
+</song>"#;
+
+    #[test]
+    fn test_openlyrics_import() -> Result<()> {
+        let song = song_from_str(SONG)?;
+        assert_eq!(song.title, "Amazing Grace");
+        assert_eq!(song.author.as_deref(), Some("John Newton, Traditional"));
+        assert_eq!(song.copyright.as_deref(), Some("Public Domain"));
+        assert_eq!(song.ccli.as_deref(), Some("4755360"));
+        assert_eq!(
+            song.songbooks,
+            vec![
+                Songbook {
+                    name: "Hymns of Faith".into(),
+                    entry: Some("12".into()),
+                },
+                Songbook {
+                    name: "Campfire Songs".into(),
+                    entry: None,
+                },
+            ]
+        );
+        assert_eq!(
+            song.verses,
+            Some(vec![
+                VerseName::Verse { number: 1 },
+                VerseName::Chorus { number: 1 },
+                VerseName::Verse { number: 2 },
+                VerseName::Chorus { number: 1 },
+            ])
+        );
+        assert_eq!(
+            song.get_lyric(&VerseName::Verse { number: 1 }).as_deref(),
+            Some("Amazing grace how sweet the sound\nThat saved a wretch like me")
+        );
+        assert_eq!(
+            song.get_lyric(&VerseName::Chorus { number: 1 }).as_deref(),
+            Some(
+                "My chains are gone\nI've been set free\n\nMy God, my Savior\nhas ransomed me"
+            )
+        );
+        assert_eq!(
+            song.get_lyric(&VerseName::Verse { number: 2 }).as_deref(),
+            Some("'Twas grace that taught\nmy heart to fear")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_openlyrics_export() -> Result<()> {
+        let mut song = song_from_str(SONG)?;
+        song.title = "Grace & Truth".into();
+        let mut verse_map = song.verse_map.clone().unwrap_or_default();
+        verse_map.insert(VerseName::PreChorus { number: 1 }, "Oh <oh>".into());
+        verse_map.insert(VerseName::Instrumental { number: 2 }, "Guitar".into());
+        verse_map.insert(VerseName::Other { number: 1 }, "Tag".into());
+        verse_map.insert(VerseName::PostChorus { number: 1 }, "Whoa".into());
+        song.verse_map = Some(verse_map);
+
+        let xml = song_to_xml(&song)?;
+        assert!(xml.contains("<title>Grace &amp; Truth</title>"));
+        assert!(xml.contains("<verseOrder>v1 c1 v2 c1</verseOrder>"));
+        assert!(xml.contains("<lines>Oh &lt;oh&gt;</lines>"));
+
+        let imported = song_from_str(&xml)?;
+        assert_eq!(imported.title, song.title);
+        assert_eq!(imported.author, song.author);
+        assert_eq!(imported.ccli, song.ccli);
+        assert_eq!(imported.copyright, song.copyright);
+        assert_eq!(imported.songbooks, song.songbooks);
+        assert_eq!(imported.verses, song.verses);
+        assert_eq!(imported.verse_map, song.verse_map);
+        Ok(())
+    }
+
+    #[test]
+    fn test_verse_names() {
+        assert_eq!(
+            verse_name_from_openlyrics("v3"),
+            VerseName::Verse { number: 3 }
+        );
+        assert_eq!(
+            verse_name_from_openlyrics("c"),
+            VerseName::Chorus { number: 1 }
+        );
+        assert_eq!(
+            verse_name_from_openlyrics("e1"),
+            VerseName::Outro { number: 1 }
+        );
+        assert_eq!(
+            verse_name_from_openlyrics("o2"),
+            VerseName::Other { number: 2 }
+        );
+        for name in [
+            VerseName::PreChorus { number: 2 },
+            VerseName::PostChorus { number: 1 },
+            VerseName::Instrumental { number: 3 },
+            VerseName::Outro { number: 1 },
+            VerseName::Blank,
+        ] {
+            assert_eq!(
+                verse_name_from_openlyrics(&verse_name_to_openlyrics(&name)),
+                name
+            );
+        }
+    }
+}
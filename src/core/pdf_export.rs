@@ -0,0 +1,473 @@
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+
+use image::{DynamicImage, RgbImage};
+use miette::{IntoDiagnostic, Result, miette};
+use mupdf::pdf::{PdfDocument, PdfObject, PdfWriteOptions};
+use mupdf::{Colorspace, Font, Image, Pixmap, Size};
+use rayon::prelude::*;
+use resvg::usvg::fontdb;
+
+use crate::core::render;
+use crate::core::service_items::ServiceItem;
+use crate::core::slide::Slide;
+
+const SLIDE_PAGE_WIDTH: f32 = 792.0;
+const NOTES_PAGE_WIDTH: f32 = 612.0;
+const NOTES_PAGE_HEIGHT: f32 = 792.0;
+const NOTES_MARGIN: f32 = 54.0;
+const NOTES_FONT_SIZE: f32 = 12.0;
+const NOTES_TITLE_SIZE: f32 = 16.0;
+const NOTES_INDENT: f32 = 18.0;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PdfLayout {
+    /// One rendered slide per page
+    #[default]
+    Slides,
+    /// Only the titles, text and speaker notes of each item, for printing
+    Notes,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NotesLine {
+    Title,
+    Text,
+    Note,
+}
+
+/// The fonts tried for the notes, before falling back to any there is.
+const NOTES_FAMILIES: [fontdb::Family; 5] = [
+    fontdb::Family::Name("Noto Sans"),
+    fontdb::Family::Name("DejaVu Sans"),
+    fontdb::Family::Name("Liberation Sans"),
+    fontdb::Family::Name("Arial"),
+    fontdb::Family::SansSerif,
+];
+
+/// The faces the notes are written in. Titles and notes use the bold and
+/// italic faces of the same family when it has them, and the regular one
+/// when it doesn't.
+struct NotesFonts {
+    regular: Font,
+    bold: Option<Font>,
+    italic: Option<Font>,
+}
+
+impl NotesFonts {
+    fn load(fontdb: &fontdb::Database) -> Result<Self> {
+        let query = fontdb::Query {
+            families: &NOTES_FAMILIES,
+            weight: fontdb::Weight::NORMAL,
+            stretch: fontdb::Stretch::Normal,
+            style: fontdb::Style::Normal,
+        };
+        let (id, regular) = fontdb
+            .query(&query)
+            .into_iter()
+            .chain(fontdb.faces().map(|face| face.id))
+            .find_map(|id| Some((id, load_font(fontdb, id)?)))
+            .ok_or_else(|| miette!("There's no font to write the notes with"))?;
+
+        let Some((family, _)) = fontdb.face(id).and_then(|face| face.families.first())
+        else {
+            return Ok(Self {
+                regular,
+                bold: None,
+                italic: None,
+            });
+        };
+        let families = [fontdb::Family::Name(family.as_str())];
+        let bold = fontdb
+            .query(&fontdb::Query {
+                families: &families,
+                weight: fontdb::Weight::BOLD,
+                ..query
+            })
+            .filter(|id| {
+                fontdb
+                    .face(*id)
+                    .is_some_and(|face| face.weight.0 >= fontdb::Weight::SEMIBOLD.0)
+            })
+            .and_then(|id| load_font(fontdb, id));
+        let italic = fontdb
+            .query(&fontdb::Query {
+                families: &families,
+                style: fontdb::Style::Italic,
+                ..query
+            })
+            .filter(|id| {
+                fontdb.face(*id).is_some_and(|face| {
+                    face.style != fontdb::Style::Normal
+                        && face.weight.0 < fontdb::Weight::SEMIBOLD.0
+                })
+            })
+            .and_then(|id| load_font(fontdb, id));
+        Ok(Self {
+            regular,
+            bold,
+            italic,
+        })
+    }
+
+    /// The font a kind of line is written in, with its name in the page
+    /// resources.
+    fn font(&self, kind: NotesLine) -> (&Font, &'static str) {
+        let styled = match kind {
+            NotesLine::Title => self.bold.as_ref().map(|font| (font, "Bold")),
+            NotesLine::Note => self.italic.as_ref().map(|font| (font, "Italic")),
+            NotesLine::Text => None,
+        };
+        styled.unwrap_or((&self.regular, "Regular"))
+    }
+
+    /// Adds each font to the document, for the pages to share.
+    fn resources(&self, document: &mut PdfDocument) -> Result<PdfObject> {
+        let mut fonts = document.new_dict().into_diagnostic()?;
+        for (name, font) in [
+            ("Regular", Some(&self.regular)),
+            ("Bold", self.bold.as_ref()),
+            ("Italic", self.italic.as_ref()),
+        ] {
+            let Some(font) = font else {
+                continue;
+            };
+            let font = document.add_font(font).into_diagnostic()?;
+            fonts.dict_put(name, font).into_diagnostic()?;
+        }
+        let mut resources = document.new_dict().into_diagnostic()?;
+        resources.dict_put("Font", fonts).into_diagnostic()?;
+        document.add_object(&resources).into_diagnostic()
+    }
+}
+
+fn load_font(fontdb: &fontdb::Database, id: fontdb::ID) -> Option<Font> {
+    let name = fontdb.face(id)?.post_script_name.clone();
+    fontdb
+        .with_face_data(id, |data, index| {
+            Font::from_bytes_with_index(&name, i32::try_from(index).ok()?, data).ok()
+        })
+        .flatten()
+}
+
+fn glyph(font: &Font, c: char) -> i32 {
+    font.encode_character(c as i32).unwrap_or_default()
+}
+
+/// The width of the text in points at this font size.
+fn text_width(font: &Font, text: &str, size: f32) -> f32 {
+    let advance: f32 = text
+        .chars()
+        .filter_map(|c| font.advance_glyph(glyph(font, c)).ok())
+        .sum();
+    advance * size
+}
+
+/// Breaks a line up so none are wider than `width`, between words where
+/// it can and inside a word too long to fit by itself.
+fn wrap(font: &Font, line: &str, size: f32, width: f32) -> Vec<String> {
+    let mut lines = vec![];
+    let mut current = String::new();
+    for word in line.split(' ') {
+        let joined = if current.is_empty() {
+            word.to_string()
+        } else {
+            format!("{current} {word}")
+        };
+        if text_width(font, &joined, size) <= width {
+            current = joined;
+            continue;
+        }
+        if !current.is_empty() {
+            lines.push(std::mem::take(&mut current));
+        }
+        for c in word.chars() {
+            current.push(c);
+            if current.chars().count() > 1 && text_width(font, &current, size) > width {
+                current.pop();
+                lines.push(std::mem::replace(&mut current, c.to_string()));
+            }
+        }
+    }
+    lines.push(current);
+    lines
+}
+
+/// The text as a string of glyph ids, which is how mupdf encodes the fonts
+/// it embeds.
+fn glyph_ids(font: &Font, text: &str) -> String {
+    let glyphs: String = text
+        .chars()
+        .map(|c| format!("{:04X}", glyph(font, c)))
+        .collect();
+    format!("<{glyphs}>")
+}
+
+/// Adds a page drawn by `content` with the fonts and images in `resources`.
+fn add_page(
+    document: &mut PdfDocument,
+    size: Size,
+    resources: PdfObject,
+    content: &str,
+) -> Result<()> {
+    let page = document.new_page(size).into_diagnostic()?;
+    let contents = document.new_dict().into_diagnostic()?;
+    let mut contents = document.add_object(&contents).into_diagnostic()?;
+    contents.write_stream_string(content).into_diagnostic()?;
+    let mut object = page.object();
+    object.dict_put("Resources", resources).into_diagnostic()?;
+    object.dict_put("Contents", contents).into_diagnostic()
+}
+
+/// Copies a rendered slide into an image mupdf can add to the document.
+fn slide_image(image: &RgbImage) -> Result<Image> {
+    let mut pixmap = Pixmap::new_with_w_h(
+        &Colorspace::device_rgb(),
+        i32::try_from(image.width()).into_diagnostic()?,
+        i32::try_from(image.height()).into_diagnostic()?,
+        false,
+    )
+    .into_diagnostic()?;
+    let stride = usize::try_from(pixmap.stride()).into_diagnostic()?;
+    let row = image.width() as usize * 3;
+    for (target, source) in pixmap
+        .samples_mut()
+        .chunks_mut(stride)
+        .zip(image.as_raw().chunks(row))
+    {
+        target[..row].copy_from_slice(source);
+    }
+    Image::from_pixmap(&pixmap).into_diagnostic()
+}
+
+fn slide_items(items: &[ServiceItem]) -> Result<Vec<(&ServiceItem, Vec<Slide>)>> {
+    items
+        .iter()
+        .map(|item| {
+            let slides = if item.slides.is_empty() {
+                item.to_slides()?
+            } else {
+                item.slides.clone()
+            };
+            Ok((item, slides))
+        })
+        .collect()
+}
+
+pub fn export_pdf(
+    items: &[ServiceItem],
+    path: impl AsRef<Path>,
+    layout: PdfLayout,
+    width: u32,
+    height: u32,
+    fontdb: &Arc<fontdb::Database>,
+) -> Result<()> {
+    let mut document = PdfDocument::new();
+    match layout {
+        PdfLayout::Slides => {
+            slide_pages(&mut document, items, width, height, fontdb)?;
+        }
+        PdfLayout::Notes => notes_pages(&mut document, items, fontdb)?,
+    }
+
+    let mut options = PdfWriteOptions::default();
+    options
+        .set_compress(true)
+        .set_compress_images(true)
+        .set_compress_fonts(true)
+        .set_garbage(true);
+    let mut file = File::create(path).into_diagnostic()?;
+    document
+        .write_to_with_options(&mut file, options)
+        .into_diagnostic()?;
+    Ok(())
+}
+
+fn slide_pages(
+    document: &mut PdfDocument,
+    items: &[ServiceItem],
+    width: u32,
+    height: u32,
+    fontdb: &Arc<fontdb::Database>,
+) -> Result<()> {
+    let slides = slide_items(items)?
+        .into_iter()
+        .flat_map(|(_item, slides)| slides)
+        .collect::<Vec<Slide>>();
+
+    #[allow(clippy::cast_precision_loss)]
+    let page_height = SLIDE_PAGE_WIDTH * height as f32 / width as f32;
+    let content = format!("q {SLIDE_PAGE_WIDTH} 0 0 {page_height} 0 0 cm /Slide Do Q");
+
+    // Rendered a few at a time so a long service isn't all held in memory.
+    for slides in slides.chunks(rayon::current_num_threads()) {
+        let images = slides
+            .par_iter()
+            .map(|slide| {
+                let image = render::render_slide(slide, width, height, fontdb)?;
+                Ok(DynamicImage::from(image).to_rgb8())
+            })
+            .collect::<Result<Vec<RgbImage>>>()?;
+
+        for image in images {
+            let image = document
+                .add_image(&slide_image(&image)?)
+                .into_diagnostic()?;
+            let mut images = document.new_dict().into_diagnostic()?;
+            images.dict_put("Slide", image).into_diagnostic()?;
+            let mut resources = document.new_dict().into_diagnostic()?;
+            resources.dict_put("XObject", images).into_diagnostic()?;
+            add_page(
+                document,
+                Size::new(SLIDE_PAGE_WIDTH, page_height),
+                resources,
+                &content,
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn notes_pages(
+    document: &mut PdfDocument,
+    items: &[ServiceItem],
+    fontdb: &fontdb::Database,
+) -> Result<()> {
+    let fonts = NotesFonts::load(fontdb)?;
+    let resources = fonts.resources(document)?;
+    let page = Size::new(NOTES_PAGE_WIDTH, NOTES_PAGE_HEIGHT);
+
+    let mut lines: Vec<(NotesLine, String)> = vec![];
+    for (item, slides) in slide_items(items)? {
+        lines.push((NotesLine::Title, item.title.clone()));
+        if !item.notes.trim().is_empty() {
+            lines.extend(
+                item.notes
+                    .lines()
+                    .map(|line| (NotesLine::Note, line.to_string())),
+            );
+            lines.push((NotesLine::Text, String::new()));
+        }
+        for slide in slides {
+            let text = slide.text();
+            let notes = slide.notes();
+            if text.trim().is_empty() && notes.trim().is_empty() {
+                continue;
+            }
+            lines.extend(text.lines().map(|line| (NotesLine::Text, line.to_string())));
+            lines.extend(
+                notes
+                    .lines()
+                    .map(|line| (NotesLine::Note, line.to_string())),
+            );
+            lines.push((NotesLine::Text, String::new()));
+        }
+        lines.push((NotesLine::Text, String::new()));
+    }
+
+    let mut pages = 0;
+    let mut content = String::new();
+    let mut y = NOTES_PAGE_HEIGHT - NOTES_MARGIN;
+
+    for (kind, line) in lines {
+        let (font, name) = fonts.font(kind);
+        let (size, x) = match kind {
+            NotesLine::Title => (NOTES_TITLE_SIZE, NOTES_MARGIN),
+            NotesLine::Text => (NOTES_FONT_SIZE, NOTES_MARGIN),
+            NotesLine::Note => (NOTES_FONT_SIZE, NOTES_MARGIN + NOTES_INDENT),
+        };
+        let width = NOTES_PAGE_WIDTH - NOTES_MARGIN - x;
+        for line in wrap(font, &line, size, width) {
+            if y - size < NOTES_MARGIN {
+                let resources = resources.try_clone().into_diagnostic()?;
+                add_page(document, page, resources, &std::mem::take(&mut content))?;
+                pages += 1;
+                y = NOTES_PAGE_HEIGHT - NOTES_MARGIN;
+            }
+            y -= size * 1.4;
+            if line.is_empty() {
+                continue;
+            }
+            let text = glyph_ids(font, &line);
+            content.push_str(&format!("BT /{name} {size} Tf {x} {y} Td {text} Tj ET\n"));
+        }
+    }
+    if !content.is_empty() || pages == 0 {
+        add_page(document, page, resources, &content)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::core::kinds::ServiceItemKind;
+    use mupdf::{Document, TextPageFlags};
+
+    fn system_fonts() -> Arc<fontdb::Database> {
+        let mut fontdb = fontdb::Database::new();
+        fontdb.load_system_fonts();
+        Arc::new(fontdb)
+    }
+
+    #[test]
+    fn test_wrap() -> Result<()> {
+        let fonts = NotesFonts::load(&system_fonts())?;
+        let font = &fonts.regular;
+        let line = "Amazing grace how sweet the sound that saved a wretch like me";
+        let width = text_width(font, line, 12.0) / 2.0;
+        let lines = wrap(font, line, 12.0, width);
+        assert!(lines.len() > 1);
+        assert_eq!(lines.join(" "), line);
+        assert!(
+            lines
+                .iter()
+                .all(|line| text_width(font, line, 12.0) <= width)
+        );
+
+        let word = "Hallelujah".repeat(10);
+        let lines = wrap(font, &word, 12.0, width);
+        assert!(lines.len() > 1);
+        assert_eq!(lines.concat(), word);
+        assert_eq!(wrap(font, "", 12.0, width), vec![String::new()]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_notes_export() -> Result<()> {
+        let slide = Slide::default()
+            .set_text("Amazing grace\nHow sweet the sound")
+            .set_notes("Slow down on the last line");
+        let items = vec![ServiceItem {
+            title: "Amazing Grace".into(),
+            kind: ServiceItemKind::Content(slide.clone()),
+            slides: vec![slide],
+            notes: "Everyone stands \u{2014} \u{4e2d}\u{6587} too".into(),
+            ..Default::default()
+        }];
+        let path = std::env::temp_dir().join("lumina_notes_test.pdf");
+        export_pdf(&items, &path, PdfLayout::Notes, 1920, 1080, &system_fonts())?;
+
+        let document =
+            Document::open(path.to_str().unwrap_or_default()).into_diagnostic()?;
+        assert!(document.is_pdf());
+        assert_eq!(document.page_count().into_diagnostic()?, 1);
+        let text = document
+            .load_page(0)
+            .into_diagnostic()?
+            .to_text_page(TextPageFlags::empty())
+            .into_diagnostic()?
+            .to_text()
+            .into_diagnostic()?;
+        for line in [
+            "Amazing Grace",
+            "How sweet the sound",
+            "Everyone stands \u{2014}",
+            "Slow down on the last line",
+        ] {
+            assert!(text.contains(line), "{line} is missing from {text}");
+        }
+        Ok(())
+    }
+}
@@ -1,5 +1,5 @@
 use cosmic::widget::image::Handle;
-use crisp::types::{Keyword, Symbol, Value};
+use crisp::types::{Keyword, Value};
 use itertools::Itertools;
 use miette::{IntoDiagnostic, Result, miette};
 use mupdf::{Colorspace, Document, Matrix};
@@ -14,6 +14,7 @@ use std::sync::Arc;
 use tracing::{debug, error};
 
 use crate::core::model::{Sort, SortDirection};
+use crate::lisp::{self, Form};
 use crate::{Background, Slide, SlideBuilder, TextAlignment};
 
 use super::content::Content;
@@ -38,6 +39,9 @@ pub struct Presentation {
     pub title: String,
     pub path: PathBuf,
     pub kind: PresKind,
+    /// Notes that service items of the presentation start out with
+    #[serde(default)]
+    pub notes: Option<String>,
     #[serde(skip)]
     pub created_at: DateTime<Local>,
     #[serde(skip)]
@@ -52,6 +56,7 @@ impl PartialEq for Presentation {
             && self.title == other.title
             && self.path == other.path
             && self.kind == other.kind
+            && self.notes == other.notes
     }
 }
 
@@ -95,6 +100,7 @@ impl From<PathBuf> for Presentation {
             title,
             path: value.canonicalize().unwrap_or(value),
             kind,
+            notes: None,
             created_at: Local::now(),
             accessed_at: Local::now(),
         }
@@ -108,8 +114,30 @@ impl From<&Path> for Presentation {
 }
 
 impl From<&Presentation> for Value {
-    fn from(_value: &Presentation) -> Self {
-        Self::List(vec![Self::Symbol(Symbol("presentation".into()))])
+    fn from(value: &Presentation) -> Self {
+        value.lisp_form().into()
+    }
+}
+
+impl Presentation {
+    #[must_use]
+    pub fn lisp_form(&self) -> Form {
+        let mut form = Form::new("presentation")
+            .string("source", self.path.display().to_string())
+            .string("title", &self.title);
+        if self.id != 0 {
+            form = form.atom("id", self.id);
+        }
+        if let PresKind::Pdf {
+            starting_index,
+            ending_index,
+        } = self.kind
+        {
+            form = form
+                .atom("start-page", starting_index)
+                .atom("end-page", ending_index);
+        }
+        form
     }
 }
 
@@ -158,17 +186,32 @@ impl From<&Value> for Presentation {
                     .position(|v| v == &Value::Keyword(Keyword::from("source")))
                 {
                     let pos = path_pos + 1;
-                    list.get(pos).map(|p| PathBuf::from(String::from(p)))
+                    list.get(pos).map(|p| PathBuf::from(lisp::string(p)))
                 } else {
                     None
                 };
 
-                let title = path
-                    .clone()
-                    .map(|p| p.to_str().unwrap_or_default().to_string());
+                let title =
+                    lisp::keyword(list, "title").map(lisp::string).or_else(|| {
+                        path.clone()
+                            .map(|p| p.to_str().unwrap_or_default().to_string())
+                    });
+                let path = path.unwrap_or_default();
+                let kind = match (
+                    lisp::keyword(list, "start-page"),
+                    lisp::keyword(list, "end-page"),
+                ) {
+                    (Some(start), Some(end)) => PresKind::Pdf {
+                        starting_index: i32::from(start),
+                        ending_index: i32::from(end),
+                    },
+                    _ => Self::from(path.clone()).kind,
+                };
                 Self {
+                    id: lisp::keyword(list, "id").map(i32::from).unwrap_or_default(),
                     title: title.unwrap_or_default(),
-                    path: path.unwrap_or_default(),
+                    path,
+                    kind,
                     ..Default::default()
                 }
             }
@@ -288,6 +331,7 @@ impl FromRow<'_, SqliteRow> for Presentation {
                     ending_index: row.try_get(5)?,
                 }
             },
+            notes: row.try_get("notes").ok().flatten(),
             created_at: Local::now(),
             accessed_at: Local::now(),
         })
@@ -307,7 +351,7 @@ impl Model<Presentation> {
 
     pub async fn load_from_db(&mut self, db: Arc<SqlitePool>) {
         let result = query!(
-            r#"SELECT id as "id: i32", title, file_path as "path", html, starting_index, ending_index, accessed_at as "accessed_at!: DateTime<Local>", created_at as "created_at!: DateTime<Local>" from presentations"#
+            r#"SELECT id as "id: i32", title, file_path as "path", html, starting_index, ending_index, notes, accessed_at as "accessed_at!: DateTime<Local>", created_at as "created_at!: DateTime<Local>" from presentations"#
         )
             .fetch_all(&*db)
             .await;
@@ -352,6 +396,7 @@ impl Model<Presentation> {
                                 },
                             )
                         },
+                        notes: presentation.notes,
                         created_at: presentation.created_at,
                         accessed_at: presentation.accessed_at,
                     });
@@ -460,12 +505,13 @@ pub async fn add_presentation(
             (0, 0)
         };
         query!(
-            r#"INSERT INTO presentations (title, file_path, html, starting_index, ending_index) VALUES ($1, $2, $3, $4, $5)"#,
+            r#"INSERT INTO presentations (title, file_path, html, starting_index, ending_index, notes) VALUES ($1, $2, $3, $4, $5, $6)"#,
             presentation.title,
             path,
             html,
             starting_index,
-            ending_index
+            ending_index,
+            presentation.notes
         )
             .execute(&*db)
             .await
@@ -499,13 +545,14 @@ pub async fn update_presentation(
     debug!(starting_index, ending_index);
 
     query!(
-        r#"UPDATE presentations SET title = $2, file_path = $3, html = $4, starting_index = $5, ending_index = $6 WHERE id = $1"#,
+        r#"UPDATE presentations SET title = $2, file_path = $3, html = $4, starting_index = $5, ending_index = $6, notes = $7 WHERE id = $1"#,
         presentation.id,
         presentation.title,
         path,
         html,
         starting_index,
-        ending_index
+        ending_index,
+        presentation.notes
     )
         .execute(&*db)
         .await.into_diagnostic()?;
@@ -528,7 +575,7 @@ pub async fn get_presentation_from_db(
     database_id: i32,
     db: &mut SqliteConnection,
 ) -> Result<Presentation> {
-    let row = query(r#"SELECT id as "id: i32", title, file_path as "path", html, accessed_at as "accessed_at!: DateTime<Local>", created_at as "created_at!: DateTime<Local>" from presentations where id = $1"#).bind(database_id).fetch_one(db).await.into_diagnostic()?;
+    let row = query(r#"SELECT id as "id: i32", title, file_path as "path", html, notes, accessed_at as "accessed_at!: DateTime<Local>", created_at as "created_at!: DateTime<Local>" from presentations where id = $1"#).bind(database_id).fetch_one(db).await.into_diagnostic()?;
     Presentation::from_row(&row).into_diagnostic()
 }
 
@@ -546,6 +593,7 @@ mod test {
                 starting_index: 0,
                 ending_index: 67,
             },
+            notes: None,
             created_at: Local::now(),
             accessed_at: Local::now(),
         }
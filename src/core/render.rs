@@ -0,0 +1,213 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use cosmic::iced::Size;
+use image::imageops::{self, FilterType};
+use image::{Rgba, RgbaImage};
+use miette::{IntoDiagnostic, Result, miette};
+use mupdf::{Colorspace, Document, Matrix};
+use rayon::prelude::*;
+use resvg::usvg::fontdb;
+use tracing::debug;
+
+use crate::core::service_items::ServiceItem;
+use crate::core::slide::{BackgroundFit, BackgroundKind, Slide};
+use crate::core::thumbnail;
+use crate::ui::text_svg;
+
+pub fn render_slide(
+    slide: &Slide,
+    width: u32,
+    height: u32,
+    fontdb: &Arc<fontdb::Database>,
+) -> Result<RgbaImage> {
+    let mut canvas = RgbaImage::from_pixel(width, height, Rgba([0, 0, 0, 255]));
+
+    if let Some(background) = render_background(slide, width, height)? {
+        overlay_fit(&mut canvas, &background, slide.background().fit);
+    }
+
+    if !slide.text().is_empty() {
+        let Some(mut cache) = dirs::cache_dir() else {
+            return Err(miette!("Cannot find the cache dir"));
+        };
+        cache.push("lumina");
+        cache.push("render_cache");
+        fs::create_dir_all(&cache).into_diagnostic()?;
+
+        #[allow(clippy::cast_precision_loss)]
+        let size = Size::new(width as f32, height as f32);
+        let slide = text_svg::text_svg_generator_with_size(
+            slide.clone(),
+            fontdb,
+            size,
+            Some(cache),
+        )?;
+        let Some(path) = slide.text_svg.and_then(|text_svg| text_svg.path) else {
+            return Err(miette!("The slide text didn't render"));
+        };
+        let text = image::open(path).into_diagnostic()?.to_rgba8();
+        imageops::overlay(&mut canvas, &text, 0, 0);
+    }
+
+    Ok(canvas)
+}
+
+fn render_background(
+    slide: &Slide,
+    width: u32,
+    height: u32,
+) -> Result<Option<RgbaImage>> {
+    let background = slide.background();
+    if background.path.as_os_str().is_empty() {
+        return Ok(None);
+    }
+    match background.kind {
+        BackgroundKind::Image => Ok(Some(
+            image::open(&background.path).into_diagnostic()?.to_rgba8(),
+        )),
+        BackgroundKind::Video => {
+            let screenshot = thumbnail::bg_path_from_video(&background.path);
+            thumbnail::bg_from_video(&background.path, &screenshot)
+                .map_err(|e| miette!("Couldn't create a video thumbnail: {e}"))?;
+            Ok(Some(image::open(screenshot).into_diagnostic()?.to_rgba8()))
+        }
+        BackgroundKind::Pdf => {
+            pdf_page(&background.path, slide.pdf_index(), width, height).map(Some)
+        }
+        BackgroundKind::Html => Ok(None),
+    }
+}
+
+/// Rasterizes the page big enough to cover `width`x`height`, so it only
+/// ever gets scaled down onto the canvas.
+pub fn pdf_page(path: &Path, index: u32, width: u32, height: u32) -> Result<RgbaImage> {
+    let document = Document::open(path.to_str().unwrap_or_default()).into_diagnostic()?;
+    let page = document
+        .load_page(i32::try_from(index).into_diagnostic()?)
+        .into_diagnostic()?;
+    let bounds = page.bounds().into_diagnostic()?;
+    let (page_width, page_height) = (bounds.x1 - bounds.x0, bounds.y1 - bounds.y0);
+    #[allow(clippy::cast_precision_loss)]
+    let scale = if page_width > 0.0 && page_height > 0.0 {
+        (width as f32 / page_width).max(height as f32 / page_height)
+    } else {
+        1.0
+    };
+    let pixmap = page
+        .to_pixmap(
+            &Matrix::new_scale(scale, scale),
+            &Colorspace::device_rgb(),
+            true,
+            true,
+        )
+        .into_diagnostic()?;
+    RgbaImage::from_raw(pixmap.width(), pixmap.height(), pixmap.samples().to_vec())
+        .ok_or_else(|| miette!("The pdf page didn't have the right amount of pixels"))
+}
+
+/// Scales the background onto the canvas the way its fit asks, contained
+/// when it doesn't say.
+fn overlay_fit(canvas: &mut RgbaImage, image: &RgbaImage, fit: Option<BackgroundFit>) {
+    let (width, height) = canvas.dimensions();
+    let (image_width, image_height) = image.dimensions();
+    if image_width == 0 || image_height == 0 {
+        return;
+    }
+    let width_scale = f64::from(width) / f64::from(image_width);
+    let height_scale = f64::from(height) / f64::from(image_height);
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let (new_width, new_height) = match fit {
+        Some(BackgroundFit::Fill) => (width, height),
+        Some(BackgroundFit::Cover) => {
+            let scale = width_scale.max(height_scale);
+            (
+                (f64::from(image_width) * scale).round() as u32,
+                (f64::from(image_height) * scale).round() as u32,
+            )
+        }
+        Some(BackgroundFit::Contain) | None => {
+            let scale = width_scale.min(height_scale);
+            (
+                (f64::from(image_width) * scale).round() as u32,
+                (f64::from(image_height) * scale).round() as u32,
+            )
+        }
+    };
+    let resized = imageops::resize(image, new_width, new_height, FilterType::Triangle);
+    imageops::overlay(
+        canvas,
+        &resized,
+        (i64::from(width) - i64::from(new_width)) / 2,
+        (i64::from(height) - i64::from(new_height)) / 2,
+    );
+}
+
+pub fn render_service(
+    items: &[ServiceItem],
+    directory: impl AsRef<Path>,
+    width: u32,
+    height: u32,
+    fontdb: &Arc<fontdb::Database>,
+) -> Result<Vec<PathBuf>> {
+    let directory = directory.as_ref();
+    fs::create_dir_all(directory).into_diagnostic()?;
+
+    let slides = items
+        .iter()
+        .enumerate()
+        .map(|(item_index, item)| {
+            let slides = if item.slides.is_empty() {
+                item.to_slides()?
+            } else {
+                item.slides.clone()
+            };
+            Ok(slides
+                .into_iter()
+                .enumerate()
+                .map(move |(slide_index, slide)| (item_index, slide_index, slide)))
+        })
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+
+    slides
+        .into_par_iter()
+        .map(|(item_index, slide_index, slide)| {
+            let name = format!("{:03}-{:03}.png", item_index + 1, slide_index + 1);
+            let path = directory.join(name);
+            debug!(?path, "rendering slide");
+            render_slide(&slide, width, height, fontdb)?
+                .save(&path)
+                .into_diagnostic()?;
+            Ok(path)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_render_slide() -> Result<()> {
+        let mut fontdb = fontdb::Database::new();
+        fontdb.load_system_fonts();
+        let fontdb = Arc::new(fontdb);
+        let slide = Slide::default()
+            .set_font("Quicksand")
+            .set_font_size(80)
+            .set_text("This is a rendered slide");
+        let image = render_slide(&slide, 640, 360, &fontdb)?;
+        assert_eq!(image.dimensions(), (640, 360));
+
+        let mut canvas = RgbaImage::from_pixel(640, 360, Rgba([0, 0, 0, 255]));
+        let square = RgbaImage::from_pixel(100, 100, Rgba([255, 255, 255, 255]));
+        overlay_fit(&mut canvas, &square, None);
+        assert_eq!(canvas.get_pixel(0, 0), &Rgba([0, 0, 0, 255]));
+        assert_eq!(canvas.get_pixel(320, 180), &Rgba([255, 255, 255, 255]));
+        Ok(())
+    }
+}
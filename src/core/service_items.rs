@@ -6,15 +6,18 @@ use std::path::PathBuf;
 use cosmic::iced::clipboard::mime::{AllowedMimeTypes, AsMimeTypes};
 use crisp::types::{Keyword, Symbol, Value};
 use miette::{IntoDiagnostic, Result, miette};
+use resvg::usvg::fontdb;
 use serde::{Deserialize, Serialize};
 use tracing::{debug, error};
 
 use crate::Slide;
-use crate::core::animation::Animation;
+use crate::core::animation::{Animation, lisp_to_animation};
+use crate::core::slide::{actions_lisp, lisp_to_actions, lisp_to_notes};
+use crate::lisp::{self, Form};
 
 use super::images::Image;
 use super::presentations::Presentation;
-use super::songs::{Song, lisp_to_song};
+use super::songs::{CreditDefaults, Song, lisp_to_song};
 use super::videos::Video;
 
 use super::kinds::ServiceItemKind;
@@ -27,6 +30,8 @@ pub struct ServiceItem {
     pub kind: ServiceItemKind,
     pub slides: Vec<Slide>,
     pub animation: Option<Animation>,
+    #[serde(default)]
+    pub notes: String,
     // pub item: Box<dyn ServiceTrait>,
 }
 
@@ -97,21 +102,97 @@ impl TryFrom<PathBuf> for ServiceItem {
 
 impl From<&ServiceItem> for Value {
     fn from(value: &ServiceItem) -> Self {
-        match &value.kind {
-            ServiceItemKind::Song(song) => Self::from(song),
-            ServiceItemKind::Video(video) => Self::from(video),
-            ServiceItemKind::Image(image) => Self::from(image),
-            ServiceItemKind::Presentation(presentation) => Self::from(presentation),
-            ServiceItemKind::Content(slide) => Self::from(slide),
-        }
+        value.lisp_form().into()
     }
 }
 
 impl ServiceItem {
+    /// Content items are written as the slide they show, the rest are
+    /// written as a slide with their library item as the background, or
+    /// as the song. Actions on slides that are made from the library item
+    /// are kept by the index of their slide.
+    #[must_use]
+    pub fn lisp_form(&self) -> Form {
+        let name = match &self.kind {
+            ServiceItemKind::Song(_) => "song",
+            _ => "slide",
+        };
+        let mut form = Form::new(name);
+        if let ServiceItemKind::Content(slide) = &self.kind {
+            if self.title != slide.text() {
+                form = form.string("title", &self.title);
+            }
+            if !self.notes.is_empty() {
+                form = form.string("item-notes", &self.notes);
+            }
+        } else {
+            // A song writes the item's notes as its own
+            if !self.notes.is_empty() && !matches!(self.kind, ServiceItemKind::Song(_)) {
+                form = form.string("notes", &self.notes);
+            }
+            let slide_actions = self
+                .slides
+                .iter()
+                .enumerate()
+                .filter(|(_, slide)| !slide.actions().is_empty())
+                .map(|(index, slide)| {
+                    format!("({index} {})", actions_lisp(slide.actions()))
+                })
+                .collect::<Vec<String>>();
+            if !slide_actions.is_empty() {
+                form =
+                    form.atom("slide-actions", format!("({})", slide_actions.join(" ")));
+            }
+        }
+        if let Some(animation) = &self.animation
+            && !matches!(self.kind, ServiceItemKind::Song(_))
+        {
+            form = form.atom("animation", animation.lisp_form());
+        }
+        match &self.kind {
+            ServiceItemKind::Song(song) => Song {
+                animation: self.animation.clone(),
+                notes: (!self.notes.is_empty()).then(|| self.notes.clone()),
+                ..song.clone()
+            }
+            .write_lisp(form),
+            ServiceItemKind::Video(video) => form.atom("background", video.lisp_form()),
+            ServiceItemKind::Image(image) => form.atom("background", image.lisp_form()),
+            ServiceItemKind::Presentation(presentation) => {
+                form.atom("background", presentation.lisp_form())
+            }
+            ServiceItemKind::Content(slide) => slide.write_lisp(form),
+        }
+    }
+
     pub fn title(&self) -> String {
         self.title.clone()
     }
 
+    /// Builds a song's slides again with the credits from the settings,
+    /// fitting its lyrics with the given fonts. The other kinds keep the
+    /// slides they have.
+    #[must_use]
+    pub fn with_credits(
+        mut self,
+        defaults: &CreditDefaults,
+        fontdb: &fontdb::Database,
+    ) -> Self {
+        if let ServiceItemKind::Song(song) = &self.kind {
+            match song.slides(defaults, fontdb) {
+                Ok(mut slides) => {
+                    for (slide, old) in slides.iter_mut().zip(&self.slides) {
+                        *slide =
+                            std::mem::take(slide).set_actions(old.actions().to_vec());
+                    }
+                    self.slides = slides;
+                }
+                Err(e) => error!(?e, "Can't build the song's slides"),
+            }
+        }
+        self
+    }
+
     pub fn to_slides(&self) -> Result<Vec<Slide>> {
         match &self.kind {
             ServiceItemKind::Song(song) => song.to_slides(),
@@ -132,6 +213,7 @@ impl Default for ServiceItem {
             kind: ServiceItemKind::Content(Slide::default()),
             slides: vec![],
             animation: None,
+            notes: String::new(),
             // item: Box::new(Image::default()),
         }
     }
@@ -147,7 +229,7 @@ impl From<Value> for ServiceItem {
 #[allow(clippy::match_like_matches_macro)]
 impl From<&Value> for ServiceItem {
     fn from(value: &Value) -> Self {
-        match value {
+        let mut item = match value {
             Value::List(list) => match &list[0] {
                 Value::Symbol(Symbol(s)) if s == "slide" => {
                     let background_pos = list
@@ -171,7 +253,8 @@ impl From<&Value> for ServiceItem {
                         _ => false,
                     }) {
                         let slide = Slide::from(value);
-                        let title = slide.text();
+                        let title = lisp::keyword(list, "title")
+                            .map_or_else(|| slide.text(), lisp::string);
                         Self {
                             id: 0,
                             title,
@@ -179,6 +262,9 @@ impl From<&Value> for ServiceItem {
                             kind: ServiceItemKind::Content(slide.clone()),
                             slides: vec![slide],
                             animation: None,
+                            notes: lisp::keyword(list, "item-notes")
+                                .map(lisp::string)
+                                .unwrap_or_default(),
                         }
                     } else if let Some(background) = list.get(background_pos) {
                         if let Value::List(item) = background {
@@ -210,7 +296,32 @@ impl From<&Value> for ServiceItem {
                 _ => Self::default(),
             },
             _ => Self::default(),
+        };
+        let Value::List(list) = value else {
+            return item;
+        };
+        if !matches!(item.kind, ServiceItemKind::Content(_))
+            && let Some(notes) = lisp_to_notes(list)
+        {
+            item.notes = notes;
+        }
+        if let Some(animation) =
+            lisp::keyword(list, "animation").and_then(lisp_to_animation)
+        {
+            item.animation = Some(animation);
         }
+        if let Some(Value::List(slide_actions)) = lisp::keyword(list, "slide-actions") {
+            for slide_action in slide_actions {
+                if let Value::List(slide_action) = slide_action
+                    && let [index, actions] = slide_action.as_slice()
+                    && let Ok(index) = usize::try_from(i32::from(index))
+                    && let Some(slide) = item.slides.get_mut(index)
+                {
+                    *slide = std::mem::take(slide).set_actions(lisp_to_actions(actions));
+                }
+            }
+        }
+        item
     }
 }
 
@@ -249,6 +360,7 @@ impl From<&Song> for ServiceItem {
                 database_id: song.id,
                 title: song.title.clone(),
                 animation: song.animation.clone(),
+                notes: song.notes.clone().unwrap_or_default(),
                 ..Default::default()
             },
             |slides| Self {
@@ -256,6 +368,7 @@ impl From<&Song> for ServiceItem {
                 database_id: song.id,
                 title: song.title.clone(),
                 animation: song.animation.clone(),
+                notes: song.notes.clone().unwrap_or_default(),
                 slides,
                 ..Default::default()
             },
@@ -310,6 +423,7 @@ impl From<&Presentation> for ServiceItem {
                 kind: ServiceItemKind::Presentation(presentation.clone()),
                 database_id: presentation.id,
                 title: presentation.title.clone(),
+                notes: presentation.notes.clone().unwrap_or_default(),
                 slides,
                 ..Default::default()
             },
@@ -319,6 +433,7 @@ impl From<&Presentation> for ServiceItem {
                     kind: ServiceItemKind::Presentation(presentation.clone()),
                     database_id: presentation.id,
                     title: presentation.title.clone(),
+                    notes: presentation.notes.clone().unwrap_or_default(),
                     ..Default::default()
                 }
             }
@@ -397,6 +512,7 @@ mod test {
                 "~/docs/notes/lessons/20240327T133649--12-isaiah-and-jesus__lesson_project_tfc.html",
             ),
             kind: PresKind::Html,
+            notes: None,
             created_at: Local::now(),
             accessed_at: Local::now(),
         }
@@ -414,4 +530,32 @@ mod test {
         assert_eq!(ServiceItemKind::Presentation(pres), pres_item.kind);
         assert_eq!(service_item, service_model.items[0]);
     }
+
+    #[test]
+    fn test_notes() {
+        let lisp = crisp::reader::read(
+            r#"(slide :notes "Tell the story first" (text "Grace"))"#,
+        );
+        let item = ServiceItem::from(&lisp);
+        assert_eq!(item.slides[0].notes(), "Tell the story first");
+        assert_eq!(item.notes, "");
+
+        let lisp = crisp::reader::read(
+            r#"(song :title "Death Was Arrested" :notes "Capo 2" (v1 "Alone in my sorrow"))"#,
+        );
+        let item = ServiceItem::from(&lisp);
+        assert_eq!(item.notes, "Capo 2");
+
+        let item: ServiceItem =
+            ron::from_str(&ron::to_string(&item).expect("Should serialize"))
+                .expect("Should deserialize");
+        assert_eq!(item.notes, "Capo 2");
+
+        let mut song = test_song();
+        song.notes = Some("Key of G".into());
+        assert_eq!(ServiceItem::from(&song).notes, "Key of G");
+        let mut pres = test_presentation();
+        pres.notes = Some("Skip the last page".into());
+        assert_eq!(ServiceItem::from(&pres).notes, "Skip the last page");
+    }
 }
@@ -7,7 +7,11 @@ use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 use std::path::PathBuf;
 
+use crate::core::keymap::Keymap;
+use crate::core::midi::MidiControl;
 use crate::core::model::Sort;
+use crate::core::slide::{TextAlignment, TextMargins};
+use crate::core::songs::{CreditDefaults, SongCredits};
 
 pub const SETTINGS_VERSION: u64 = 1;
 
@@ -28,6 +32,86 @@ impl AppTheme {
     }
 }
 
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub enum KeyColor {
+    #[default]
+    Transparent,
+    Green,
+    Blue,
+    Magenta,
+}
+
+impl KeyColor {
+    pub const ALL: [Self; 4] =
+        [Self::Transparent, Self::Green, Self::Blue, Self::Magenta];
+    pub const LABELS: [&str; 4] = ["Transparent", "Green", "Blue", "Magenta"];
+
+    #[must_use]
+    pub const fn color(self) -> cosmic::iced::Color {
+        match self {
+            Self::Transparent => cosmic::iced::Color::TRANSPARENT,
+            Self::Green => cosmic::iced::Color::from_rgb(0.0, 1.0, 0.0),
+            Self::Blue => cosmic::iced::Color::from_rgb(0.0, 0.0, 1.0),
+            Self::Magenta => cosmic::iced::Color::from_rgb(1.0, 0.0, 1.0),
+        }
+    }
+}
+
+/// How the second output draws the current slide's text for a stream,
+/// without the backgrounds the room sees.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct LowerThird {
+    pub background: KeyColor,
+    pub font_size: u8,
+    pub margins: TextMargins,
+    pub text_alignment: TextAlignment,
+}
+
+impl Default for LowerThird {
+    fn default() -> Self {
+        Self {
+            background: KeyColor::Transparent,
+            font_size: 60,
+            margins: TextMargins::LOWER_THIRD,
+            text_alignment: TextAlignment::BottomCenter,
+        }
+    }
+}
+
+/// The local server phones and stream overlays use to follow and drive
+/// the presenter.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct RemoteControl {
+    pub enabled: bool,
+    pub port: u16,
+    /// Listen on every interface instead of only localhost so phones on
+    /// the same network can connect.
+    pub allow_lan: bool,
+    /// The PIN other devices have to send to drive the presenter, `0`
+    /// until one is made.
+    pub pin: u32,
+}
+
+impl RemoteControl {
+    #[must_use]
+    pub fn new_pin() -> u32 {
+        fastrand::u32(100_000..1_000_000)
+    }
+}
+
+impl Default for RemoteControl {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 7272,
+            allow_lan: false,
+            pin: 0,
+        }
+    }
+}
+
 #[derive(Clone, CosmicConfigEntry, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(default)]
 pub struct Settings {
@@ -38,6 +122,24 @@ pub struct Settings {
     pub image_sort: Option<Sort>,
     pub video_sort: Option<Sort>,
     pub presentation_sort: Option<Sort>,
+    pub song_credits: SongCredits,
+    pub ccli_licence: Option<String>,
+    pub lower_third: LowerThird,
+    pub remote_control: RemoteControl,
+    /// Shown on the live output in logo mode.
+    pub logo: Option<PathBuf>,
+    pub keymap: Keymap,
+    pub midi: MidiControl,
+}
+
+impl Settings {
+    #[must_use]
+    pub fn credit_defaults(&self) -> CreditDefaults {
+        CreditDefaults {
+            credits: self.song_credits,
+            ccli_licence: self.ccli_licence.clone(),
+        }
+    }
 }
 
 impl Default for Settings {
@@ -50,6 +152,13 @@ impl Default for Settings {
             image_sort: None,
             video_sort: None,
             presentation_sort: None,
+            song_credits: SongCredits::default(),
+            ccli_licence: None,
+            lower_third: LowerThird::default(),
+            remote_control: RemoteControl::default(),
+            logo: None,
+            keymap: Keymap::default(),
+            midi: MidiControl::default(),
         }
     }
 }
@@ -1,6 +1,7 @@
 #![allow(clippy::similar_names, unused)]
-use cosmic::iced::Size;
 use cosmic::iced::core::image::Allocation;
+use cosmic::iced::font::{Style, Weight};
+use cosmic::iced::{ContentFit, Rectangle, Size};
 use cosmic::widget::image::Handle;
 // use cosmic::dialog::ashpd::url::Url;
 use crisp::types::{Keyword, Symbol, Value};
@@ -12,8 +13,10 @@ use std::fmt::Display;
 use std::path::{Path, PathBuf};
 use tracing::error;
 
+use crate::core::slide_actions::{self, lisp_to_action};
+use crate::lisp::{self, Form, quote};
 use crate::ui::gst_video;
-use crate::ui::text_svg::{Color, Font, Shadow, Stroke, TextSvg};
+use crate::ui::text_svg::{self, Color, Font, Shadow, ShrinkToFit, Stroke, TextSvg};
 
 use super::songs::Song;
 
@@ -24,17 +27,30 @@ pub struct Slide {
     #[serde(skip)]
     pub(crate) thumbnail: Option<Allocation>,
     text: String,
+    /// A small line along the bottom of the slide, like a song's licence.
+    #[serde(default)]
+    footer: String,
+    /// The verse of a song this slide shows, like `Chorus 1`.
+    #[serde(default)]
+    verse: String,
+    #[serde(default)]
+    notes: String,
     font: Option<Font>,
     font_size: i32,
+    shrink_to_fit: Option<ShrinkToFit>,
     stroke: Option<Stroke>,
     shadow: Option<Shadow>,
     text_alignment: TextAlignment,
+    #[serde(default)]
+    text_margins: TextMargins,
     text_color: Option<Color>,
     audio: Option<PathBuf>,
     video_loop: bool,
     video_start_time: f32,
     video_end_time: f32,
     pdf_index: u32,
+    #[serde(default)]
+    actions: Vec<slide_actions::Action>,
     pub text_svg: Option<TextSvg>,
     #[serde(skip)]
     pdf_page: Option<Handle>,
@@ -63,6 +79,35 @@ pub enum TextAlignment {
     BottomRight,
 }
 
+impl TextAlignment {
+    const ALL: [Self; 9] = [
+        Self::TopLeft,
+        Self::TopCenter,
+        Self::TopRight,
+        Self::MiddleLeft,
+        Self::MiddleCenter,
+        Self::MiddleRight,
+        Self::BottomLeft,
+        Self::BottomCenter,
+        Self::BottomRight,
+    ];
+
+    #[must_use]
+    pub const fn lisp_name(self) -> &'static str {
+        match self {
+            Self::TopLeft => "top-left",
+            Self::TopCenter => "top-center",
+            Self::TopRight => "top-right",
+            Self::MiddleLeft => "middle-left",
+            Self::MiddleCenter => "center",
+            Self::MiddleRight => "middle-right",
+            Self::BottomLeft => "bottom-left",
+            Self::BottomCenter => "bottom-center",
+            Self::BottomRight => "bottom-right",
+        }
+    }
+}
+
 impl From<Value> for TextAlignment {
     fn from(value: Value) -> Self {
         Self::from(&value)
@@ -71,10 +116,81 @@ impl From<Value> for TextAlignment {
 
 impl From<&Value> for TextAlignment {
     fn from(value: &Value) -> Self {
-        if value == &Value::Symbol("center".into()) {
-            Self::MiddleCenter
-        } else {
-            Self::TopCenter
+        match lisp::symbol(value) {
+            Some("middle-center") => Self::MiddleCenter,
+            Some(name) => Self::ALL
+                .into_iter()
+                .find(|alignment| alignment.lisp_name() == name)
+                .unwrap_or(Self::TopCenter),
+            None => Self::TopCenter,
+        }
+    }
+}
+
+/// The part of the slide that text is kept inside, given as the percent
+/// of the slide's width or height taken off of each edge.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, Hash)]
+pub struct TextMargins {
+    pub left: u8,
+    pub top: u8,
+    pub right: u8,
+    pub bottom: u8,
+}
+
+impl TextMargins {
+    /// Keeps the text in the lower third for livestream framing.
+    pub const LOWER_THIRD: Self = Self {
+        left: 5,
+        top: 66,
+        right: 5,
+        bottom: 5,
+    };
+
+    #[must_use]
+    pub fn rect(self, bounds: Rectangle) -> Rectangle {
+        let percent =
+            |margin: u8, length: f32| length * f32::from(margin.min(100)) / 100.0;
+        let left = percent(self.left, bounds.width);
+        let top = percent(self.top, bounds.height);
+        let right = percent(self.right, bounds.width);
+        let bottom = percent(self.bottom, bounds.height);
+        Rectangle {
+            x: bounds.x + left,
+            y: bounds.y + top,
+            width: (bounds.width - left - right).max(0.0),
+            height: (bounds.height - top - bottom).max(0.0),
+        }
+    }
+}
+
+/// How a background fills the slide, when it's unset each kind of
+/// background uses its own.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BackgroundFit {
+    Fill,
+    Contain,
+    Cover,
+}
+
+impl BackgroundFit {
+    const ALL: [Self; 3] = [Self::Fill, Self::Contain, Self::Cover];
+
+    #[must_use]
+    pub const fn lisp_name(self) -> &'static str {
+        match self {
+            Self::Fill => "fill",
+            Self::Contain => "contain",
+            Self::Cover => "cover",
+        }
+    }
+}
+
+impl From<BackgroundFit> for ContentFit {
+    fn from(fit: BackgroundFit) -> Self {
+        match fit {
+            BackgroundFit::Fill => Self::Fill,
+            BackgroundFit::Contain => Self::Contain,
+            BackgroundFit::Cover => Self::Cover,
         }
     }
 }
@@ -83,12 +199,38 @@ impl From<&Value> for TextAlignment {
 pub struct Background {
     pub path: PathBuf,
     pub kind: BackgroundKind,
+    #[serde(default)]
+    pub fit: Option<BackgroundFit>,
     #[serde(skip)]
     pub image_handle: Option<Handle>,
     #[serde(skip)]
     pub image_allocation: Option<Allocation>,
 }
 
+impl Background {
+    /// Pdf and html backgrounds are both written as presentations.
+    #[must_use]
+    pub fn lisp_form(&self) -> Form {
+        let kind = match self.kind {
+            BackgroundKind::Image => "image",
+            BackgroundKind::Video => "video",
+            BackgroundKind::Pdf | BackgroundKind::Html => "presentation",
+        };
+        let form = Form::new(kind).string("source", self.path.display().to_string());
+        match self.fit {
+            Some(fit) => form.atom("fit", fit.lisp_name()),
+            None => form,
+        }
+    }
+
+    /// How the background fills the slide, contained unless it says
+    /// otherwise.
+    #[must_use]
+    pub fn content_fit(&self) -> ContentFit {
+        self.fit.map_or(ContentFit::Contain, ContentFit::from)
+    }
+}
+
 impl TryFrom<&Background> for Video {
     type Error = ParseError;
 
@@ -153,24 +295,28 @@ impl TryFrom<PathBuf> for Background {
                     "jpeg" | "jpg" | "png" | "webp" => Ok(Self {
                         path: value.clone(),
                         kind: BackgroundKind::Image,
+                        fit: None,
                         image_handle: Some(value.into()),
                         image_allocation: None,
                     }),
                     "mp4" | "mkv" | "webm" => Ok(Self {
                         path: value,
                         kind: BackgroundKind::Video,
+                        fit: None,
                         image_handle: None,
                         image_allocation: None,
                     }),
                     "pdf" => Ok(Self {
                         path: value,
                         kind: BackgroundKind::Pdf,
+                        fit: None,
                         image_handle: None,
                         image_allocation: None,
                     }),
                     "html" => Ok(Self {
                         path: value,
                         kind: BackgroundKind::Html,
+                        fit: None,
                         image_handle: None,
                         image_allocation: None,
                     }),
@@ -250,11 +396,72 @@ impl From<String> for BackgroundKind {
 }
 
 impl From<&Slide> for Value {
-    fn from(_value: &Slide) -> Self {
-        Self::List(vec![Self::Symbol(Symbol("slide".into()))])
+    fn from(value: &Slide) -> Self {
+        value.lisp_form().into()
     }
 }
 
+const FONT_WEIGHTS: [(Weight, &str); 9] = [
+    (Weight::Thin, "thin"),
+    (Weight::ExtraLight, "extra-light"),
+    (Weight::Light, "light"),
+    (Weight::Normal, "normal"),
+    (Weight::Medium, "medium"),
+    (Weight::Semibold, "semibold"),
+    (Weight::Bold, "bold"),
+    (Weight::ExtraBold, "extra-bold"),
+    (Weight::Black, "black"),
+];
+
+const FONT_STYLES: [(Style, &str); 3] = [
+    (Style::Normal, "normal"),
+    (Style::Italic, "italic"),
+    (Style::Oblique, "oblique"),
+];
+
+#[must_use]
+pub fn weight_name(weight: Weight) -> &'static str {
+    FONT_WEIGHTS
+        .iter()
+        .find(|(font_weight, _)| *font_weight == weight)
+        .map_or("normal", |(_, name)| name)
+}
+
+#[must_use]
+pub fn style_name(style: Style) -> &'static str {
+    FONT_STYLES
+        .iter()
+        .find(|(font_style, _)| *font_style == style)
+        .map_or("normal", |(_, name)| name)
+}
+
+#[must_use]
+pub fn lisp_to_weight(lisp: &Value) -> Option<Weight> {
+    let name = lisp::symbol(lisp)?;
+    FONT_WEIGHTS
+        .iter()
+        .find(|(_, weight)| *weight == name)
+        .map(|(weight, _)| *weight)
+}
+
+#[must_use]
+pub fn lisp_to_style(lisp: &Value) -> Option<Style> {
+    let name = lisp::symbol(lisp)?;
+    FONT_STYLES
+        .iter()
+        .find(|(_, style)| *style == name)
+        .map(|(style, _)| *style)
+}
+
+/// Margins are written as `(left top right bottom)`.
+#[must_use]
+pub fn margins_lisp(margins: TextMargins) -> String {
+    format!(
+        "({} {} {} {})",
+        margins.left, margins.top, margins.right, margins.bottom
+    )
+}
+
 impl Slide {
     #[must_use]
     pub fn set_text(mut self, text: impl AsRef<str>) -> Self {
@@ -262,6 +469,30 @@ impl Slide {
         self
     }
 
+    #[must_use]
+    pub fn set_footer(mut self, footer: impl AsRef<str>) -> Self {
+        self.footer = footer.as_ref().into();
+        self
+    }
+
+    #[must_use]
+    pub fn set_verse(mut self, verse: impl AsRef<str>) -> Self {
+        self.verse = verse.as_ref().into();
+        self
+    }
+
+    #[must_use]
+    pub fn set_notes(mut self, notes: impl AsRef<str>) -> Self {
+        self.notes = notes.as_ref().into();
+        self
+    }
+
+    #[must_use]
+    pub const fn set_text_margins(mut self, text_margins: TextMargins) -> Self {
+        self.text_margins = text_margins;
+        self
+    }
+
     #[must_use]
     pub fn with_text_svg(mut self, text_svg: TextSvg) -> Self {
         self.text_svg = Some(text_svg);
@@ -310,6 +541,102 @@ impl Slide {
         self
     }
 
+    #[must_use]
+    pub(crate) fn set_actions(mut self, actions: Vec<slide_actions::Action>) -> Self {
+        self.actions = actions;
+        self
+    }
+
+    #[must_use]
+    pub(crate) fn actions(&self) -> &[slide_actions::Action] {
+        &self.actions
+    }
+
+    /// Every property of the slide that isn't derived while loading, in
+    /// the form that [`Slide::from`] reads back.
+    #[must_use]
+    pub fn lisp_form(&self) -> Form {
+        self.write_lisp(Form::new("slide"))
+    }
+
+    /// Adds the slide's properties and text to a form that may already
+    /// hold the properties of its service item.
+    #[must_use]
+    pub fn write_lisp(&self, mut form: Form) -> Form {
+        if !self.background.path.as_os_str().is_empty() {
+            form = form.atom("background", self.background.lisp_form());
+        }
+        if let Some(font) = &self.font {
+            form = form.string("font", font.get_name());
+            if font.get_weight() != Weight::Normal {
+                form = form.atom("font-weight", weight_name(font.get_weight()));
+            }
+            if font.get_style() != Style::Normal {
+                form = form.atom("font-style", style_name(font.get_style()));
+            }
+        }
+        if self.text_alignment != TextAlignment::MiddleCenter {
+            form = form.atom("text-alignment", self.text_alignment.lisp_name());
+        }
+        if self.text_margins != TextMargins::default() {
+            form = form.atom("margins", margins_lisp(self.text_margins));
+        }
+        if let Some(color) = &self.text_color {
+            form = form.string("color", color.to_css_hex_string());
+        }
+        if let Some(stroke) = &self.stroke {
+            form = form.atom(
+                "stroke",
+                format!(
+                    "({} {})",
+                    stroke.size(),
+                    quote(&stroke.color().to_css_hex_string())
+                ),
+            );
+        }
+        if let Some(shadow) = &self.shadow {
+            form = form.atom(
+                "shadow",
+                format!(
+                    "({} {} {} {})",
+                    shadow.offset_x,
+                    shadow.offset_y,
+                    shadow.spread,
+                    quote(&shadow.color.to_css_hex_string())
+                ),
+            );
+        }
+        if let Some(fit) = self.shrink_to_fit {
+            form = form.atom("shrink-to-fit", format!("({} {})", fit.min, fit.max));
+        }
+        if let Some(audio) = &self.audio {
+            form = form.string("audio", audio.display().to_string());
+        }
+        if self.video_loop {
+            form = form.atom("loop", true);
+        }
+        if self.video_start_time > 0.0 {
+            form = form.atom("start-time", self.video_start_time);
+        }
+        if self.video_end_time > 0.0 {
+            form = form.atom("end-time", self.video_end_time);
+        }
+        if self.pdf_index > 0 {
+            form = form.atom("pdf-index", self.pdf_index);
+        }
+        if !self.notes.is_empty() {
+            form = form.string("notes", &self.notes);
+        }
+        if !self.actions.is_empty() {
+            form = form.atom("actions", actions_lisp(&self.actions));
+        }
+        form.child(
+            Form::new("text")
+                .push(quote(&self.text))
+                .atom("font-size", self.font_size),
+        )
+    }
+
     #[must_use]
     pub const fn background(&self) -> &Background {
         &self.background
@@ -320,6 +647,21 @@ impl Slide {
         self.text.clone()
     }
 
+    #[must_use]
+    pub fn footer(&self) -> String {
+        self.footer.clone()
+    }
+
+    #[must_use]
+    pub fn verse(&self) -> String {
+        self.verse.clone()
+    }
+
+    #[must_use]
+    pub fn notes(&self) -> String {
+        self.notes.clone()
+    }
+
     #[must_use]
     pub const fn text_alignment(&self) -> TextAlignment {
         self.text_alignment
@@ -335,6 +677,16 @@ impl Slide {
         self.font.clone()
     }
 
+    #[must_use]
+    pub const fn shrink_to_fit(&self) -> Option<ShrinkToFit> {
+        self.shrink_to_fit
+    }
+
+    #[must_use]
+    pub const fn text_margins(&self) -> TextMargins {
+        self.text_margins
+    }
+
     #[must_use]
     pub const fn video_loop(&self) -> bool {
         self.video_loop
@@ -441,7 +793,7 @@ fn lisp_to_slide(lisp: &[Value]) -> Slide {
 
     let text_position = lisp.iter().position(|v| match v {
         Value::List(vec) => {
-            vec[DEFAULT_TEXT_LOCATION] == Value::Symbol(Symbol::from("text"))
+            vec.get(DEFAULT_TEXT_LOCATION) == Some(&Value::Symbol(Symbol::from("text")))
         }
         _ => false,
     });
@@ -466,12 +818,62 @@ fn lisp_to_slide(lisp: &[Value]) -> Slide {
         slide = slide.font_size(0);
     }
 
+    if let Some(margins) = lisp::keyword(lisp, "margins").and_then(lisp_to_margins) {
+        slide = slide.text_margins(margins);
+    }
+
+    if let Some(notes) = lisp_to_notes(lisp) {
+        slide = slide.notes(notes);
+    }
+
+    if let Some(color) = lisp::keyword(lisp, "color") {
+        slide = slide.text_color(Color::from_hex_str(lisp::string(color)));
+    }
+
+    if let Some(stroke) = lisp::keyword(lisp, "stroke").and_then(lisp_to_stroke) {
+        slide = slide.stroke(stroke);
+    }
+
+    if let Some(shadow) = lisp::keyword(lisp, "shadow").and_then(lisp_to_shadow) {
+        slide = slide.shadow(shadow);
+    }
+
+    if let Some(audio) = lisp::keyword(lisp, "audio") {
+        slide = slide.audio(lisp::string(audio));
+    }
+
+    let mut font = Font::from(
+        lisp::keyword(lisp, "font").map_or_else(|| "Quicksand".into(), lisp::string),
+    );
+    if let Some(weight) = lisp::keyword(lisp, "font-weight").and_then(lisp_to_weight) {
+        font = font.weight(weight);
+    }
+    if let Some(style) = lisp::keyword(lisp, "font-style").and_then(lisp_to_style) {
+        font = font.style(style);
+    }
+
     slide = slide
-        .font("Quicksand")
-        .text_alignment(TextAlignment::MiddleCenter)
-        .video_loop(false)
-        .video_start_time(0.0)
-        .video_end_time(0.0);
+        .font(font)
+        .text_alignment(
+            lisp::keyword(lisp, "text-alignment")
+                .map_or(TextAlignment::MiddleCenter, TextAlignment::from),
+        )
+        .shrink_to_fit(
+            lisp::keyword(lisp, "shrink-to-fit").and_then(lisp_to_shrink_to_fit),
+        )
+        .video_loop(lisp::keyword(lisp, "loop").is_some_and(lisp::boolean))
+        .video_start_time(lisp::keyword(lisp, "start-time").map_or(0.0, lisp::float))
+        .video_end_time(lisp::keyword(lisp, "end-time").map_or(0.0, lisp::float))
+        .pdf_index(
+            lisp::keyword(lisp, "pdf-index")
+                .and_then(|index| u32::try_from(i32::from(index)).ok())
+                .unwrap_or_default(),
+        )
+        .actions(
+            lisp::keyword(lisp, "actions")
+                .map(lisp_to_actions)
+                .unwrap_or_default(),
+        );
 
     match slide.build() {
         Ok(slide) => slide,
@@ -503,62 +905,136 @@ fn lisp_to_font_size(lisp: &Value) -> i32 {
     }
 }
 
-fn lisp_to_text(lisp: &Value) -> impl Into<String> {
+/// Reads the `:notes` string of a slide or service item.
+pub fn lisp_to_notes(lisp: &[Value]) -> Option<String> {
+    let position = lisp
+        .iter()
+        .position(|v| v == &Value::Keyword(Keyword::from("notes")))?;
+    match lisp.get(position + 1)? {
+        Value::String(notes) => Some(notes.clone()),
+        _ => None,
+    }
+}
+
+/// Reads a stroke written as `(size "#color")`.
+fn lisp_to_stroke(lisp: &Value) -> Option<Stroke> {
+    let Value::List(list) = lisp else {
+        return None;
+    };
+    let [size, color] = list.as_slice() else {
+        return None;
+    };
+    Some(text_svg::stroke(
+        u16::try_from(i32::from(size)).unwrap_or_default(),
+        Color::from_hex_str(lisp::string(color)),
+    ))
+}
+
+/// Reads a shadow written as `(offset-x offset-y spread "#color")`.
+fn lisp_to_shadow(lisp: &Value) -> Option<Shadow> {
+    let Value::List(list) = lisp else {
+        return None;
+    };
+    let [offset_x, offset_y, spread, color] = list.as_slice() else {
+        return None;
+    };
+    Some(text_svg::shadow(
+        i16::try_from(i32::from(offset_x)).unwrap_or_default(),
+        i16::try_from(i32::from(offset_y)).unwrap_or_default(),
+        u16::try_from(i32::from(spread)).unwrap_or_default(),
+        Color::from_hex_str(lisp::string(color)),
+    ))
+}
+
+/// Reads the font size range written as `(min max)`.
+pub fn lisp_to_shrink_to_fit(lisp: &Value) -> Option<ShrinkToFit> {
+    let Value::List(list) = lisp else {
+        return None;
+    };
+    let [min, max] = list.as_slice() else {
+        return None;
+    };
+    Some(ShrinkToFit {
+        min: u8::try_from(i32::from(min)).unwrap_or_default(),
+        max: u8::try_from(i32::from(max)).unwrap_or_default(),
+    })
+}
+
+/// Slide actions are a list of forms like `((obs :start-stream))`.
+pub(crate) fn lisp_to_actions(lisp: &Value) -> Vec<slide_actions::Action> {
+    match lisp {
+        Value::List(list) => list.iter().filter_map(lisp_to_action).collect(),
+        _ => vec![],
+    }
+}
+
+pub(crate) fn actions_lisp(actions: &[slide_actions::Action]) -> String {
+    let actions = actions
+        .iter()
+        .map(|action| action.lisp_form().to_string())
+        .collect::<Vec<String>>();
+    format!("({})", actions.join(" "))
+}
+
+/// Reads margins written as `(left top right bottom)` in percent.
+pub fn lisp_to_margins(lisp: &Value) -> Option<TextMargins> {
+    match lisp {
+        Value::List(list) if list.len() == 4 => {
+            let mut margins = list
+                .iter()
+                .map(|margin| u8::try_from(i32::from(margin)).unwrap_or_default());
+            Some(TextMargins {
+                left: margins.next()?,
+                top: margins.next()?,
+                right: margins.next()?,
+                bottom: margins.next()?,
+            })
+        }
+        _ => None,
+    }
+}
+
+fn lisp_to_text(lisp: &Value) -> String {
     match lisp {
-        Value::List(list) => list[1].clone(),
-        _ => "".into(),
+        Value::List(list) => list.get(1).map(lisp::string).unwrap_or_default(),
+        _ => String::new(),
     }
 }
 
 // Need to return a Result here so that we can propogate
 // errors and then handle them appropriately
-#[allow(clippy::option_if_let_else)]
 pub fn lisp_to_background(lisp: &Value) -> Background {
-    match lisp {
-        Value::List(list) => {
-            let _kind = list[0].clone();
-            if let Some(source) = list
-                .iter()
-                .position(|v| v == &Value::Keyword(Keyword::from("source")))
-            {
-                let source = &list[source + 1];
-                match source {
-                    Value::String(s) => {
-                        if s.starts_with("./") {
-                            let Some(home) = dirs::home_dir() else {
-                                panic!("Should always be there");
-                            };
-                            let Some(home) = home.to_str() else {
-                                panic!("Should always be there");
-                            };
-                            let mut home = home.to_string();
-                            home.push('/');
-
-                            let s = s.replace("./", &home);
-                            match Background::try_from(s.as_str()) {
-                                Ok(background) => background,
-                                Err(e) => {
-                                    error!("Couldn't load background: {e}");
-                                    Background::default()
-                                }
-                            }
-                        } else {
-                            match Background::try_from(s.as_str()) {
-                                Ok(background) => background,
-                                Err(e) => {
-                                    error!("Couldn't load background: {e}");
-                                    Background::default()
-                                }
-                            }
-                        }
-                    }
-                    _ => Background::default(),
-                }
-            } else {
-                Background::default()
-            }
+    let Value::List(list) = lisp else {
+        return Background::default();
+    };
+    let Some(Value::String(source)) = lisp::keyword(list, "source") else {
+        return Background::default();
+    };
+    let source = if source.starts_with("./") {
+        let mut home = dirs::home_dir()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        home.push('/');
+        source.replace("./", &home)
+    } else {
+        source.clone()
+    };
+    match Background::try_from(source.as_str()) {
+        Ok(background) => Background {
+            fit: lisp::keyword(list, "fit")
+                .and_then(lisp::symbol)
+                .and_then(|name| {
+                    BackgroundFit::ALL
+                        .into_iter()
+                        .find(|fit| fit.lisp_name() == name)
+                }),
+            ..background
+        },
+        Err(e) => {
+            error!("Couldn't load background: {e}");
+            Background::default()
         }
-        _ => Background::default(),
     }
 }
 
@@ -566,17 +1042,21 @@ pub fn lisp_to_background(lisp: &Value) -> Background {
 pub struct SlideBuilder {
     background: Option<Background>,
     text: Option<String>,
+    notes: Option<String>,
     font: Option<Font>,
     font_size: Option<i32>,
+    shrink_to_fit: Option<ShrinkToFit>,
     audio: Option<PathBuf>,
     stroke: Option<Stroke>,
     shadow: Option<Shadow>,
     text_color: Option<Color>,
     text_alignment: Option<TextAlignment>,
+    text_margins: Option<TextMargins>,
     video_loop: Option<bool>,
     video_start_time: Option<f32>,
     video_end_time: Option<f32>,
     pdf_index: Option<u32>,
+    actions: Option<Vec<slide_actions::Action>>,
     #[serde(skip)]
     pdf_page: Option<Handle>,
     #[serde(skip)]
@@ -607,6 +1087,11 @@ impl SlideBuilder {
         self
     }
 
+    pub(crate) fn notes(mut self, notes: impl Into<String>) -> Self {
+        let _ = self.notes.insert(notes.into());
+        self
+    }
+
     pub(crate) fn text_color(mut self, text_color: impl Into<Color>) -> Self {
         let _ = self.text_color.insert(text_color.into());
         self
@@ -642,11 +1127,24 @@ impl SlideBuilder {
         self
     }
 
+    pub(crate) const fn shrink_to_fit(
+        mut self,
+        shrink_to_fit: Option<ShrinkToFit>,
+    ) -> Self {
+        self.shrink_to_fit = shrink_to_fit;
+        self
+    }
+
     pub(crate) fn text_alignment(mut self, text_alignment: TextAlignment) -> Self {
         let _ = self.text_alignment.insert(text_alignment);
         self
     }
 
+    pub(crate) fn text_margins(mut self, text_margins: TextMargins) -> Self {
+        let _ = self.text_margins.insert(text_margins);
+        self
+    }
+
     pub(crate) fn video_loop(mut self, video_loop: bool) -> Self {
         let _ = self.video_loop.insert(video_loop);
         self
@@ -677,6 +1175,11 @@ impl SlideBuilder {
         self
     }
 
+    pub(crate) fn actions(mut self, actions: Vec<slide_actions::Action>) -> Self {
+        let _ = self.actions.insert(actions);
+        self
+    }
+
     pub(crate) fn build(self) -> Result<Slide> {
         let Some(background) = self.background else {
             return Err(miette!("No background"));
@@ -702,9 +1205,12 @@ impl SlideBuilder {
         Ok(Slide {
             background,
             text,
+            notes: self.notes.unwrap_or_default(),
             font: self.font,
             font_size,
+            shrink_to_fit: self.shrink_to_fit,
             text_alignment,
+            text_margins: self.text_margins.unwrap_or_default(),
             audio: self.audio,
             stroke: self.stroke,
             shadow: self.shadow,
@@ -714,6 +1220,7 @@ impl SlideBuilder {
             video_end_time,
             text_svg: self.text_svg,
             pdf_index: self.pdf_index.unwrap_or_default(),
+            actions: self.actions.unwrap_or_default(),
             pdf_page: self.pdf_page,
             ..Default::default()
         })
@@ -722,6 +1229,7 @@ impl SlideBuilder {
 
 #[cfg(test)]
 mod test {
+    use cosmic::iced::Point;
     use pretty_assertions::assert_eq;
     use std::fs::read_to_string;
 
@@ -746,6 +1254,19 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_text_margins() {
+        let bounds = Rectangle::new(Point::new(0.0, 0.0), Size::new(1920.0, 1080.0));
+        assert_eq!(TextMargins::default().rect(bounds), bounds);
+        let lower_third = TextMargins::LOWER_THIRD.rect(bounds);
+        assert!((lower_third.x - 96.0).abs() < 0.01);
+        assert!((lower_third.width - 1728.0).abs() < 0.01);
+        assert!((lower_third.y - 712.8).abs() < 0.01);
+
+        let lisp = crisp::reader::read("(5 66 5 5)");
+        assert_eq!(lisp_to_margins(&lisp), Some(TextMargins::LOWER_THIRD));
+    }
+
     #[test]
     fn test_ron_deserialize() {
         let slide =
@@ -1,3 +1,4 @@
+use crisp::types::Value;
 use miette::{IntoDiagnostic, Result};
 use std::sync::Arc;
 use tracing::warn;
@@ -6,6 +7,8 @@ use obws::Client;
 use obws::responses::scenes::Scene;
 use serde::{Deserialize, Serialize};
 
+use crate::lisp::{self, Form};
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub(crate) enum ObsAction {
     Scene { scene: Scene },
@@ -40,3 +43,78 @@ impl ObsAction {
         Ok(())
     }
 }
+
+impl Action {
+    /// Scenes keep the fields obs gives them, like
+    /// `(obs :scene (:sceneName "Worship" :sceneIndex 2))`.
+    pub(crate) fn lisp_form(&self) -> Form {
+        match self {
+            Self::Obs {
+                action: ObsAction::Scene { scene },
+            } => {
+                let fields = match serde_json::to_value(scene) {
+                    Ok(serde_json::Value::Object(map)) => map
+                        .into_iter()
+                        .filter_map(|(key, value)| match value {
+                            serde_json::Value::String(value) => {
+                                Some(format!(":{key} {}", lisp::quote(&value)))
+                            }
+                            serde_json::Value::Number(value) => {
+                                Some(format!(":{key} {value}"))
+                            }
+                            _ => None,
+                        })
+                        .collect::<Vec<String>>()
+                        .join(" "),
+                    _ => String::new(),
+                };
+                Form::new("obs").atom("scene", format!("({fields})"))
+            }
+            Self::Obs {
+                action: ObsAction::StartStream,
+            } => Form::new("obs").flag("start-stream"),
+            Self::Obs {
+                action: ObsAction::StopStream,
+            } => Form::new("obs").flag("stop-stream"),
+            Self::Other => Form::new("other"),
+        }
+    }
+}
+
+pub(crate) fn lisp_to_action(value: &Value) -> Option<Action> {
+    let Value::List(list) = value else {
+        return None;
+    };
+    match list.first().and_then(lisp::symbol)? {
+        "obs" if lisp::has_keyword(list, "start-stream") => Some(Action::Obs {
+            action: ObsAction::StartStream,
+        }),
+        "obs" if lisp::has_keyword(list, "stop-stream") => Some(Action::Obs {
+            action: ObsAction::StopStream,
+        }),
+        "obs" => {
+            let Some(Value::List(fields)) = lisp::keyword(list, "scene") else {
+                return None;
+            };
+            let map = fields
+                .chunks(2)
+                .filter_map(|pair| {
+                    let [Value::Keyword(key), value] = pair else {
+                        return None;
+                    };
+                    let value = match value {
+                        Value::String(_) => serde_json::Value::from(lisp::string(value)),
+                        value => serde_json::Value::from(i32::from(value)),
+                    };
+                    Some((key.0.clone(), value))
+                })
+                .collect::<serde_json::Map<String, serde_json::Value>>();
+            let scene = serde_json::from_value(serde_json::Value::Object(map)).ok()?;
+            Some(Action::Obs {
+                action: ObsAction::Scene { scene },
+            })
+        }
+        "other" => Some(Action::Other),
+        _ => None,
+    }
+}
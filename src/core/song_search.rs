@@ -4,9 +4,9 @@ use miette::{IntoDiagnostic, Result, miette};
 use nom::branch::alt;
 use nom::bytes::complete::{tag, take_till, take_till1, take_until};
 use nom::character::complete::{digit0, space0};
-use nom::combinator::rest;
+use nom::combinator::{opt, rest};
 use nom::multi::many1;
-use nom::sequence::{delimited, pair};
+use nom::sequence::{delimited, pair, preceded};
 use nom::{IResult, Parser};
 use reqwest::header;
 use serde::{Deserialize, Serialize};
@@ -133,6 +133,235 @@ fn parse_verse_name(line: &str) -> IResult<&str, VerseName> {
     Ok((input, verse_name))
 }
 
+#[derive(Debug)]
+struct ChordProSection {
+    name: VerseName,
+    lyrics: Vec<String>,
+    chords: Vec<String>,
+    /// Sections started by a directive end with another directive,
+    /// the rest end at a blank line.
+    explicit: bool,
+}
+
+#[derive(Debug, Default)]
+struct ChordPro {
+    verse_map: HashMap<VerseName, String>,
+    chord_map: HashMap<VerseName, String>,
+    verses: Vec<VerseName>,
+    section: Option<ChordProSection>,
+    last_chorus: Option<VerseName>,
+}
+
+impl ChordPro {
+    fn next_name(&self, mut name: VerseName) -> VerseName {
+        while self.verse_map.contains_key(&name)
+            || self
+                .section
+                .as_ref()
+                .is_some_and(|section| section.name == name)
+        {
+            name = name.next();
+        }
+        name
+    }
+
+    fn start(&mut self, name: VerseName, explicit: bool) {
+        self.finish();
+        self.section = Some(ChordProSection {
+            name,
+            lyrics: vec![],
+            chords: vec![],
+            explicit,
+        });
+    }
+
+    fn push_line(&mut self, line: &str) {
+        if self.section.is_none() {
+            let name = self.next_name(VerseName::Verse { number: 1 });
+            self.start(name, false);
+        }
+        if let Some(section) = self.section.as_mut() {
+            let lyric = strip_chords(line);
+            if !lyric.is_empty() {
+                section.lyrics.push(lyric);
+            }
+            section.chords.push(line.trim_end().to_string());
+        }
+    }
+
+    fn push_break(&mut self) {
+        match self.section.as_mut() {
+            Some(section) if section.explicit => {
+                if section.lyrics.last().is_some_and(|line| !line.is_empty()) {
+                    section.lyrics.push(String::new());
+                    section.chords.push(String::new());
+                }
+            }
+            _ => self.finish(),
+        }
+    }
+
+    fn finish(&mut self) {
+        let Some(section) = self.section.take() else {
+            return;
+        };
+        let lyrics = section.lyrics.join("\n").trim().to_string();
+        if lyrics.is_empty() {
+            return;
+        }
+        let chords = section.chords.join("\n").trim().to_string();
+        let mut name = section.name;
+        while self
+            .verse_map
+            .get(&name)
+            .is_some_and(|existing| *existing != lyrics)
+        {
+            name = name.next();
+        }
+        if matches!(name, VerseName::Chorus { .. }) {
+            self.last_chorus = Some(name);
+        }
+        self.verse_map.insert(name, lyrics);
+        self.chord_map.insert(name, chords);
+        self.verses.push(name);
+    }
+}
+
+fn strip_chords(line: &str) -> String {
+    let mut lyric = String::with_capacity(line.len());
+    let mut rest = line;
+    while let Some(start) = rest.find('[') {
+        lyric.push_str(&rest[..start]);
+        match rest[start..].find(']') {
+            Some(end) => rest = &rest[start + end + 1..],
+            None => {
+                lyric.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+    lyric.push_str(rest);
+    lyric.split_whitespace().join(" ")
+}
+
+fn parse_directive(line: &str) -> IResult<&str, (&str, Option<&str>)> {
+    let (input, (name, value)) = delimited(
+        (tag("{"), space0),
+        pair(
+            take_till1(|c: char| c == ':' || c == '}' || c.is_whitespace()),
+            opt(preceded((space0, opt(tag(":"))), take_until("}"))),
+        ),
+        tag("}"),
+    )
+    .parse(line.trim())?;
+    Ok((
+        input,
+        (name, value.map(str::trim).filter(|v| !v.is_empty())),
+    ))
+}
+
+/// Parses a ChordPro chart into a song, the chords are stripped out of the
+/// lyrics but kept in `chords` for the musicians.
+pub fn parse_chordpro(chart: &str) -> Result<Song> {
+    let mut song = Song::default();
+    let mut authors: Vec<String> = vec![];
+    let mut parser = ChordPro::default();
+    let mut skipping = false;
+
+    for line in chart.lines() {
+        if line.trim_start().starts_with('#') {
+            continue;
+        }
+        let Ok((_, (directive, value))) = parse_directive(line) else {
+            if skipping {
+                continue;
+            }
+            if line.trim().is_empty() {
+                parser.push_break();
+            } else {
+                parser.push_line(line);
+            }
+            continue;
+        };
+
+        let start = |default: VerseName, parser: &ChordPro| {
+            value
+                .and_then(VerseName::from_label)
+                .unwrap_or_else(|| parser.next_name(default))
+        };
+        match directive.to_lowercase().as_str() {
+            "title" | "t" => song.title = value.unwrap_or_default().to_string(),
+            "artist" | "composer" | "lyricist" => {
+                if let Some(author) = value
+                    && !authors.iter().any(|existing| existing == author)
+                {
+                    authors.push(author.to_string());
+                }
+            }
+            "ccli" => song.ccli = value.map(ToString::to_string),
+            "copyright" => song.copyright = value.map(ToString::to_string),
+            "start_of_chorus" | "soc" => {
+                parser.finish();
+                let name = start(VerseName::Chorus { number: 1 }, &parser);
+                parser.start(name, true);
+            }
+            "start_of_verse" | "sov" => {
+                parser.finish();
+                let name = start(VerseName::Verse { number: 1 }, &parser);
+                parser.start(name, true);
+            }
+            "start_of_bridge" | "sob" => {
+                parser.finish();
+                let name = start(VerseName::Bridge { number: 1 }, &parser);
+                parser.start(name, true);
+            }
+            "start_of_tab" | "sot" | "start_of_grid" | "sog" => {
+                parser.finish();
+                skipping = true;
+            }
+            "end_of_tab" | "eot" | "end_of_grid" | "eog" => skipping = false,
+            "chorus" => {
+                parser.finish();
+                if let Some(chorus) = parser.last_chorus {
+                    parser.verses.push(chorus);
+                }
+            }
+            "comment" | "c" | "comment_italic" | "ci" | "comment_box" | "cb"
+            | "highlight" => {
+                if let Some(name) = value.and_then(VerseName::from_label) {
+                    parser.start(name, false);
+                }
+            }
+            directive if directive.starts_with("start_of_") => {
+                parser.finish();
+                let name = start(VerseName::Other { number: 1 }, &parser);
+                parser.start(name, true);
+            }
+            directive
+                if directive.starts_with("end_of_") || directive.starts_with("eo") =>
+            {
+                parser.finish();
+            }
+            _ => (),
+        }
+    }
+    parser.finish();
+
+    if parser.verse_map.is_empty() {
+        return Err(miette!("There weren't any lyrics in this chart"));
+    }
+
+    song.author = (!authors.is_empty()).then(|| authors.join(", "));
+    song.lyrics = ron::ser::to_string(&Some(&parser.verse_map)).ok();
+    song.verse_order = Some(parser.verses.iter().map(VerseName::get_name).collect());
+    song.verses = Some(parser.verses);
+    song.verse_map = Some(parser.verse_map);
+    if parser.chord_map.values().any(|chords| chords.contains('[')) {
+        song.chords = Some(parser.chord_map);
+    }
+    Ok(song)
+}
+
 pub async fn search_genius(query: String, auth_token: String) -> Result<Vec<OnlineSong>> {
     // let Some(auth_token) = option_env!("GENIUS_TOKEN") else {
     //     return Err(miette!("No Genius Token"));
@@ -576,4 +805,63 @@ Aw man, that was good"#;
         // assert!(false);
         Ok(())
     }
+
+    #[test]
+    fn test_chordpro() -> Result<()> {
+        let chart = r"{title: Amazing Grace}
+{artist: John Newton}
+{ccli: 4755360}
+# arranged for our band
+{comment: Verse 1}
+[G]Amazing [G7]grace how [C]sweet the [G]sound
+That saved a wretch like me
+
+{start_of_chorus}
+[C]My chains are [G]gone
+
+I've been set free
+{end_of_chorus}
+
+{sov}
+[G]'Twas grace that taught
+{eov}
+{start_of_tab}
+e|--3--|
+{end_of_tab}
+{chorus}
+";
+        let song = parse_chordpro(chart)?;
+        assert_eq!(song.title, "Amazing Grace");
+        assert_eq!(song.author.as_deref(), Some("John Newton"));
+        assert_eq!(song.ccli.as_deref(), Some("4755360"));
+        assert_eq!(
+            song.verses,
+            Some(vec![
+                VerseName::Verse { number: 1 },
+                VerseName::Chorus { number: 1 },
+                VerseName::Verse { number: 2 },
+                VerseName::Chorus { number: 1 },
+            ])
+        );
+        assert_eq!(
+            song.get_lyric(&VerseName::Verse { number: 1 }).as_deref(),
+            Some("Amazing grace how sweet the sound\nThat saved a wretch like me")
+        );
+        assert_eq!(
+            song.get_lyric(&VerseName::Chorus { number: 1 }).as_deref(),
+            Some("My chains are gone\n\nI've been set free")
+        );
+        assert_eq!(
+            song.chords
+                .as_ref()
+                .and_then(|chords| chords.get(&VerseName::Verse { number: 2 }))
+                .map(String::as_str),
+            Some("[G]'Twas grace that taught")
+        );
+        assert_eq!(
+            strip_chords("[C]My chains [Am7]are gone"),
+            "My chains are gone"
+        );
+        Ok(())
+    }
 }
@@ -2,28 +2,34 @@ use std::borrow::Cow;
 use std::collections::HashMap;
 use std::mem::replace;
 use std::option::Option;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use cosmic::cosmic_theme::palette::Srgb;
 use cosmic::iced::clipboard::mime::{AllowedMimeTypes, AsMimeTypes};
 use cosmic::iced::font::{Style, Weight};
-use crisp::types::{Keyword, Symbol, Value};
+use crisp::types::{Symbol, Value};
 use itertools::Itertools;
 use miette::{IntoDiagnostic, Result, miette};
+use resvg::usvg::fontdb;
 use serde::{Deserialize, Serialize};
 use sqlx::sqlite::SqliteRow;
 use sqlx::types::chrono::{DateTime, Local};
 use sqlx::{AssertSqlSafe, FromRow, Row, SqlitePool, query};
 use tracing::{debug, error};
 
-use crate::core::animation::Animation;
+use crate::core::animation::{Animation, lisp_to_animation};
 use crate::core::content::Content;
 use crate::core::kinds::ServiceItemKind;
 use crate::core::model::{LibraryKind, Model, Sort, SortDirection};
 use crate::core::service_items::ServiceTrait;
-use crate::core::slide::{self, Background, TextAlignment};
-use crate::ui::text_svg::{Color, Font, Stroke, shadow, stroke};
+use crate::core::slide::{
+    self, Background, TextAlignment, TextMargins, lisp_to_shrink_to_fit, lisp_to_style,
+    lisp_to_weight, margins_lisp, style_name, weight_name,
+};
+use crate::core::{openlp, openlyrics, song_search, songselect};
+use crate::lisp::{self, Form, quote};
+use crate::ui::text_svg::{self, Color, Font, ShrinkToFit, Stroke, shadow, stroke};
 use crate::{Slide, SlideBuilder};
 
 #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
@@ -33,12 +39,17 @@ pub struct Song {
     pub lyrics: Option<String>,
     pub author: Option<String>,
     pub ccli: Option<String>,
+    pub copyright: Option<String>,
+    #[serde(default)]
+    pub songbooks: Vec<Songbook>,
     pub audio: Option<PathBuf>,
     pub verse_order: Option<Vec<String>>,
     pub background: Option<Background>,
     pub text_alignment: Option<TextAlignment>,
+    pub text_margins: Option<TextMargins>,
     pub font: Option<String>,
     pub font_size: Option<i32>,
+    pub shrink_to_fit: Option<ShrinkToFit>,
     pub font_weight: Option<Weight>,
     pub font_style: Option<Style>,
     pub text_color: Option<Srgb>,
@@ -49,8 +60,16 @@ pub struct Song {
     pub shadow_color: Option<Srgb>,
     pub verses: Option<Vec<VerseName>>,
     pub verse_map: Option<HashMap<VerseName, String>>,
+    /// The lyrics of each verse with their chords left in, ChordPro style
+    pub chords: Option<HashMap<VerseName, String>>,
+    /// Falls back to the default credits in the settings when unset
+    pub credits: Option<SongCredits>,
+    /// How verses get split into slides beyond their blank lines
+    pub slide_fit: Option<SlideFit>,
     pub lyric_video: Option<PathBuf>,
     pub music_video: Option<PathBuf>,
+    /// Notes that service items of the song start out with, like `Capo 2`
+    pub notes: Option<String>,
     #[serde(skip)]
     pub created_at: DateTime<Local>,
     #[serde(skip)]
@@ -58,6 +77,101 @@ pub struct Song {
     pub animation: Option<Animation>,
 }
 
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SlideFit {
+    #[default]
+    BlankLines,
+    MaxLines(usize),
+    Fit,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Licence {
+    #[default]
+    Off,
+    Slide,
+    Overlay,
+}
+
+/// Which credit slides get added around the lyrics of a song.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SongCredits {
+    pub title_slide: bool,
+    pub licence: Licence,
+}
+
+impl SongCredits {
+    pub const ALL: [Self; 6] = [
+        Self {
+            title_slide: false,
+            licence: Licence::Off,
+        },
+        Self {
+            title_slide: true,
+            licence: Licence::Off,
+        },
+        Self {
+            title_slide: false,
+            licence: Licence::Slide,
+        },
+        Self {
+            title_slide: true,
+            licence: Licence::Slide,
+        },
+        Self {
+            title_slide: false,
+            licence: Licence::Overlay,
+        },
+        Self {
+            title_slide: true,
+            licence: Licence::Overlay,
+        },
+    ];
+
+    pub const LABELS: [&str; 6] = [
+        "No Credits",
+        "Title Slide",
+        "Licence Slide",
+        "Title and Licence Slides",
+        "Licence on Last Slide",
+        "Title Slide and Licence on Last Slide",
+    ];
+}
+
+impl std::fmt::Display for SongCredits {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let index = Self::ALL
+            .iter()
+            .position(|credits| credits == self)
+            .unwrap_or_default();
+        write!(f, "{}", Self::LABELS[index])
+    }
+}
+
+/// A hymnal or songbook the song is printed in, with its number there.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Songbook {
+    pub name: String,
+    pub entry: Option<String>,
+}
+
+impl std::fmt::Display for Songbook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.entry {
+            Some(entry) => write!(f, "{} #{entry}", self.name),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+/// The credits used by songs that don't choose their own along with the
+/// church's CCLI licence number, these come from the settings.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CreditDefaults {
+    pub credits: SongCredits,
+    pub ccli_licence: Option<String>,
+}
+
 #[derive(
     Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Hash, PartialOrd, Ord,
 )]
@@ -92,6 +206,29 @@ impl VerseName {
         }
     }
 
+    /// Section labels like `Chorus 2` or `Pre-Chorus` as they are written
+    /// in charts and SongSelect exports.
+    #[must_use]
+    pub fn from_label(label: &str) -> Option<Self> {
+        let label = label.trim().trim_end_matches(':').to_lowercase();
+        let name =
+            label.trim_end_matches(|c: char| c.is_ascii_digit() || c.is_whitespace());
+        let number = label[name.len()..].trim().parse().unwrap_or(1);
+        let verse_name = match name.replace([' ', '-'], "").as_str() {
+            "verse" => Self::Verse { number },
+            "prechorus" => Self::PreChorus { number },
+            "chorus" | "refrain" => Self::Chorus { number },
+            "postchorus" => Self::PostChorus { number },
+            "bridge" => Self::Bridge { number },
+            "intro" => Self::Intro { number },
+            "outro" | "ending" | "tag" => Self::Outro { number },
+            "instrumental" | "interlude" => Self::Instrumental { number },
+            "other" | "misc" | "vamp" => Self::Other { number },
+            _ => return None,
+        };
+        Some(verse_name)
+    }
+
     #[must_use]
     pub fn all_names() -> Vec<String> {
         vec![
@@ -211,8 +348,186 @@ impl Default for VerseName {
 }
 
 impl From<&Song> for Value {
-    fn from(_value: &Song) -> Self {
-        Self::List(vec![Self::Symbol(Symbol("song".into()))])
+    fn from(value: &Song) -> Self {
+        value.lisp_form().into()
+    }
+}
+
+impl Song {
+    /// Verses are written as `(v1 "...")` in the order they are first
+    /// sung, any that aren't sung come after.
+    #[must_use]
+    pub fn lisp_form(&self) -> Form {
+        self.write_lisp(Form::new("song"))
+    }
+
+    /// Adds the song's properties and verses to a form that may already
+    /// hold the properties of its service item.
+    #[must_use]
+    #[allow(clippy::too_many_lines)]
+    pub fn write_lisp(&self, mut form: Form) -> Form {
+        let hex = |color: Srgb| quote(&Color::from(color).to_css_hex_string());
+        if self.id != 0 {
+            form = form.atom("id", self.id);
+        }
+        form = form.string("title", &self.title);
+        if let Some(author) = &self.author {
+            form = form.string("author", author);
+        }
+        if let Some(ccli) = &self.ccli {
+            form = form.string("ccli", ccli);
+        }
+        if let Some(copyright) = &self.copyright {
+            form = form.string("copyright", copyright);
+        }
+        if let Some(notes) = self.notes.as_ref().filter(|notes| !notes.is_empty()) {
+            form = form.string("notes", notes);
+        }
+        if !self.songbooks.is_empty() {
+            let songbooks = self
+                .songbooks
+                .iter()
+                .map(|songbook| {
+                    let form = Form::new("songbook").string("name", &songbook.name);
+                    match &songbook.entry {
+                        Some(entry) => form.string("entry", entry),
+                        None => form,
+                    }
+                })
+                .join(" ");
+            form = form.atom("songbooks", format!("({songbooks})"));
+        }
+        if let Some(audio) = &self.audio {
+            form = form.string("audio", audio.display().to_string());
+        }
+        if let Some(font) = &self.font {
+            form = form.string("font", font);
+        }
+        if let Some(font_size) = self.font_size {
+            form = form.atom("font-size", font_size);
+        }
+        if let Some(weight) = self.font_weight {
+            form = form.atom("font-weight", weight_name(weight));
+        }
+        if let Some(style) = self.font_style {
+            form = form.atom("font-style", style_name(style));
+        }
+        if let Some(background) = &self.background {
+            form = form.atom("background", background.lisp_form());
+        }
+        if let Some(alignment) = self.text_alignment {
+            form = form.atom("text-alignment", alignment.lisp_name());
+        }
+        if let Some(margins) = self.text_margins {
+            form = form.atom("margins", margins_lisp(margins));
+        }
+        if let Some(fit) = self.shrink_to_fit {
+            form = form.atom("shrink-to-fit", format!("({} {})", fit.min, fit.max));
+        }
+        if let Some(color) = self.text_color {
+            form = form.atom("color", hex(color));
+        }
+        if let Some(size) = self.stroke_size {
+            form = form.atom("stroke-size", size);
+        }
+        if let Some(color) = self.stroke_color {
+            form = form.atom("stroke-color", hex(color));
+        }
+        if let Some(size) = self.shadow_size {
+            form = form.atom("shadow-size", size);
+        }
+        if let Some((x, y)) = self.shadow_offset {
+            form = form.atom("shadow-offset", format!("({x} {y})"));
+        }
+        if let Some(color) = self.shadow_color {
+            form = form.atom("shadow-color", hex(color));
+        }
+        if let Some(credits) = self.credits {
+            let licence = match credits.licence {
+                Licence::Off => "off",
+                Licence::Slide => "slide",
+                Licence::Overlay => "overlay",
+            };
+            form = form.atom(
+                "credits",
+                format!("(:title-slide {} :licence {licence})", credits.title_slide),
+            );
+        }
+        if let Some(fit) = self.slide_fit {
+            form = match fit {
+                SlideFit::BlankLines => form.atom("slide-fit", "blank-lines"),
+                SlideFit::MaxLines(lines) => {
+                    form.atom("slide-fit", format!("(max-lines {lines})"))
+                }
+                SlideFit::Fit => form.atom("slide-fit", "fit"),
+            };
+        }
+        if let Some(video) = &self.lyric_video {
+            form = form.string("lyric-video", video.display().to_string());
+        }
+        if let Some(video) = &self.music_video {
+            form = form.string("music-video", video.display().to_string());
+        }
+        if let Some(animation) = &self.animation {
+            form = form.atom("animation", animation.lisp_form());
+        }
+        if let Some(verses) = &self.verses {
+            let order = verses
+                .iter()
+                .map(openlyrics::verse_name_to_openlyrics)
+                .collect::<Vec<String>>();
+            form = form.atom("verse-order", format!("({})", order.join(" ")));
+        } else if let Some(order) = &self.verse_order {
+            form = form.atom(
+                "verse-order",
+                format!("({})", order.join(" ").to_lowercase()),
+            );
+        }
+        if let Some(chords) = &self.chords {
+            let mut names = chords.keys().collect::<Vec<&VerseName>>();
+            names.sort();
+            let chords = names
+                .into_iter()
+                .map(|name| {
+                    format!(
+                        "({} {})",
+                        openlyrics::verse_name_to_openlyrics(name),
+                        quote(&chords[name])
+                    )
+                })
+                .collect::<Vec<String>>();
+            form = form.atom("chords", format!("({})", chords.join(" ")));
+        }
+
+        let Some(verse_map) = &self.verse_map else {
+            return match &self.lyrics {
+                Some(lyrics) => form.string("lyrics", lyrics),
+                None => form,
+            };
+        };
+        let mut names: Vec<VerseName> = vec![];
+        for name in self.verses.iter().flatten() {
+            if !names.contains(name) {
+                names.push(*name);
+            }
+        }
+        let mut unsung = verse_map
+            .keys()
+            .filter(|name| !names.contains(name))
+            .copied()
+            .collect::<Vec<VerseName>>();
+        unsung.sort();
+        names.extend(unsung);
+        for name in names {
+            if let Some(lyric) = verse_map.get(&name) {
+                form = form.child(format!(
+                    "({} {})",
+                    openlyrics::verse_name_to_openlyrics(&name),
+                    quote(lyric)
+                ));
+            }
+        }
+        form
     }
 }
 
@@ -250,72 +565,9 @@ impl ServiceTrait for Song {
     }
 
     fn to_slides(&self) -> Result<Vec<Slide>> {
-        // let lyrics = self.get_lyrics()?;
-        let lyrics: Vec<String> = self
-            .verses
-            .as_ref()
-            .ok_or_else(|| miette!("There are no verses assigned yet."))?
-            .iter()
-            .filter_map(|verse| self.get_lyric(verse))
-            .flat_map(|lyric| {
-                lyric
-                    .split("\n\n")
-                    .map(std::string::ToString::to_string)
-                    .collect::<Vec<String>>()
-            })
-            .collect();
-
-        debug!(?lyrics);
-        let slides: Vec<Slide> = lyrics
-            .iter()
-            .filter_map(|l| {
-                let font = Font::default()
-                    .name(self.font.clone().unwrap_or_else(|| "Calibri".into()))
-                    .style(self.font_style.unwrap_or_default())
-                    .weight(self.font_weight.unwrap_or_default())
-                    .size(u8::try_from(self.font_size.unwrap_or(100)).unwrap_or(100));
-                let stroke_size = self.stroke_size.unwrap_or_default();
-                let stroke: Stroke = stroke(
-                    stroke_size,
-                    self.stroke_color.map(Color::from).unwrap_or_default(),
-                );
-                let shadow_size = self.shadow_size.unwrap_or_default();
-                let shadow = shadow(
-                    self.shadow_offset.unwrap_or_default().0,
-                    self.shadow_offset.unwrap_or_default().1,
-                    shadow_size,
-                    self.shadow_color.map(Color::from).unwrap_or_default(),
-                );
-                let builder = SlideBuilder::new();
-                let builder = if shadow_size > 0 {
-                    builder.shadow(shadow)
-                } else {
-                    builder
-                };
-                let builder = if stroke_size > 0 {
-                    builder.stroke(stroke)
-                } else {
-                    builder
-                };
-                builder
-                    .background(self.background.clone().unwrap_or_default())
-                    .font(font)
-                    .font_size(self.font_size.unwrap_or_default())
-                    .text_alignment(self.text_alignment.unwrap_or_default())
-                    .text_color(
-                        self.text_color.unwrap_or_else(|| Srgb::new(1.0, 1.0, 1.0)),
-                    )
-                    .audio(self.audio.clone().unwrap_or_default())
-                    .video_loop(true)
-                    .video_start_time(0.0)
-                    .video_end_time(0.0)
-                    .text(l)
-                    .build()
-                    .ok()
-            })
-            .collect();
-
-        Ok(slides)
+        // Without any fonts to measure with the fitted lines are a guess,
+        // the app builds them again with its fonts in `with_credits`.
+        self.slides(&CreditDefaults::default(), &fontdb::Database::new())
     }
 
     fn box_clone(&self) -> Box<dyn ServiceTrait> {
@@ -406,6 +658,39 @@ impl FromRow<'_, SqliteRow> for Song {
             lyrics: Some(lyrics.to_string()),
             author: row.try_get("author")?,
             ccli: row.try_get("ccli")?,
+            copyright: row.try_get("copyright").ok().flatten(),
+            notes: row.try_get("notes").ok().flatten(),
+            songbooks: row
+                .try_get::<Option<&str>, &str>("songbooks")
+                .ok()
+                .flatten()
+                .and_then(|songbooks| ron::de::from_str(songbooks).ok())
+                .unwrap_or_default(),
+            chords: row
+                .try_get::<Option<&str>, &str>("chords")
+                .ok()
+                .flatten()
+                .and_then(|chords| ron::de::from_str(chords).ok()),
+            credits: row
+                .try_get::<Option<&str>, &str>("credits")
+                .ok()
+                .flatten()
+                .and_then(|credits| ron::de::from_str(credits).ok()),
+            slide_fit: row
+                .try_get::<Option<&str>, &str>("slide_fit")
+                .ok()
+                .flatten()
+                .and_then(|fit| ron::de::from_str(fit).ok()),
+            shrink_to_fit: row
+                .try_get::<Option<&str>, &str>("shrink_to_fit")
+                .ok()
+                .flatten()
+                .and_then(|fit| ron::de::from_str(fit).ok()),
+            text_margins: row
+                .try_get::<Option<&str>, &str>("text_margins")
+                .ok()
+                .flatten()
+                .and_then(|margins| ron::de::from_str(margins).ok()),
             audio: Some(PathBuf::from({
                 let string: String = row.try_get("audio")?;
                 string
@@ -465,202 +750,184 @@ impl From<Value> for Song {
     }
 }
 
-#[allow(clippy::option_if_let_else)]
 #[allow(clippy::needless_pass_by_value)]
 #[allow(clippy::too_many_lines)]
 pub fn lisp_to_song(list: Vec<Value>) -> Song {
-    const DEFAULT_SONG_ID: i32 = 0;
-    // const DEFAULT_SONG_LOCATION: usize = 0;
-
-    let id = if let Some(key_pos) = list
-        .iter()
-        .position(|v| v == &Value::Keyword(Keyword::from("id")))
-    {
-        let pos = key_pos + 1;
-        list.get(pos).map(i32::from).unwrap_or_default()
-    } else {
-        DEFAULT_SONG_ID
+    let string = |key| lisp::keyword(&list, key).map(lisp::string);
+    let number = |key| lisp::keyword(&list, key).map(i32::from);
+    let size = |key| number(key).and_then(|size| u16::try_from(size).ok());
+    let color = |key| {
+        lisp::keyword(&list, key)
+            .map(|color| Srgb::from(Color::from_hex_str(lisp::string(color))))
     };
 
-    let background = if let Some(key_pos) = list
-        .iter()
-        .position(|v| v == &Value::Keyword(Keyword::from("background")))
-    {
-        let pos = key_pos + 1;
-        list.get(pos).map(slide::lisp_to_background)
-    } else {
-        None
-    };
+    // Verses are the forms like (v1 "...") that aren't the value of a keyword
+    let mut sung_order = vec![];
+    let mut verse_map = HashMap::new();
+    for (index, element) in list.iter().enumerate().skip(1) {
+        if matches!(list.get(index - 1), Some(Value::Keyword(_))) {
+            continue;
+        }
+        let Value::List(verse) = element else {
+            continue;
+        };
+        let [Value::Symbol(Symbol(code)), lyric @ Value::String(_)] = verse.as_slice()
+        else {
+            continue;
+        };
+        let name = openlyrics::verse_name_from_openlyrics(code);
+        if !sung_order.contains(&name) {
+            sung_order.push(name);
+        }
+        verse_map.insert(name, lisp::lyric(lyric));
+    }
 
-    let author = if let Some(key_pos) = list
-        .iter()
-        .position(|v| v == &Value::Keyword(Keyword::from("author")))
-    {
-        let pos = key_pos + 1;
-        list.get(pos).map(String::from)
-    } else {
-        None
+    let verse_codes = match lisp::keyword(&list, "verse-order") {
+        Some(Value::List(codes)) => {
+            Some(codes.iter().map(String::from).collect::<Vec<String>>())
+        }
+        _ => None,
     };
+    let verses = verse_codes.as_ref().map_or_else(
+        || (!sung_order.is_empty()).then(|| sung_order.clone()),
+        |codes| {
+            Some(
+                codes
+                    .iter()
+                    .map(|code| openlyrics::verse_name_from_openlyrics(code))
+                    .collect(),
+            )
+        },
+    );
 
-    let audio = if let Some(key_pos) = list
-        .iter()
-        .position(|v| v == &Value::Keyword(Keyword::from("audio")))
-    {
-        let pos = key_pos + 1;
-        list.get(pos).map(|a| PathBuf::from(String::from(a)))
+    let lyrics = if verse_map.is_empty() {
+        string("lyrics")
     } else {
-        None
+        Some(
+            sung_order
+                .iter()
+                .map(|name| format!("{}\n{}", name.get_name(), verse_map[name]))
+                .collect::<Vec<String>>()
+                .join("\n\n"),
+        )
     };
 
-    let ccli = if let Some(key_pos) = list
-        .iter()
-        .position(|v| v == &Value::Keyword(Keyword::from("ccli")))
-    {
-        let pos = key_pos + 1;
-        list.get(pos).map(|c| i32::from(c).to_string())
-    } else {
-        None
-    };
+    let ccli = lisp::keyword(&list, "ccli").map(|ccli| match ccli {
+        Value::String(_) => lisp::string(ccli),
+        ccli => i32::from(ccli).to_string(),
+    });
 
-    let font = if let Some(key_pos) = list
-        .iter()
-        .position(|v| v == &Value::Keyword(Keyword::from("font")))
-    {
-        let pos = key_pos + 1;
-        list.get(pos).map(String::from)
-    } else {
-        None
+    let credits = match lisp::keyword(&list, "credits") {
+        Some(Value::List(credits)) => Some(SongCredits {
+            title_slide: lisp::keyword(credits, "title-slide").is_some_and(lisp::boolean),
+            licence: match lisp::keyword(credits, "licence").and_then(lisp::symbol) {
+                Some("slide") => Licence::Slide,
+                Some("overlay") => Licence::Overlay,
+                _ => Licence::Off,
+            },
+        }),
+        _ => None,
     };
 
-    let font_size = if let Some(key_pos) = list
-        .iter()
-        .position(|v| v == &Value::Keyword(Keyword::from("font-size")))
-    {
-        let pos = key_pos + 1;
-        list.get(pos).map(i32::from)
-    } else {
-        None
+    let songbooks = match lisp::keyword(&list, "songbooks") {
+        Some(Value::List(songbooks)) => songbooks
+            .iter()
+            .filter_map(|songbook| match songbook {
+                Value::List(songbook) => Some(Songbook {
+                    name: lisp::keyword(songbook, "name").map(lisp::string)?,
+                    entry: lisp::keyword(songbook, "entry").map(lisp::string),
+                }),
+                _ => None,
+            })
+            .collect(),
+        _ => vec![],
     };
 
-    let title = if let Some(key_pos) = list
-        .iter()
-        .position(|v| v == &Value::Keyword(Keyword::from("title")))
-    {
-        let pos = key_pos + 1;
-        list.get(pos)
-            .map_or_else(|| String::from("song"), String::from)
-    } else {
-        String::from("song")
-    };
+    let slide_fit = lisp::keyword(&list, "slide-fit").and_then(|fit| match fit {
+        Value::List(fit) => match fit.as_slice() {
+            [name, lines] if lisp::symbol(name) == Some("max-lines") => Some(
+                SlideFit::MaxLines(usize::try_from(i32::from(lines)).unwrap_or(1)),
+            ),
+            _ => None,
+        },
+        fit => match lisp::symbol(fit)? {
+            "blank-lines" => Some(SlideFit::BlankLines),
+            "fit" => Some(SlideFit::Fit),
+            _ => None,
+        },
+    });
 
-    let text_alignment = if let Some(key_pos) = list
-        .iter()
-        .position(|v| v == &Value::Keyword(Keyword::from("text-alignment")))
-    {
-        let pos = key_pos + 1;
-        list.get(pos).map(TextAlignment::from)
-    } else {
-        None
+    let shadow_offset = match lisp::keyword(&list, "shadow-offset") {
+        Some(Value::List(offset)) => match offset.as_slice() {
+            [x, y] => Some((
+                i16::try_from(i32::from(x)).unwrap_or_default(),
+                i16::try_from(i32::from(y)).unwrap_or_default(),
+            )),
+            _ => None,
+        },
+        _ => None,
     };
 
-    let verse_order = if let Some(key_pos) = list
-        .iter()
-        .position(|v| v == &Value::Keyword(Keyword::from("verse-order")))
-    {
-        let pos = key_pos + 1;
-        list.get(pos).map(|v| match v {
-            Value::List(vals) => vals
+    let chords = match lisp::keyword(&list, "chords") {
+        Some(Value::List(chords)) => Some(
+            chords
                 .iter()
-                .map(|v| String::from(v).to_uppercase())
-                .collect::<Vec<String>>(),
-            _ => vec![],
-        })
-    } else {
-        None
+                .filter_map(|chord| match chord {
+                    Value::List(chord) => match chord.as_slice() {
+                        [Value::Symbol(Symbol(code)), lyric] => Some((
+                            openlyrics::verse_name_from_openlyrics(code),
+                            lisp::lyric(lyric),
+                        )),
+                        _ => None,
+                    },
+                    _ => None,
+                })
+                .collect(),
+        ),
+        _ => None,
     };
 
-    let first_text_postiion = list
-        .iter()
-        .position(|v| match v {
-            Value::List(inner) => {
-                (match &inner[0] {
-                    Value::Symbol(Symbol(text)) => {
-                        text.contains("v1")
-                            || text.contains("text")
-                            || text.contains("c1")
-                            || text.contains("i1")
-                    }
-                    _ => false,
-                } && matches!(&inner[1], Value::String(_)))
-            }
-            _ => false,
-        })
-        .unwrap_or(1);
-
-    let lyric_elements = &list[first_text_postiion..];
-
-    let mut lyrics = vec![];
-
-    for element in lyric_elements {
-        let Value::List(lyric) = element else {
-            continue;
-        };
-        let Value::Symbol(Symbol(lyric_verse)) = &lyric[0] else {
-            continue;
-        };
-
-        let lyric = String::from(&lyric[1]);
-
-        let verse_title = match lyric_verse.as_str() {
-            "i1" => r"\n\nIntro 1\n",
-            "i2" => r"\n\nIntro 2\n",
-            "v1" => r"\n\nVerse 1\n",
-            "v2" => r"\n\nVerse 2\n",
-            "v3" => r"\n\nVerse 3\n",
-            "v4" => r"\n\nVerse 4\n",
-            "v5" => r"\n\nVerse 5\n",
-            "c1" => r"\n\nChorus 1\n",
-            "c2" => r"\n\nChorus 2\n",
-            "c3" => r"\n\nChorus 3\n",
-            "c4" => r"\n\nChorus 4\n",
-            "b1" => r"\n\nBridge 1\n",
-            "b2" => r"\n\nBridge 2\n",
-            "e1" => r"\n\nEnding 1\n",
-            "e2" => r"\n\nEnding 2\n",
-            "o1" => r"\n\nOther 1\n",
-            "o2" => r"\n\nOther 2\n",
-            _ => "",
-        };
-
-        let lyric = format!("{verse_title}{lyric}");
-        let lyric = lyric.replace(
-            "\\n", r"
-",
-        );
-        lyrics.push(lyric);
-    }
-
-    let lyrics: String = lyrics.iter().flat_map(|s| s.chars()).collect();
-    let lyrics = lyrics.trim_start().to_string();
-
     Song {
-        id,
-        title,
-        lyrics: Some(lyrics),
-        author,
-        audio,
+        id: number("id").unwrap_or_default(),
+        title: string("title").unwrap_or_else(|| String::from("song")),
+        lyrics,
+        author: string("author"),
         ccli,
-        verse_order,
-        background,
-        font,
-        font_size,
-        text_alignment,
+        copyright: string("copyright"),
+        songbooks,
+        notes: string("notes"),
+        audio: string("audio").map(PathBuf::from),
+        verse_order: verse_codes
+            .map(|codes| codes.iter().map(|code| code.to_uppercase()).collect()),
+        background: lisp::keyword(&list, "background").map(slide::lisp_to_background),
+        text_alignment: lisp::keyword(&list, "text-alignment").map(TextAlignment::from),
+        text_margins: lisp::keyword(&list, "margins").and_then(slide::lisp_to_margins),
+        font: string("font"),
+        font_size: number("font-size"),
+        shrink_to_fit: lisp::keyword(&list, "shrink-to-fit")
+            .and_then(lisp_to_shrink_to_fit),
+        font_weight: lisp::keyword(&list, "font-weight").and_then(lisp_to_weight),
+        font_style: lisp::keyword(&list, "font-style").and_then(lisp_to_style),
+        text_color: color("color"),
+        stroke_size: size("stroke-size"),
+        stroke_color: color("stroke-color"),
+        shadow_size: size("shadow-size"),
+        shadow_offset,
+        shadow_color: color("shadow-color"),
+        verses,
+        verse_map: (!verse_map.is_empty()).then_some(verse_map),
+        chords,
+        credits,
+        slide_fit,
+        lyric_video: string("lyric-video").map(PathBuf::from),
+        music_video: string("music-video").map(PathBuf::from),
+        animation: lisp::keyword(&list, "animation").and_then(lisp_to_animation),
         ..Default::default()
     }
 }
 
 pub async fn get_song_from_db(id: i32, db: Arc<SqlitePool>) -> Result<Song> {
-    let row = query("SELECT verse_order, font_size, background_type, horizontal_text_alignment, vertical_text_alignment, title, font, background, lyrics, ccli, author, audio, stroke_size, stroke_color, shadow_color, shadow_size, shadow_offset_x, shadow_offset_y, style, weight, id from songs where id = $1").bind(id).fetch_one(&*db).await.into_diagnostic()?;
+    let row = query("SELECT verse_order, font_size, background_type, horizontal_text_alignment, vertical_text_alignment, title, font, background, lyrics, ccli, copyright, songbooks, notes, chords, credits, slide_fit, shrink_to_fit, text_margins, author, audio, stroke_size, stroke_color, shadow_color, shadow_size, shadow_offset_x, shadow_offset_y, style, weight, id from songs where id = $1").bind(id).fetch_one(&*db).await.into_diagnostic()?;
     Song::from_row(&row).into_diagnostic()
 }
 
@@ -678,7 +945,7 @@ impl Model<Song> {
 
     pub async fn load_from_db(&mut self, db: Arc<SqlitePool>) {
         // static DATABASE_URL: &str = "sqlite:///home/chris/.local/share/lumina/library-db.sqlite3";
-        let result = query("SELECT verse_order, font_size, background_type, horizontal_text_alignment, vertical_text_alignment, title, font, background, lyrics, ccli, author, audio, stroke_size, shadow_size, stroke_color, shadow_color, shadow_offset_x, shadow_offset_y, style, weight, id from songs").fetch_all(&*db).await;
+        let result = query("SELECT verse_order, font_size, background_type, horizontal_text_alignment, vertical_text_alignment, title, font, background, lyrics, ccli, copyright, songbooks, notes, chords, credits, slide_fit, shrink_to_fit, text_margins, author, audio, stroke_size, shadow_size, stroke_color, shadow_color, shadow_offset_x, shadow_offset_y, style, weight, id from songs").fetch_all(&*db).await;
         match result {
             Ok(s) => {
                 for song in s {
@@ -816,7 +1083,7 @@ pub async fn insert_song(
         .map(|vid| vid.to_str().unwrap_or_default().to_string());
 
     let res = query!(
-        r#"INSERT INTO songs (title, lyrics, author, ccli, verse_order, audio, font, font_size, background, lyric_video, music_video) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)"#,
+        r#"INSERT INTO songs (title, lyrics, author, ccli, verse_order, audio, font, font_size, background, lyric_video, music_video, copyright) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)"#,
         song.title,
         song.lyrics,
         song.author,
@@ -827,7 +1094,8 @@ pub async fn insert_song(
         song.font_size,
         background,
         lyric_video,
-        music_video
+        music_video,
+        song.copyright
     )
     .execute(&*db)
     .await
@@ -843,6 +1111,76 @@ pub async fn add_song(songs: Vec<Song>, db: Arc<SqlitePool>) -> Result<Vec<Song>
     insert_song(song, songs, db).await
 }
 
+/// Inserts fully formed songs, like those from an importer, making sure
+/// their verses are stored the same way the song editor stores them.
+pub async fn add_songs(
+    new_songs: Vec<Song>,
+    mut songs: Vec<Song>,
+    db: Arc<SqlitePool>,
+) -> Result<Vec<Song>> {
+    for song in new_songs {
+        songs = insert_song(song, songs, Arc::clone(&db)).await?;
+        let song = songs
+            .last()
+            .cloned()
+            .ok_or_else(|| miette!("The song wasn't inserted"))?;
+        songs = update_song(song, songs, Arc::clone(&db)).await?;
+    }
+    Ok(songs)
+}
+
+pub fn songs_from_file(path: impl AsRef<Path>) -> Result<Vec<Song>> {
+    let path = path.as_ref();
+    match path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_lowercase)
+        .as_deref()
+    {
+        Some("xml") => openlyrics::song_from_file(path).map(|song| vec![song]),
+        Some("usr" | "txt") => songselect::song_from_file(path).map(|song| vec![song]),
+        Some("cho" | "chordpro" | "chopro" | "crd") => {
+            let chart = std::fs::read_to_string(path).into_diagnostic()?;
+            let mut song = song_search::parse_chordpro(&chart)?;
+            if song.title.is_empty() {
+                song.title = path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+                    .unwrap_or_default();
+            }
+            Ok(vec![song])
+        }
+        _ => Err(miette!("Don't know how to import {}", path.display())),
+    }
+}
+
+/// Reads songs out of every file, OpenLP databases are checked against
+/// the existing songs and the songs they skip are returned instead of
+/// added.
+pub async fn import_files(
+    paths: &[PathBuf],
+    existing: &[Song],
+) -> (Vec<Song>, Vec<openlp::Skipped>) {
+    let mut songs = vec![];
+    let mut skipped = vec![];
+    for path in paths {
+        let result = if path.extension().is_some_and(|ext| ext == "sqlite") {
+            let existing = [existing, songs.as_slice()].concat();
+            openlp::import(path, &existing).await.map(|import| {
+                skipped.extend(import.skipped);
+                import.songs
+            })
+        } else {
+            songs_from_file(path)
+        };
+        match result {
+            Ok(new_songs) => songs.extend(new_songs),
+            Err(e) => error!(?e, ?path, "couldn't import songs"),
+        }
+    }
+    (songs, skipped)
+}
+
 pub async fn update_song(
     song: Song,
     mut songs: Vec<Song>,
@@ -852,6 +1190,40 @@ pub async fn update_song(
 
     // debug!(?item);
     let verse_order = ron::ser::to_string(&song.verses).into_diagnostic()?;
+    let chords = song
+        .chords
+        .as_ref()
+        .map(ron::ser::to_string)
+        .transpose()
+        .into_diagnostic()?;
+    let credits = song
+        .credits
+        .as_ref()
+        .map(ron::ser::to_string)
+        .transpose()
+        .into_diagnostic()?;
+    let slide_fit = song
+        .slide_fit
+        .as_ref()
+        .map(ron::ser::to_string)
+        .transpose()
+        .into_diagnostic()?;
+    let shrink_to_fit = song
+        .shrink_to_fit
+        .as_ref()
+        .map(ron::ser::to_string)
+        .transpose()
+        .into_diagnostic()?;
+    let text_margins = song
+        .text_margins
+        .as_ref()
+        .map(ron::ser::to_string)
+        .transpose()
+        .into_diagnostic()?;
+    let songbooks = (!song.songbooks.is_empty())
+        .then(|| ron::ser::to_string(&song.songbooks))
+        .transpose()
+        .into_diagnostic()?;
 
     let audio = song
         .audio
@@ -920,7 +1292,7 @@ pub async fn update_song(
     // );
 
     let result = query!(
-        r#"UPDATE songs SET title = $2, lyrics = $3, author = $4, ccli = $5, verse_order = $6, audio = $7, font = $8, font_size = $9, background = $10, horizontal_text_alignment = $11, vertical_text_alignment = $12, stroke_color = $13, shadow_color = $14, stroke_size = $15, shadow_size = $16, shadow_offset_x = $17, shadow_offset_y = $18, style = $19, weight = $20, lyric_video = $21, music_video = $22, accessed_at = $23 WHERE id = $1"#,
+        r#"UPDATE songs SET title = $2, lyrics = $3, author = $4, ccli = $5, verse_order = $6, audio = $7, font = $8, font_size = $9, background = $10, horizontal_text_alignment = $11, vertical_text_alignment = $12, stroke_color = $13, shadow_color = $14, stroke_size = $15, shadow_size = $16, shadow_offset_x = $17, shadow_offset_y = $18, style = $19, weight = $20, lyric_video = $21, music_video = $22, accessed_at = $23, copyright = $24, chords = $25, credits = $26, slide_fit = $27, shrink_to_fit = $28, text_margins = $29, songbooks = $30, notes = $31 WHERE id = $1"#,
         song.id,
         song.title,
         lyrics,
@@ -943,7 +1315,15 @@ pub async fn update_song(
         weight,
         lyric_video,
         music_video,
-        accessed_at
+        accessed_at,
+        song.copyright,
+        chords,
+        credits,
+        slide_fit,
+        shrink_to_fit,
+        text_margins,
+        songbooks,
+        song.notes
     )
         .execute(&*db)
         .await
@@ -965,6 +1345,199 @@ pub async fn update_song(
 }
 
 impl Song {
+    /// The text of every slide of the song along with the name of the
+    /// verse or credit it came from.
+    pub fn slide_texts(
+        &self,
+        defaults: &CreditDefaults,
+        fontdb: &fontdb::Database,
+    ) -> Result<Vec<(String, String)>> {
+        // let lyrics = self.get_lyrics()?;
+        let mut lyrics: Vec<(String, String)> = self
+            .verses
+            .as_ref()
+            .ok_or_else(|| miette!("There are no verses assigned yet."))?
+            .iter()
+            .filter_map(|verse| Some((verse.get_name(), self.get_lyric(verse)?)))
+            .flat_map(|(name, lyric)| {
+                self.split_lyric(&lyric, fontdb)
+                    .into_iter()
+                    .map(move |text| (name.clone(), text))
+            })
+            .collect();
+
+        debug!(?lyrics);
+        let credits = self.credits.unwrap_or(defaults.credits);
+        if credits.title_slide {
+            lyrics.insert(0, ("Title".into(), self.title_lines().join("\n")));
+        }
+        if credits.licence == Licence::Slide {
+            let mut lines = self.title_lines();
+            lines.extend(self.licence_lines(defaults.ccli_licence.as_deref()));
+            lyrics.push(("Licence".into(), lines.join("\n")));
+        }
+        Ok(lyrics)
+    }
+
+    /// Builds the slides of the song, each knowing the verse it's from,
+    /// with the licence as a footer on the last one when the credits
+    /// overlay it.
+    pub fn slides(
+        &self,
+        defaults: &CreditDefaults,
+        fontdb: &fontdb::Database,
+    ) -> Result<Vec<Slide>> {
+        let mut slides: Vec<Slide> = self
+            .slide_texts(defaults, fontdb)?
+            .iter()
+            .filter_map(|(verse, text)| Some(self.build_slide(text)?.set_verse(verse)))
+            .collect();
+        if self.credits.unwrap_or(defaults.credits).licence == Licence::Overlay
+            && let Some(last) = slides.last_mut()
+        {
+            let footer = self.licence_lines(defaults.ccli_licence.as_deref());
+            *last = std::mem::take(last).set_footer(footer.join(" | "));
+        }
+        Ok(slides)
+    }
+
+    fn slide_font(&self) -> Font {
+        Font::default()
+            .name(self.font.clone().unwrap_or_else(|| "Calibri".into()))
+            .style(self.font_style.unwrap_or_default())
+            .weight(self.font_weight.unwrap_or_default())
+            .size(u8::try_from(self.font_size.unwrap_or(100)).unwrap_or(100))
+    }
+
+    fn max_lines(&self, fontdb: &fontdb::Database) -> Option<usize> {
+        match self.slide_fit.unwrap_or_default() {
+            SlideFit::BlankLines => None,
+            SlideFit::MaxLines(lines) => Some(lines.max(1)),
+            SlideFit::Fit => Some(text_svg::lines_that_fit(
+                &self.slide_font(),
+                self.text_margins.unwrap_or_default(),
+                fontdb,
+            )),
+        }
+    }
+
+    /// Splits a verse into the text of each of its slides. Blank lines
+    /// always break, then any part that is still too long gets split
+    /// into evenly sized slides. When fitting to the slide, lines too
+    /// wide for it are wrapped first.
+    #[must_use]
+    pub fn split_lyric(&self, lyric: &str, fontdb: &fontdb::Database) -> Vec<String> {
+        let max_lines = self.max_lines(fontdb);
+        let fit = self.slide_fit.unwrap_or_default() == SlideFit::Fit;
+        let font = self.slide_font();
+        let margins = self.text_margins.unwrap_or_default();
+        lyric
+            .split("\n\n")
+            .flat_map(|part| {
+                let lines: Vec<String> = if fit {
+                    part.lines()
+                        .flat_map(|line| {
+                            text_svg::wrap_line(line, &font, margins, fontdb)
+                        })
+                        .collect()
+                } else {
+                    part.lines().map(ToString::to_string).collect()
+                };
+                match max_lines {
+                    Some(max_lines) if lines.len() > max_lines => {
+                        let slides = lines.len().div_ceil(max_lines);
+                        lines
+                            .chunks(lines.len().div_ceil(slides))
+                            .map(|chunk| chunk.join("\n"))
+                            .collect()
+                    }
+                    _ if fit => vec![lines.join("\n")],
+                    _ => vec![part.to_string()],
+                }
+            })
+            .collect()
+    }
+
+    /// Turns the automatic breaks into blank lines in the lyrics so they
+    /// stay where they are when the font or fit changes.
+    pub fn pin_breaks(&mut self, fontdb: &fontdb::Database) {
+        let Some(mut verse_map) = self.verse_map.take() else {
+            return;
+        };
+        for lyric in verse_map.values_mut() {
+            *lyric = self.split_lyric(lyric, fontdb).join("\n\n");
+        }
+        self.lyrics = ron::ser::to_string(&Some(&verse_map)).ok();
+        self.verse_map = Some(verse_map);
+    }
+
+    fn build_slide(&self, text: &str) -> Option<Slide> {
+        let font = self.slide_font();
+        let stroke_size = self.stroke_size.unwrap_or_default();
+        let stroke: Stroke = stroke(
+            stroke_size,
+            self.stroke_color.map(Color::from).unwrap_or_default(),
+        );
+        let shadow_size = self.shadow_size.unwrap_or_default();
+        let shadow = shadow(
+            self.shadow_offset.unwrap_or_default().0,
+            self.shadow_offset.unwrap_or_default().1,
+            shadow_size,
+            self.shadow_color.map(Color::from).unwrap_or_default(),
+        );
+        let builder = SlideBuilder::new();
+        let builder = if shadow_size > 0 {
+            builder.shadow(shadow)
+        } else {
+            builder
+        };
+        let builder = if stroke_size > 0 {
+            builder.stroke(stroke)
+        } else {
+            builder
+        };
+        builder
+            .background(self.background.clone().unwrap_or_default())
+            .font(font)
+            .font_size(self.font_size.unwrap_or_default())
+            .shrink_to_fit(self.shrink_to_fit)
+            .text_alignment(self.text_alignment.unwrap_or_default())
+            .text_margins(self.text_margins.unwrap_or_default())
+            .text_color(self.text_color.unwrap_or_else(|| Srgb::new(1.0, 1.0, 1.0)))
+            .audio(self.audio.clone().unwrap_or_default())
+            .video_loop(true)
+            .video_start_time(0.0)
+            .video_end_time(0.0)
+            .text(text)
+            .build()
+            .ok()
+    }
+
+    fn title_lines(&self) -> Vec<String> {
+        let mut lines = vec![self.title.clone()];
+        lines.extend(self.author.clone().filter(|author| !author.is_empty()));
+        lines
+    }
+
+    fn licence_lines(&self, ccli_licence: Option<&str>) -> Vec<String> {
+        let mut lines = vec![];
+        if let Some(copyright) = self.copyright.as_ref().filter(|c| !c.is_empty()) {
+            if copyright.starts_with('©') {
+                lines.push(copyright.clone());
+            } else {
+                lines.push(format!("© {copyright}"));
+            }
+        }
+        lines.extend(self.songbooks.iter().map(ToString::to_string));
+        if let Some(ccli) = self.ccli.as_ref().filter(|ccli| !ccli.is_empty()) {
+            lines.push(format!("CCLI Song # {ccli}"));
+        }
+        if let Some(licence) = ccli_licence.filter(|licence| !licence.is_empty()) {
+            lines.push(format!("CCLI License # {licence}"));
+        }
+        lines
+    }
+
     #[must_use]
     pub fn get_lyric(&self, verse: &VerseName) -> Option<String> {
         self.verse_map.as_ref().and_then(|verse_map| {
@@ -1557,6 +2130,118 @@ You saved my soul"
         });
     }
 
+    #[test]
+    fn test_song_credits() -> Result<()> {
+        let mut song = test_song();
+        song.author = Some("North Point Worship".into());
+        song.ccli = Some("7016417".into());
+        song.copyright = Some("2017 North Point Worship Publishing".into());
+        let lyric_slides = song.to_slides()?.len();
+
+        song.credits = Some(SongCredits {
+            title_slide: true,
+            licence: Licence::Slide,
+        });
+        let slides = song.to_slides()?;
+        assert_eq!(slides.len(), lyric_slides + 2);
+        assert_eq!(
+            slides[0].text(),
+            format!("{}\nNorth Point Worship", song.title)
+        );
+        assert_eq!(slides[0].verse(), "Title");
+        assert_eq!(
+            slides[1].verse(),
+            VerseName::Chorus { number: 1 }.get_name()
+        );
+        assert_eq!(slides[slides.len() - 1].verse(), "Licence");
+        assert_eq!(
+            slides[slides.len() - 1].text(),
+            format!(
+                "{}\nNorth Point Worship\n© 2017 North Point Worship Publishing\nCCLI Song # 7016417",
+                song.title
+            )
+        );
+
+        song.credits = Some(SongCredits {
+            title_slide: false,
+            licence: Licence::Overlay,
+        });
+        let slides = song.to_slides()?;
+        assert_eq!(slides.len(), lyric_slides);
+        assert_eq!(
+            slides[slides.len() - 1].footer(),
+            "© 2017 North Point Worship Publishing | CCLI Song # 7016417"
+        );
+        assert!(!slides[slides.len() - 1].text().contains("CCLI"));
+
+        song.credits = None;
+        let defaults = CreditDefaults {
+            credits: SongCredits {
+                title_slide: false,
+                licence: Licence::Slide,
+            },
+            ccli_licence: Some("1234567".into()),
+        };
+        let slides = song.slides(&defaults, &fontdb::Database::new())?;
+        assert_eq!(slides.len(), lyric_slides + 1);
+        assert!(
+            slides[slides.len() - 1]
+                .text()
+                .ends_with("CCLI License # 1234567")
+        );
+
+        song.songbooks = vec![Songbook {
+            name: "Worship Together".into(),
+            entry: Some("42".into()),
+        }];
+        let slides = song.slides(&defaults, &fontdb::Database::new())?;
+        assert!(
+            slides[slides.len() - 1]
+                .text()
+                .contains("Publishing\nWorship Together #42\nCCLI Song")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_slide_fit() -> Result<()> {
+        let mut song = Song {
+            title: "Split".into(),
+            verses: Some(vec![VerseName::Verse { number: 1 }]),
+            verse_map: Some(HashMap::from([(
+                VerseName::Verse { number: 1 },
+                "one\ntwo\nthree\nfour\nfive\nsix\n\nseven".to_string(),
+            )])),
+            ..Default::default()
+        };
+        assert_eq!(song.to_slides()?.len(), 2);
+
+        song.slide_fit = Some(SlideFit::MaxLines(4));
+        let slides = song.to_slides()?;
+        assert_eq!(
+            slides.iter().map(Slide::text).collect::<Vec<String>>(),
+            vec!["one\ntwo\nthree", "four\nfive\nsix", "seven"]
+        );
+
+        song.pin_breaks(&fontdb::Database::new());
+        assert_eq!(
+            song.get_lyric(&VerseName::Verse { number: 1 }).as_deref(),
+            Some("one\ntwo\nthree\n\nfour\nfive\nsix\n\nseven")
+        );
+        song.slide_fit = None;
+        assert_eq!(song.to_slides()?.len(), 3);
+
+        song.slide_fit = Some(SlideFit::Fit);
+        song.set_lyrics(
+            &VerseName::Verse { number: 1 },
+            "Amazing grace how sweet the sound that saved a wretch like me",
+        );
+        let slides = song.to_slides()?;
+        assert_eq!(slides.len(), 1);
+        assert!(slides[0].text().lines().count() > 1);
+        Ok(())
+    }
+
     // extern crate test;
     // use test::{Bencher, black_box};
 
@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use miette::{IntoDiagnostic, Result, miette};
+
+use crate::core::songs::{Song, VerseName};
+
+pub fn song_from_file(path: impl AsRef<Path>) -> Result<Song> {
+    let path = path.as_ref();
+    let text = fs::read_to_string(path).into_diagnostic()?;
+    if path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("usr"))
+    {
+        song_from_usr(&text)
+    } else {
+        song_from_txt(&text)
+    }
+}
+
+fn song_from_sections(
+    mut song: Song,
+    sections: Vec<(VerseName, String)>,
+) -> Result<Song> {
+    let mut verse_map: HashMap<VerseName, String> = HashMap::new();
+    let mut verses = vec![];
+    for (mut name, lyrics) in sections {
+        let lyrics = lyrics.trim().to_string();
+        if lyrics.is_empty() {
+            continue;
+        }
+        while verse_map
+            .get(&name)
+            .is_some_and(|existing| *existing != lyrics)
+        {
+            name = name.next();
+        }
+        verse_map.insert(name, lyrics);
+        verses.push(name);
+    }
+    if verse_map.is_empty() {
+        return Err(miette!("{} doesn't have any lyrics", song.title));
+    }
+    song.lyrics = ron::ser::to_string(&Some(&verse_map)).ok();
+    song.verse_order = Some(verses.iter().map(VerseName::get_name).collect());
+    song.verses = Some(verses);
+    song.verse_map = Some(verse_map);
+    Ok(song)
+}
+
+fn authors(authors: &str) -> Option<String> {
+    let authors = authors
+        .split('|')
+        .map(str::trim)
+        .filter(|author| !author.is_empty())
+        .collect::<Vec<&str>>()
+        .join(", ");
+    (!authors.is_empty()).then_some(authors)
+}
+
+/// The usr format is an ini file where the sections and their lines are
+/// separated by `/t` and `/n`, the CCLI number is in the header like
+/// `[S A4755360]`.
+pub fn song_from_usr(text: &str) -> Result<Song> {
+    let mut song = Song::default();
+    let mut fields: Vec<&str> = vec![];
+    let mut words: Vec<&str> = vec![];
+    for line in text.lines().map(str::trim) {
+        if let Some(header) = line.strip_prefix("[S ") {
+            let ccli: String = header.chars().filter(char::is_ascii_digit).collect();
+            song.ccli = (!ccli.is_empty()).then_some(ccli);
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "Title" => song.title = value.to_string(),
+            "Author" => song.author = authors(value),
+            "Copyright" => {
+                song.copyright = (!value.is_empty()).then(|| value.replace(" | ", ", "));
+            }
+            "Fields" => fields = value.split("/t").collect(),
+            "Words" => words = value.split("/t").collect(),
+            _ => (),
+        }
+    }
+    if song.title.is_empty() {
+        return Err(miette!("This SongSelect file doesn't have a title"));
+    }
+
+    let sections = fields
+        .iter()
+        .zip(words)
+        .map(|(field, words)| {
+            let name =
+                VerseName::from_label(field).unwrap_or(VerseName::Other { number: 1 });
+            let lyrics = words
+                .split("/n")
+                .map(str::trim)
+                .collect::<Vec<&str>>()
+                .join("\n");
+            (name, lyrics)
+        })
+        .collect();
+    song_from_sections(song, sections)
+}
+
+/// The txt format is the title, then each section under its label and a
+/// footer that starts with the CCLI number.
+pub fn song_from_txt(text: &str) -> Result<Song> {
+    let Some(footer_start) = text.find("CCLI Song #") else {
+        return Err(miette!("This isn't a SongSelect lyrics file"));
+    };
+    let (body, footer) = text.split_at(footer_start);
+    let mut lines = body
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| line.is_empty());
+
+    let mut song = Song {
+        title: lines
+            .next()
+            .ok_or_else(|| miette!("This SongSelect file doesn't have a title"))?
+            .to_string(),
+        ..Default::default()
+    };
+
+    let mut sections: Vec<(VerseName, String)> = vec![];
+    for line in lines {
+        if let Some(name) = VerseName::from_label(line) {
+            sections.push((name, String::new()));
+        } else if let Some((_, lyrics)) = sections.last_mut() {
+            lyrics.push_str(line);
+            lyrics.push('\n');
+        } else if !line.is_empty() {
+            sections.push((VerseName::Verse { number: 1 }, format!("{line}\n")));
+        }
+    }
+    let sections = sections
+        .into_iter()
+        .map(|(name, lyrics)| {
+            let lyrics = lyrics
+                .split("\n\n")
+                .map(str::trim)
+                .filter(|lines| !lines.is_empty())
+                .collect::<Vec<&str>>()
+                .join("\n\n");
+            (name, lyrics)
+        })
+        .collect();
+
+    let mut footer = footer
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty());
+    if let Some(ccli) = footer.next() {
+        let ccli: String = ccli.chars().filter(char::is_ascii_digit).collect();
+        song.ccli = (!ccli.is_empty()).then_some(ccli);
+    }
+    for line in footer {
+        if line.starts_with("For use solely") || line.starts_with("CCLI License") {
+            continue;
+        } else if line.starts_with('©') || line.starts_with("Copyright") {
+            song.copyright = Some(line.replace(" | ", ", "));
+        } else if song.author.is_none() {
+            song.author = authors(line);
+        }
+    }
+
+    song_from_sections(song, sections)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_songselect_txt() -> Result<()> {
+        let text = "Death Was Arrested
+
+Verse 1
+Alone in my sorrow
+And dead in my sin
+
+Lost without hope
+With no place to begin
+
+Chorus
+Oh Your grace so free
+Washes over me
+
+Verse 2
+Ash was redeemed
+Only beauty remains
+
+Chorus
+Oh Your grace so free
+Washes over me
+
+CCLI Song # 7016417
+Adam Kersh | Brandon Coker | Heath Balltzglier | Paul Taylor
+© 2017 North Point Worship Publishing
+For use solely with the SongSelect® Terms of Use. All rights reserved. www.ccli.com
+CCLI License # 123456
+";
+        let song = song_from_txt(text)?;
+        assert_eq!(song.title, "Death Was Arrested");
+        assert_eq!(song.ccli.as_deref(), Some("7016417"));
+        assert_eq!(
+            song.author.as_deref(),
+            Some("Adam Kersh, Brandon Coker, Heath Balltzglier, Paul Taylor")
+        );
+        assert_eq!(
+            song.copyright.as_deref(),
+            Some("© 2017 North Point Worship Publishing")
+        );
+        assert_eq!(
+            song.verses,
+            Some(vec![
+                VerseName::Verse { number: 1 },
+                VerseName::Chorus { number: 1 },
+                VerseName::Verse { number: 2 },
+                VerseName::Chorus { number: 1 },
+            ])
+        );
+        assert_eq!(
+            song.get_lyric(&VerseName::Verse { number: 1 }).as_deref(),
+            Some(
+                "Alone in my sorrow\nAnd dead in my sin\n\nLost without hope\nWith no place to begin"
+            )
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_songselect_usr() -> Result<()> {
+        let text = "[File]
+Type=SongSelect Import File
+Version=3.0
+[S A4755360]
+Title=Amazing Grace
+Author=John Newton | Chris Tomlin
+Copyright=Public Domain
+Keys=G
+Fields=Verse 1/tChorus/tVerse 2
+Words=Amazing grace how sweet the sound/nThat saved a wretch like me/tMy chains are gone/tTwas grace that taught/nMy heart to fear
+";
+        let song = song_from_usr(text)?;
+        assert_eq!(song.title, "Amazing Grace");
+        assert_eq!(song.ccli.as_deref(), Some("4755360"));
+        assert_eq!(song.author.as_deref(), Some("John Newton, Chris Tomlin"));
+        assert_eq!(song.copyright.as_deref(), Some("Public Domain"));
+        assert_eq!(
+            song.get_lyric(&VerseName::Verse { number: 2 }).as_deref(),
+            Some("Twas grace that taught\nMy heart to fear")
+        );
+        assert_eq!(
+            song.get_lyric(&VerseName::Chorus { number: 1 }).as_deref(),
+            Some("My chains are gone")
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,148 @@
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use miette::{IntoDiagnostic, Result};
+use sqlx::types::chrono::{Local, NaiveDate};
+use sqlx::{Row, SqlitePool, query};
+use tracing::debug;
+
+use crate::core::songs::Song;
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SongUsage {
+    pub song_id: Option<i32>,
+    pub title: String,
+    pub ccli: Option<String>,
+    pub count: i64,
+}
+
+/// Records that a song was projected, called when a song item goes live.
+/// A song is only counted once a day for each service, so going back to
+/// it or reloading the service doesn't count it again.
+pub async fn log_song_usage(
+    song: Song,
+    service_file: Option<PathBuf>,
+    db: Arc<SqlitePool>,
+) -> Result<()> {
+    let used_at = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    let service_file = service_file.map(|file| file.to_string_lossy().to_string());
+    let song_id = (song.id != 0).then_some(song.id);
+    debug!(title = %song.title, %used_at, "logging song usage");
+    let logged = query("INSERT INTO song_usage (song_id, title, ccli, used_at, service_file) SELECT $1, $2, $3, $4, $5 WHERE NOT EXISTS (SELECT 1 FROM song_usage WHERE COALESCE(ccli, song_id, title) IS COALESCE($3, $1, $2) AND service_file IS $5 AND date(used_at) = date($4))")
+        .bind(song_id)
+        .bind(&song.title)
+        .bind(&song.ccli)
+        .bind(&used_at)
+        .bind(&service_file)
+        .execute(&*db)
+        .await
+        .into_diagnostic()?;
+    if logged.rows_affected() == 0 {
+        debug!(title = %song.title, "song already logged for this service today");
+    }
+    Ok(())
+}
+
+/// Counts how many times each song was used between the two dates,
+/// including both of them.
+pub async fn usage_report(
+    from: NaiveDate,
+    to: NaiveDate,
+    db: Arc<SqlitePool>,
+) -> Result<Vec<SongUsage>> {
+    let rows = query("SELECT song_id, MAX(title) AS title, ccli, COUNT(*) AS count FROM song_usage WHERE date(used_at) BETWEEN $1 AND $2 GROUP BY COALESCE(ccli, song_id, title) ORDER BY count DESC, title")
+        .bind(from.format(DATE_FORMAT).to_string())
+        .bind(to.format(DATE_FORMAT).to_string())
+        .fetch_all(&*db)
+        .await
+        .into_diagnostic()?;
+    rows.iter()
+        .map(|row| {
+            Ok(SongUsage {
+                song_id: row.try_get("song_id").into_diagnostic()?,
+                title: row.try_get("title").into_diagnostic()?,
+                ccli: row.try_get("ccli").into_diagnostic()?,
+                count: row.try_get("count").into_diagnostic()?,
+            })
+        })
+        .collect()
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// The columns follow CCLI's reporting form where projecting lyrics
+/// counts as a digital use.
+#[must_use]
+pub fn report_csv(report: &[SongUsage]) -> String {
+    let mut csv = String::from("CCLI Song #,Song Title,Digital,Print,Record,Translate\n");
+    for usage in report {
+        let _ = writeln!(
+            csv,
+            "{},{},{},0,0,0",
+            csv_field(usage.ccli.as_deref().unwrap_or_default()),
+            csv_field(&usage.title),
+            usage.count
+        );
+    }
+    csv
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use sqlx::migrate;
+
+    #[tokio::test]
+    async fn test_usage_report() -> Result<()> {
+        let db = Arc::new(
+            SqlitePool::connect("sqlite::memory:")
+                .await
+                .into_diagnostic()?,
+        );
+        migrate!().run(&*db).await.into_diagnostic()?;
+
+        let grace = Song {
+            id: 1,
+            title: "Amazing Grace".into(),
+            ccli: Some("4755360".into()),
+            ..Default::default()
+        };
+        let arrested = Song {
+            id: 2,
+            title: "Death Was Arrested, Live".into(),
+            ccli: Some("7016417".into()),
+            ..Default::default()
+        };
+        log_song_usage(grace.clone(), None, Arc::clone(&db)).await?;
+        log_song_usage(grace.clone(), Some("service.lisp".into()), Arc::clone(&db))
+            .await?;
+        log_song_usage(arrested, Some("service.lisp".into()), Arc::clone(&db)).await?;
+        // Going back to a song already shown in the same service
+        log_song_usage(grace, Some("service.lisp".into()), Arc::clone(&db)).await?;
+
+        let today = Local::now().date_naive();
+        let report = usage_report(today, today, Arc::clone(&db)).await?;
+        assert_eq!(report.len(), 2);
+        assert_eq!(report[0].title, "Amazing Grace");
+        assert_eq!(report[0].count, 2);
+
+        let yesterday = today.pred_opt().unwrap_or(today);
+        assert!(usage_report(yesterday, yesterday, db).await?.is_empty());
+
+        assert_eq!(
+            report_csv(&report),
+            "CCLI Song #,Song Title,Digital,Print,Record,Translate\n4755360,Amazing Grace,2,0,0,0\n7016417,\"Death Was Arrested, Live\",1,0,0,0\n"
+        );
+        Ok(())
+    }
+}
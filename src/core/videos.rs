@@ -1,4 +1,5 @@
 use crate::core::model::{Sort, SortDirection};
+use crate::lisp::{self, Form};
 use crate::{Background, SlideBuilder, TextAlignment};
 
 use super::content::Content;
@@ -6,7 +7,7 @@ use super::kinds::ServiceItemKind;
 use super::model::{LibraryKind, Model};
 use super::service_items::ServiceTrait;
 use super::slide::Slide;
-use crisp::types::{Keyword, Symbol, Value};
+use crisp::types::{Keyword, Value};
 use itertools::Itertools;
 use miette::{IntoDiagnostic, Result, miette};
 use serde::{Deserialize, Serialize};
@@ -32,8 +33,30 @@ pub struct Video {
 }
 
 impl From<&Video> for Value {
-    fn from(_value: &Video) -> Self {
-        Self::List(vec![Self::Symbol(Symbol("video".into()))])
+    fn from(value: &Video) -> Self {
+        value.lisp_form().into()
+    }
+}
+
+impl Video {
+    #[must_use]
+    pub fn lisp_form(&self) -> Form {
+        let mut form = Form::new("video")
+            .string("source", self.path.display().to_string())
+            .string("title", &self.title);
+        if self.id != 0 {
+            form = form.atom("id", self.id);
+        }
+        if let Some(start_time) = self.start_time {
+            form = form.atom("start-time", start_time);
+        }
+        if let Some(end_time) = self.end_time {
+            form = form.atom("end-time", end_time);
+        }
+        if self.looping {
+            form = form.atom("loop", true);
+        }
+        form
     }
 }
 
@@ -102,30 +125,21 @@ impl From<&Value> for Video {
                     .position(|v| v == &Value::Keyword(Keyword::from("source")))
                     .and_then(|path_pos| {
                         let pos = path_pos + 1;
-                        list.get(pos).map(|p| PathBuf::from(String::from(p)))
+                        list.get(pos).map(|p| PathBuf::from(lisp::string(p)))
                     });
 
-                let title = path.clone().map(|p| {
-                    let path = p.to_str().unwrap_or_default().to_string();
-                    let title = path.rsplit_once('/').unwrap_or_default().1;
-                    title.to_string()
-                });
-
-                let start_time = list
-                    .iter()
-                    .position(|v| v == &Value::Keyword(Keyword::from("start-time")))
-                    .and_then(|start_pos| {
-                        let pos = start_pos + 1;
-                        list.get(pos).map(|p| i32::from(p) as f32)
+                let title =
+                    lisp::keyword(list, "title").map(lisp::string).or_else(|| {
+                        path.clone().map(|p| {
+                            let path = p.to_str().unwrap_or_default().to_string();
+                            let title = path.rsplit_once('/').unwrap_or_default().1;
+                            title.to_string()
+                        })
                     });
 
-                let end_time = list
-                    .iter()
-                    .position(|v| v == &Value::Keyword(Keyword::from("end-time")))
-                    .and_then(|end_pos| {
-                        let pos = end_pos + 1;
-                        list.get(pos).map(|p| i32::from(p) as f32)
-                    });
+                let start_time = lisp::keyword(list, "start-time").map(lisp::float);
+
+                let end_time = lisp::keyword(list, "end-time").map(lisp::float);
 
                 let looping = list
                     .iter()
@@ -136,6 +150,7 @@ impl From<&Value> for Video {
                     });
 
                 Self {
+                    id: lisp::keyword(list, "id").map(i32::from).unwrap_or_default(),
                     title: title.unwrap_or_default(),
                     path: path.unwrap_or_default(),
                     start_time,
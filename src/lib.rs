@@ -2,13 +2,21 @@
 #![allow(clippy::missing_panics_doc)]
 #![allow(clippy::missing_errors_doc)]
 
+pub mod cli;
 pub mod core;
-// pub mod lisp;
+pub mod lisp;
+pub mod markdown;
+pub mod midi;
+pub mod org;
+pub mod remote;
+pub mod source;
 pub mod ui;
 
 use clap::{Args, Parser, Subcommand};
 use core::service_items::ServiceItem;
-use core::slide::{Background, BackgroundKind, Slide, SlideBuilder, TextAlignment};
+use core::slide::{
+    Background, BackgroundKind, Slide, SlideBuilder, TextAlignment, TextMargins,
+};
 use cosmic::app::{Core, Settings, Task};
 use cosmic::cosmic_config::{Config, ConfigSet, CosmicConfigEntry};
 use cosmic::dialog::file_chooser::{open, save};
@@ -23,14 +31,13 @@ use cosmic::iced::{
 };
 use cosmic::widget::dnd_destination::dnd_destination;
 use cosmic::widget::image::Handle;
-use cosmic::widget::menu::key_bind::Modifier;
 use cosmic::widget::menu::{ItemWidth, KeyBind};
 use cosmic::widget::nav_bar::nav_bar_style;
 use cosmic::widget::space::{self, horizontal};
 use cosmic::widget::{
-    Container, Space, button, container, divider, icon, menu, mouse_area, nav_bar,
-    nav_bar_toggle, popover, responsive, scrollable, search_input, settings, slider,
-    text, text_input, tooltip,
+    Container, Space, button, container, divider, dropdown, icon, menu, mouse_area,
+    nav_bar, nav_bar_toggle, popover, responsive, scrollable, search_input, settings,
+    slider, text, text_editor, text_input, tooltip,
 };
 use cosmic::{
     Application, ApplicationExt, Apply, Element, cosmic_config, executor, theme,
@@ -54,12 +61,19 @@ use ui::song_editor::{self, SongEditor};
 
 use core::content::Content;
 use core::file;
+use core::keymap::{Chord, Command, Keymap, Scope};
 use core::kinds::ServiceItemKind;
+use core::midi::MidiControl;
 use core::model::KindWrapper;
+use core::settings::{KeyColor, LowerThird, RemoteControl};
+use core::songs::{CreditDefaults, SongCredits};
+use core::usage;
 use ui::gst_video;
 use ui::image_editor::{self, ImageEditor};
+use ui::lower_third;
 use ui::presentation_editor::{self, PresentationEditor};
-use ui::text_svg::{self};
+use ui::stage_display::{self, StageDisplay};
+use ui::text_svg::{self, TextSvg};
 use ui::video_editor::{self, VideoEditor};
 use ui::widgets::draggable;
 
@@ -76,6 +90,30 @@ struct Cli {
 #[command(version, about, long_about = None)]
 enum Commands {
     Cli(CliCommand),
+    Import(ImportCommand),
+    Report(ReportCommand),
+}
+
+#[derive(Args, Debug, Clone)]
+#[command(about = "Import songs into the library", long_about = None)]
+struct ImportCommand {
+    /// OpenLyrics, ChordPro, SongSelect or OpenLP songs.sqlite files to import
+    #[arg(required = true)]
+    files: Vec<PathBuf>,
+}
+
+#[derive(Args, Debug, Clone)]
+#[command(about = "Report how many times each song was used for CCLI", long_about = None)]
+struct ReportCommand {
+    /// The first day of the report, like 2026-01-01
+    #[arg(long)]
+    from: sqlx::types::chrono::NaiveDate,
+    /// The last day of the report, defaults to today
+    #[arg(long)]
+    to: Option<sqlx::types::chrono::NaiveDate>,
+    /// Write the report as a csv for CCLI instead of printing it
+    #[arg(long)]
+    csv: Option<PathBuf>,
 }
 
 #[derive(Args, Debug, Clone)]
@@ -83,6 +121,19 @@ enum Commands {
 struct CliCommand {
     #[arg(short, long)]
     watch: bool,
+    /// Render every slide to a png in this directory
+    #[arg(short, long)]
+    render: Option<PathBuf>,
+    /// Export the service to a pdf with one slide per page
+    #[arg(short, long)]
+    pdf: Option<PathBuf>,
+    /// Only print the titles and text of each item in the pdf
+    #[arg(short, long, requires = "pdf")]
+    notes: bool,
+    #[arg(long, default_value_t = 1920)]
+    width: u32,
+    #[arg(long, default_value_t = 1080)]
+    height: u32,
     file: Option<PathBuf>,
 }
 
@@ -111,6 +162,14 @@ pub fn run() -> Result<()> {
         .with_timer(timer)
         .init();
 
+    if let Some(Commands::Import(ImportCommand { files })) = &args.command {
+        return cli::import(files);
+    }
+
+    if let Some(Commands::Report(ReportCommand { from, to, csv })) = &args.command {
+        return cli::report(*from, *to, csv.as_deref());
+    }
+
     let (config_handler, config) =
         match cosmic_config::Config::new(App::APP_ID, core::settings::SETTINGS_VERSION) {
             Ok(config_handler) => {
@@ -128,6 +187,41 @@ pub fn run() -> Result<()> {
                 (None, core::settings::Settings::default())
             }
         };
+
+    if let Some(Commands::Cli(CliCommand {
+        watch: false,
+        render,
+        pdf,
+        notes,
+        width,
+        height,
+        file: Some(file),
+    })) = &args.command
+    {
+        let credits = config.credit_defaults();
+        let mut fontdb = fontdb::Database::new();
+        fontdb.load_system_fonts();
+        let fontdb = Arc::new(fontdb);
+        if let Some(pdf) = pdf {
+            let layout = if *notes {
+                core::pdf_export::PdfLayout::Notes
+            } else {
+                core::pdf_export::PdfLayout::Slides
+            };
+            cli::export_pdf(file, pdf, layout, *width, *height, &credits, &fontdb)?;
+        }
+        return render.as_ref().map_or_else(
+            || {
+                if pdf.is_some() {
+                    Ok(())
+                } else {
+                    cli::check(file, &credits, &fontdb)
+                }
+            },
+            |directory| cli::render(file, directory, *width, *height, &credits, &fontdb),
+        );
+    }
+
     let (state_handler, state) = match cosmic_config::Config::new_state(
         App::APP_ID,
         core::settings::SETTINGS_VERSION,
@@ -150,7 +244,7 @@ pub fn run() -> Result<()> {
     };
 
     let settings = if args.command.is_some_and(|command| match command {
-        Commands::Cli(_) => true,
+        Commands::Cli(_) | Commands::Import(_) | Commands::Report(_) => true,
     }) {
         debug!("window view");
         Settings::default()
@@ -198,6 +292,10 @@ struct App {
     library_dragged_item: Option<ServiceItem>,
     fontdb: Arc<fontdb::Database>,
     menu_keys: HashMap<KeyBind, MenuAction>,
+    rebinding: Option<Command>,
+    /// Keys a focused text input took, so letting them go doesn't run a
+    /// shortcut.
+    typed_keys: Vec<Key>,
     context_menu: Option<usize>,
     modifiers_pressed: Option<Modifiers>,
     loading_state: LoadingState,
@@ -209,8 +307,23 @@ struct App {
     obs_connection: String,
     view_mode: ViewMode,
     genius_token_hidden: bool,
+    lower_third_window: Option<window::Id>,
+    lower_third_text: Option<TextSvg>,
+    lower_third_source: String,
+    /// The notes of the live item and slide, kept in step with them by
+    /// [`App::update_notes`].
+    item_notes: text_editor::Content,
+    slide_notes: text_editor::Content,
+    stage_display: Option<StageDisplay>,
+    remote: Option<remote::Remote>,
+    remote_port: String,
+    midi: Option<midi::Midi>,
+    midi_learning: bool,
+    midi_action: usize,
+    midi_verse: String,
     hovered_point: iced::Point,
     context_point: iced::Point,
+    watched: Option<cli::WatchedService>,
 }
 
 #[derive(Debug, Clone)]
@@ -241,6 +354,7 @@ enum Message {
     LibraryToggle,
     Quit,
     Key(Key, Modifiers),
+    KeyTyped(Key),
     Tick(Instant),
     None,
     EditorToggle(bool),
@@ -252,6 +366,7 @@ enum Message {
     AddServiceItem(usize, ServiceItem),
     AddServiceItemKind(usize, KindWrapper),
     AddServiceItemsFiles(usize, Vec<ServiceItem>),
+    DropFiles(usize, Vec<PathBuf>),
     RemoveServiceItem(usize),
     AddServiceItemDrop(usize),
     AppendServiceItem(ServiceItem),
@@ -280,6 +395,33 @@ enum Message {
     ViewModeSwitch(ViewMode),
     ShowGeniusToken,
     SetGeniusToken(String),
+    SetSongCredits(usize),
+    SetCcliLicence(String),
+    ToggleLowerThird,
+    UpdateLowerThird(Option<TextSvg>),
+    SetLowerThirdBackground(usize),
+    SetLowerThirdFontSize(String),
+    SetLowerThirdArea(usize),
+    ToggleStageDisplay,
+    StageDisplay(stage_display::Message),
+    SetRemoteEnabled(bool),
+    EditRemotePort(String),
+    SetRemotePort(String),
+    SetRemoteLan(bool),
+    NewRemotePin,
+    ChooseLogo,
+    SetLogo(Option<PathBuf>),
+    Rebind(Option<Command>),
+    ClearKeys(Command),
+    ResetKeymap,
+    SetMidiEnabled(bool),
+    Midi(core::midi::Event),
+    SetMidiAction(usize),
+    EditMidiVerse(String),
+    LearnMidi(bool),
+    RemoveMidiBinding(usize),
+    EditItemNotes(text_editor::Action),
+    EditSlideNotes(text_editor::Action),
     InsertBackgroundImage((iced::core::image::Allocation, usize)),
     InsertThumbnail((iced::core::image::Allocation, usize)),
     ClearFooterMsg,
@@ -288,6 +430,8 @@ enum Message {
     AddSlideTextToItem(usize, usize, Slide),
     LoadedOpenItem(usize),
     HideLoadingBar,
+    CheckWatchedFiles,
+    ReloadService(Vec<ServiceItem>),
 }
 
 #[allow(dead_code)]
@@ -306,6 +450,8 @@ enum MenuAction {
     SaveAs,
     Open,
     OpenSettings,
+    ToggleLowerThird,
+    ToggleStageDisplay,
     DeleteItem(usize),
 }
 
@@ -319,6 +465,8 @@ impl menu::Action for MenuAction {
             Self::SaveAs => Message::SaveAsDialog,
             Self::Open => Message::Open,
             Self::OpenSettings => Message::OpenSettings,
+            Self::ToggleLowerThird => Message::ToggleLowerThird,
+            Self::ToggleStageDisplay => Message::ToggleStageDisplay,
             Self::DeleteItem(index) => Message::RemoveServiceItem(*index),
         }
     }
@@ -326,6 +474,29 @@ impl menu::Action for MenuAction {
 
 const HEADER_SPACE: u16 = 6;
 
+const LOWER_THIRD_AREAS: [(TextMargins, TextAlignment); 3] = [
+    (TextMargins::LOWER_THIRD, TextAlignment::BottomCenter),
+    (
+        TextMargins {
+            left: 5,
+            top: 5,
+            right: 5,
+            bottom: 66,
+        },
+        TextAlignment::TopCenter,
+    ),
+    (
+        TextMargins {
+            left: 0,
+            top: 0,
+            right: 0,
+            bottom: 0,
+        },
+        TextAlignment::MiddleCenter,
+    ),
+];
+const LOWER_THIRD_AREA_LABELS: [&str; 3] = ["Lower Third", "Upper Third", "Full Screen"];
+
 impl cosmic::Application for App {
     type Executor = executor::multi::Executor;
     type Flags = (
@@ -354,8 +525,14 @@ impl cosmic::Application for App {
         let fontdb = Arc::new(fontdb);
 
         let mut windows = vec![];
-        let cli_mode = input.0.command.is_some_and(|command| match command {
-            Commands::Cli(_cli_command) => true,
+        let cli_mode = input.0.command.is_some();
+        let watched = input.0.command.and_then(|command| match command {
+            Commands::Cli(CliCommand {
+                watch: true,
+                file: Some(file),
+                ..
+            }) => Some(cli::WatchedService::new(file)),
+            Commands::Cli(_) | Commands::Import(_) | Commands::Report(_) => None,
         });
 
         if !cli_mode {
@@ -408,9 +585,11 @@ impl cosmic::Application for App {
         //     .collect();
         let items: Arc<Vec<ServiceItem>> = Arc::new(vec![]);
 
-        let presenter = Presenter::with_items(items.clone());
-        let song_editor =
+        let mut presenter = Presenter::with_items(items.clone());
+        presenter.logo.clone_from(&settings.logo);
+        let mut song_editor =
             SongEditor::new(Arc::clone(&fontdb), settings.genius_token.clone());
+        song_editor.credit_defaults = settings.credit_defaults();
 
         // for item in items.iter() {
         //     nav_model.insert().text(item.title()).data(item.clone());
@@ -428,28 +607,7 @@ impl cosmic::Application for App {
             },
         );
 
-        let mut menu_keys = HashMap::new();
-        menu_keys.insert(
-            KeyBind {
-                modifiers: vec![Modifier::Ctrl],
-                key: Key::Character("s".into()),
-            },
-            MenuAction::Save,
-        );
-        menu_keys.insert(
-            KeyBind {
-                modifiers: vec![Modifier::Ctrl],
-                key: Key::Character("o".into()),
-            },
-            MenuAction::Open,
-        );
-        menu_keys.insert(
-            KeyBind {
-                modifiers: vec![Modifier::Ctrl],
-                key: Key::Character(",".into()),
-            },
-            MenuAction::OpenSettings,
-        );
+        let menu_keys = menu_keys(&settings.keymap);
         // nav_model.activate_position(0);
         let mut app = Self {
             presenter,
@@ -477,6 +635,8 @@ impl cosmic::Application for App {
             library_dragged_item: None,
             fontdb: Arc::clone(&fontdb),
             menu_keys,
+            rebinding: None,
+            typed_keys: vec![],
             hovered_item: None,
             hovered_dnd: None,
             context_menu: None,
@@ -485,6 +645,7 @@ impl cosmic::Application for App {
             modifiers_pressed: None,
             loading_state: LoadingState::None,
             settings_open: false,
+            remote_port: settings.remote_control.port.to_string(),
             settings,
             config_handler,
             state_handler,
@@ -492,6 +653,18 @@ impl cosmic::Application for App {
             obs_connection: String::new(),
             view_mode: ViewMode::Row,
             genius_token_hidden: true,
+            lower_third_window: None,
+            lower_third_text: None,
+            lower_third_source: String::new(),
+            item_notes: text_editor::Content::new(),
+            slide_notes: text_editor::Content::new(),
+            stage_display: None,
+            remote: None,
+            midi: None,
+            midi_learning: false,
+            midi_action: 0,
+            midi_verse: String::new(),
+            watched,
         };
 
         let mut batch = vec![];
@@ -503,10 +676,18 @@ impl cosmic::Application for App {
         } else {
             debug!("main view");
             batch.push(app.update_title());
+            batch.push(app.start_remote());
+            batch.push(app.start_midi());
         }
 
         batch.push(add_library());
-        if let Some(file) = app.state.recent_files.front()
+        if let Some(watched) = &app.watched {
+            batch.push(reload_service(
+                watched.path.clone(),
+                app.settings.credit_defaults(),
+                Arc::clone(&app.fontdb),
+            ));
+        } else if let Some(file) = app.state.recent_files.front()
             && file.exists()
         {
             batch.push(app.update(Message::OpenFile(file.clone())));
@@ -567,11 +748,25 @@ impl cosmic::Application for App {
             ),
             menu::items(
                 &self.menu_keys,
-                vec![menu::Item::Button(
-                    "Open Settings",
-                    Some(icon::from_name("preferences-system-symbolic").into()),
-                    MenuAction::OpenSettings,
-                )],
+                vec![
+                    menu::Item::Button(
+                        "Open Settings",
+                        Some(icon::from_name("preferences-system-symbolic").into()),
+                        MenuAction::OpenSettings,
+                    ),
+                    menu::Item::CheckBox(
+                        "Lower Third Output",
+                        None,
+                        self.lower_third_window.is_some(),
+                        MenuAction::ToggleLowerThird,
+                    ),
+                    menu::Item::CheckBox(
+                        "Stage Display",
+                        None,
+                        self.stage_display.is_some(),
+                        MenuAction::ToggleStageDisplay,
+                    ),
+                ],
             ),
         );
         let menu_bar = menu::bar::<Message>(vec![file_menu, settings_menu])
@@ -606,7 +801,11 @@ impl cosmic::Application for App {
         //     .width(Length::Shrink)
         //     .on_toggle(Message::EditorToggle);
 
-        let presenter_window = self.windows.get(1);
+        let presenter_window = self
+            .windows
+            .iter()
+            .skip(1)
+            .find(|id| !self.is_output_window(**id));
         let text = if self.presentation_open {
             text::body("End Presentation")
         } else {
@@ -789,11 +988,24 @@ impl cosmic::Application for App {
                         iced::Event::InputMethod(_event) => None,
                     }
                 }
-                event::Status::Captured => None,
+                event::Status::Captured => match event {
+                    iced::Event::Keyboard(iced::keyboard::Event::KeyPressed {
+                        key,
+                        ..
+                    }) => Some(Message::KeyTyped(key)),
+                    _ => None,
+                },
             }
         });
 
-        Subscription::batch([time_subscription, event_subscription])
+        let watch_subscription = if self.watched.is_some() {
+            cosmic::iced::time::every(Duration::from_millis(500))
+                .map(|_| Message::CheckWatchedFiles)
+        } else {
+            Subscription::none()
+        };
+
+        Subscription::batch([time_subscription, event_subscription, watch_subscription])
     }
 
     fn context_drawer(
@@ -905,6 +1117,211 @@ impl cosmic::Application for App {
                 .select_on_focus(true)
                 .on_input(Message::SetGeniusToken),
             );
+            let song_credits = settings::item(
+                "Credits",
+                dropdown(
+                    &SongCredits::LABELS,
+                    SongCredits::ALL
+                        .iter()
+                        .position(|credits| *credits == self.settings.song_credits),
+                    Message::SetSongCredits,
+                )
+                .gap(5.0),
+            );
+            let ccli_licence = settings::item(
+                "CCLI Licence",
+                text_input("", self.settings.ccli_licence.clone().unwrap_or_default())
+                    .select_on_focus(true)
+                    .on_input(Message::SetCcliLicence),
+            );
+            let lower_third = self.settings.lower_third;
+            let lower_third_background = settings::item(
+                "Background",
+                dropdown(
+                    &KeyColor::LABELS,
+                    KeyColor::ALL
+                        .iter()
+                        .position(|color| *color == lower_third.background),
+                    Message::SetLowerThirdBackground,
+                )
+                .gap(5.0),
+            );
+            let lower_third_font_size = settings::item(
+                "Font Size",
+                text_input("60", lower_third.font_size.to_string())
+                    .select_on_focus(true)
+                    .on_input(Message::SetLowerThirdFontSize),
+            );
+            let lower_third_area = settings::item(
+                "Text Area",
+                dropdown(
+                    &LOWER_THIRD_AREA_LABELS,
+                    LOWER_THIRD_AREAS.iter().position(|(margins, alignment)| {
+                        *margins == lower_third.margins
+                            && *alignment == lower_third.text_alignment
+                    }),
+                    Message::SetLowerThirdArea,
+                )
+                .gap(5.0),
+            );
+            let remote_control = self.settings.remote_control;
+            let remote_enabled = settings::item::builder("Enabled")
+                .description(self.remote.as_ref().map_or_else(String::new, |remote| {
+                    format!("Listening on {}", remote.address)
+                }))
+                .toggler(remote_control.enabled, Message::SetRemoteEnabled);
+            let remote_port = settings::item(
+                "Port",
+                text_input("7272", &self.remote_port)
+                    .select_on_focus(true)
+                    .on_input(Message::EditRemotePort)
+                    .on_submit(Message::SetRemotePort),
+            );
+            let remote_lan = settings::item::builder("Allow Other Devices")
+                .description("Listen on the local network instead of only this computer")
+                .toggler(remote_control.allow_lan, Message::SetRemoteLan);
+            let remote_pin = settings::item::builder("PIN")
+                .description(if remote_control.pin == 0 {
+                    "Made when the remote control starts".to_string()
+                } else {
+                    format!(
+                        "Other devices enter {} to control the presenter",
+                        remote_control.pin
+                    )
+                })
+                .control(button::standard("New PIN").on_press(Message::NewRemotePin));
+            let logo = settings::item::builder("Logo")
+                .description(self.settings.logo.as_ref().map_or_else(
+                    || "Logo mode shows a black screen".to_string(),
+                    |logo| logo.display().to_string(),
+                ))
+                .control(
+                    row![
+                        button::standard("Choose").on_press(Message::ChooseLogo),
+                        button::standard("Clear").on_press_maybe(
+                            self.settings
+                                .logo
+                                .is_some()
+                                .then_some(Message::SetLogo(None))
+                        ),
+                    ]
+                    .spacing(space_s),
+                );
+            let keymap = &self.settings.keymap;
+            let conflicts =
+                keymap
+                    .conflicts()
+                    .into_iter()
+                    .map(|(chord, first, second)| {
+                        text::body(format!(
+                            "{chord} is used by both {} and {}",
+                            first.label(),
+                            second.label()
+                        ))
+                        .class(theme::Text::Color(Color::from_rgb8(0xe0, 0x1b, 0x24)))
+                    });
+            let keyboard = Command::ALL.iter().fold(
+                conflicts.fold(
+                    settings::section().title("Keyboard Shortcuts"),
+                    |section, conflict| section.add(conflict),
+                ),
+                |section, command| {
+                    let chords = keymap.chords(*command);
+                    let recording = self.rebinding == Some(*command);
+                    let description = if recording {
+                        "Press a key, or Esc to cancel".to_string()
+                    } else if chords.is_empty() {
+                        "Unbound".to_string()
+                    } else {
+                        chords
+                            .iter()
+                            .map(ToString::to_string)
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    };
+                    section.add(
+                        settings::item::builder(command.label())
+                            .description(description)
+                            .control(
+                                row![
+                                    button::standard(if recording {
+                                        "Cancel"
+                                    } else {
+                                        "Add"
+                                    })
+                                    .on_press(
+                                        Message::Rebind((!recording).then_some(*command))
+                                    ),
+                                    button::standard("Clear").on_press_maybe(
+                                        (!chords.is_empty())
+                                            .then_some(Message::ClearKeys(*command))
+                                    ),
+                                ]
+                                .spacing(space_s),
+                            ),
+                    )
+                },
+            );
+            let reset_keymap = settings::item::builder("").control(
+                button::standard("Reset to Defaults").on_press_maybe(
+                    (*keymap != Keymap::default()).then_some(Message::ResetKeymap),
+                ),
+            );
+            let midi_enabled = settings::item::builder("Enabled")
+                .description(self.midi.as_ref().map_or_else(String::new, |midi| {
+                    format!("Listening to {}", midi.ports.join(", "))
+                }))
+                .toggler(self.settings.midi.enabled, Message::SetMidiEnabled);
+            let midi_bindings = self.settings.midi.bindings.iter().enumerate().fold(
+                settings::section().title("MIDI").add(midi_enabled),
+                |section, (index, binding)| {
+                    section.add(
+                        settings::item::builder(binding.trigger.to_string())
+                            .description(binding.action.to_string())
+                            .control(
+                                button::standard("Remove")
+                                    .on_press(Message::RemoveMidiBinding(index)),
+                            ),
+                    )
+                },
+            );
+            let verse_action = self.midi_action >= core::midi::COMMANDS.len();
+            let mut midi_controls = row![
+                dropdown(
+                    &core::midi::ACTION_LABELS,
+                    Some(self.midi_action),
+                    Message::SetMidiAction,
+                )
+                .gap(5.0)
+            ]
+            .spacing(space_s);
+            if verse_action {
+                midi_controls = midi_controls.push(
+                    text_input("Chorus", &self.midi_verse)
+                        .select_on_focus(true)
+                        .on_input(Message::EditMidiVerse)
+                        .width(120),
+                );
+            }
+            midi_controls = midi_controls.push(
+                button::standard(if self.midi_learning {
+                    "Cancel"
+                } else {
+                    "Learn"
+                })
+                .on_press_maybe(
+                    (self.settings.midi.enabled
+                        && (!verse_action || !self.midi_verse.trim().is_empty()))
+                    .then_some(Message::LearnMidi(!self.midi_learning)),
+                ),
+            );
+            let midi_learn = settings::item::builder("New Binding")
+                .description(if self.midi_learning {
+                    "Play a note or press a pedal"
+                } else {
+                    "Choose what to do, then learn the note or pedal"
+                })
+                .control(midi_controls);
             let close_button = icon::from_name("window-close-symbolic")
                 .apply(button::icon)
                 .class(theme::Button::Icon)
@@ -920,12 +1337,30 @@ impl cosmic::Application for App {
                     .add(obs_socket)
                     .add(apply_button),
                 settings::section().title("Genius").add(genius_token),
-                space::vertical(),
+                settings::section()
+                    .title("Songs")
+                    .add(song_credits)
+                    .add(ccli_licence),
+                settings::section()
+                    .title("Lower Third Output")
+                    .add(lower_third_background)
+                    .add(lower_third_font_size)
+                    .add(lower_third_area),
+                settings::section().title("Output").add(logo),
+                settings::section()
+                    .title("Remote Control")
+                    .add(remote_enabled)
+                    .add(remote_port)
+                    .add(remote_lan)
+                    .add(remote_pin),
+                keyboard.add(reset_keymap),
+                midi_bindings.add(midi_learn),
             ]
             .spacing(space_s)
-            .height(Length::Fill)
             .apply(container)
-            .padding(space_xxl);
+            .padding(space_xxl)
+            .apply(scrollable)
+            .height(Length::Fill);
             let settings_container = column![close_button, settings_column]
                 .apply(container)
                 .style(nav_bar_style)
@@ -986,6 +1421,12 @@ impl cosmic::Application for App {
     fn update(&mut self, message: Message) -> Task<Message> {
         match message {
             Message::Key(key, modifiers) => self.process_key_press(key, modifiers),
+            Message::KeyTyped(key) => {
+                if !self.typed_keys.contains(&key) {
+                    self.typed_keys.push(key);
+                }
+                Task::none()
+            }
             Message::SongEditor(message) => {
                 // debug!(?message);
                 match self.song_editor.update(message) {
@@ -1088,12 +1529,44 @@ impl cosmic::Application for App {
                 {
                     video.set_muted(true);
                 }
-                match self.presenter.update(message) {
+                let task = match self.presenter.update(message) {
                     presenter::Action::Task(task) => task.map(|m| {
                         // debug!("Should run future");
                         cosmic::Action::App(Message::Present(m))
                     }),
                     presenter::Action::None => Task::none(),
+                    presenter::Action::SetSlideActions(
+                        item_index,
+                        slide_index,
+                        actions,
+                    ) => {
+                        if let Some(item) =
+                            Arc::make_mut(&mut self.service).get_mut(item_index)
+                        {
+                            if let Some(slide) = item.slides.get_mut(slide_index) {
+                                *slide =
+                                    std::mem::take(slide).set_actions(actions.clone());
+                            }
+                            if let ServiceItemKind::Content(slide) = &mut item.kind
+                                && slide_index == 0
+                            {
+                                *slide =
+                                    std::mem::take(slide).set_actions(actions.clone());
+                            }
+                        }
+                        if (item_index, slide_index)
+                            == (
+                                self.presenter.current_item_index,
+                                self.presenter.current_slide_index,
+                            )
+                        {
+                            self.presenter.current_slide =
+                                std::mem::take(&mut self.presenter.current_slide)
+                                    .set_actions(actions);
+                        }
+                        self.presenter.update_items(Arc::clone(&self.service));
+                        Task::none()
+                    }
                     presenter::Action::ChangeSlide(item_index, slide_index) => {
                         self.current_item = (item_index, slide_index);
                         let action = self.presenter.update(
@@ -1130,13 +1603,37 @@ impl cosmic::Application for App {
                             Task::none()
                         }
                     }
+                };
+                self.update_stage_display();
+                self.update_remote();
+                self.update_notes();
+                let task = Task::batch([task, self.update_lower_third(false)]);
+                if let Some(song) = self.presenter.take_live_song()
+                    && let Some(library) = &self.library
+                {
+                    let db = library.db();
+                    let file = self.file.clone();
+                    let log = Task::perform(
+                        async move { usage::log_song_usage(song, file, db).await },
+                        |res| {
+                            if let Err(e) = res {
+                                error!(?e, "couldn't log song usage");
+                            }
+                            cosmic::Action::App(Message::None)
+                        },
+                    );
+                    return Task::batch([task, log]);
                 }
+                task
             }
             Message::Tick(instant) => {
                 let present_task =
                     self.update(Message::Present(presenter::Message::Tick(instant)));
                 let song_editor_task =
                     self.update(Message::SongEditor(song_editor::Message::Tick(instant)));
+                if let Some(stage_display) = &mut self.stage_display {
+                    stage_display.tick(instant);
+                }
                 Task::batch([present_task, song_editor_task])
             }
             Message::Library(message) => {
@@ -1158,6 +1655,9 @@ impl cosmic::Application for App {
                                 cosmic::Action::App(Message::Library(message))
                             });
                         }
+                        library::Action::Notify(message) => {
+                            self.footer_message = Some(message);
+                        }
                         library::Action::None => return Task::none(),
                         library::Action::OpenItem(Some((kind, index))) => match kind {
                             core::model::LibraryKind::Song => {
@@ -1235,6 +1735,9 @@ impl cosmic::Application for App {
             Message::CloseWindow(id) => id.map_or_else(Task::none, window::close),
             Message::WindowOpened(id) => {
                 debug!(?id, "Window opened");
+                if self.is_output_window(id) {
+                    return Task::none();
+                }
                 // let radii =
                 //     self.core.sync_window_border_radii_to_theme();
                 // self.core.set_sync_window_border_radii_to_theme(true);
@@ -1264,6 +1767,19 @@ impl cosmic::Application for App {
                     return Task::none();
                 };
                 self.windows.remove(window);
+                if self.lower_third_window == Some(id) {
+                    self.lower_third_window = None;
+                    self.lower_third_text = None;
+                    return Task::none();
+                }
+                if self
+                    .stage_display
+                    .as_ref()
+                    .is_some_and(|stage_display| stage_display.window == id)
+                {
+                    self.stage_display = None;
+                    return Task::none();
+                }
                 // This closes the app if using the cli example
                 if self.windows.is_empty() {
                     self.update(Message::Quit)
@@ -1346,6 +1862,8 @@ impl cosmic::Application for App {
                 Task::none()
             }
             Message::AddServiceItem(index, item) => {
+                let item =
+                    item.with_credits(&self.settings.credit_defaults(), &self.fontdb);
                 let mut tasks = Vec::new();
                 if matches!(item.kind, ServiceItemKind::Song(_)) {
                     let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
@@ -1375,6 +1893,7 @@ impl cosmic::Application for App {
 
                 Arc::make_mut(&mut self.service).insert(index, item.clone());
                 self.presenter.update_items(self.service.clone());
+                self.update_notes();
                 self.hovered_dnd = None;
 
                 let Some(first_slide) = item.slides.first() else {
@@ -1484,6 +2003,24 @@ impl cosmic::Application for App {
                 }
                 self.update(Message::AddServiceItem(index, item))
             }
+            Message::DropFiles(index, paths) => {
+                self.hovered_dnd = None;
+                Task::perform(
+                    async move {
+                        let mut items = Vec::new();
+                        for path in paths {
+                            match file::items_from_file(path).await {
+                                Ok(file_items) => items.extend(file_items),
+                                Err(e) => error!(?e),
+                            }
+                        }
+                        items
+                    },
+                    move |items| {
+                        cosmic::Action::App(Message::AddServiceItemsFiles(index, items))
+                    },
+                )
+            }
             Message::AddServiceItemsFiles(index, items) => {
                 self.hovered_dnd = None;
                 let mut tasks = Vec::new();
@@ -1495,6 +2032,7 @@ impl cosmic::Application for App {
             Message::RemoveServiceItem(index) => {
                 Arc::make_mut(&mut self.service).remove(index);
                 self.presenter.update_items(self.service.clone());
+                self.update_notes();
                 Task::none()
             }
             Message::ContextMenuItem(index) => {
@@ -1512,7 +2050,9 @@ impl cosmic::Application for App {
                 }
                 Task::none()
             }
-            Message::AppendServiceItem(mut item) => {
+            Message::AppendServiceItem(item) => {
+                let mut item =
+                    item.with_credits(&self.settings.credit_defaults(), &self.fontdb);
                 if matches!(item.kind, ServiceItemKind::Song(_)) {
                     item.slides = item
                         .slides
@@ -1597,6 +2137,7 @@ impl cosmic::Application for App {
                 }
                 Arc::make_mut(&mut self.service).push(item);
                 self.presenter.update_items(Arc::clone(&self.service));
+                self.update_notes();
                 Task::batch(tasks)
             }
             Message::InsertThumbnail((allocation, item_index)) => {
@@ -1607,6 +2148,7 @@ impl cosmic::Application for App {
                     }
                 }
                 self.presenter.update_items(Arc::clone(&self.service));
+                self.update_notes();
                 Task::none()
             }
             Message::InsertBackgroundImage((allocation, item_index)) => {
@@ -1617,6 +2159,7 @@ impl cosmic::Application for App {
                     }
                 }
                 self.presenter.update_items(Arc::clone(&self.service));
+                self.update_notes();
                 Task::none()
             }
             Message::AppendServiceItemKind(item) => {
@@ -1627,6 +2170,7 @@ impl cosmic::Application for App {
                 let item = Arc::make_mut(&mut self.service).remove(index);
                 Arc::make_mut(&mut self.service).insert(target_index, item);
                 self.presenter.update_items(self.service.clone());
+                self.update_notes();
                 Task::none()
             }
             Message::Search(query) => {
@@ -1720,6 +2264,37 @@ impl cosmic::Application for App {
                     }
                 })
             }
+            Message::CheckWatchedFiles => {
+                let Some(watched) = self.watched.as_mut() else {
+                    return Task::none();
+                };
+                if watched.changed() {
+                    debug!(?watched.path, "service file changed, reloading");
+                    reload_service(
+                        watched.path.clone(),
+                        self.settings.credit_defaults(),
+                        Arc::clone(&self.fontdb),
+                    )
+                } else {
+                    Task::none()
+                }
+            }
+            Message::ReloadService(items) => {
+                self.service = Arc::new(items);
+                let action = self.presenter.reload_items(Arc::clone(&self.service));
+                self.current_item = (
+                    self.presenter.current_item_index,
+                    self.presenter.current_slide_index,
+                );
+                self.update_stage_display();
+                self.update_remote();
+                self.update_notes();
+                if let presenter::Action::Task(task) = action {
+                    task.map(|m| cosmic::Action::App(Message::Present(m)))
+                } else {
+                    Task::none()
+                }
+            }
             Message::OpenLoadItems(items) => {
                 self.loading_state = LoadingState::Loading {
                     total_items: items.len(),
@@ -1728,6 +2303,7 @@ impl cosmic::Application for App {
 
                 self.service = Arc::new(Vec::new());
                 self.presenter.update_items(self.service.clone());
+                self.update_notes();
                 let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
 
                 std::thread::spawn(move || {
@@ -1850,6 +2426,8 @@ impl cosmic::Application for App {
             }
             Message::CloseSettings => {
                 self.settings_open = false;
+                self.rebinding = None;
+                self.midi_learning = false;
                 Task::none()
             }
             Message::SetObsUrl(url) => {
@@ -1876,6 +2454,265 @@ impl cosmic::Application for App {
                 }
                 Task::none()
             }
+            Message::SetSongCredits(index) => {
+                if let Some(credits) = SongCredits::ALL.get(index) {
+                    if let Some(config_handler) = self.config_handler.as_ref()
+                        && let Err(e) =
+                            self.settings.set_song_credits(config_handler, *credits)
+                    {
+                        error!(?e, "Can't write to disk song credits");
+                    }
+                    self.settings.song_credits = *credits;
+                    self.set_credit_defaults();
+                }
+                Task::none()
+            }
+            Message::SetCcliLicence(licence) => {
+                let licence = (!licence.trim().is_empty()).then_some(licence);
+                if let Some(config_handler) = self.config_handler.as_ref()
+                    && let Err(e) = self
+                        .settings
+                        .set_ccli_licence(config_handler, licence.clone())
+                {
+                    error!(?e, "Can't write to disk ccli licence");
+                }
+                self.settings.ccli_licence = licence;
+                self.set_credit_defaults();
+                Task::none()
+            }
+            Message::ToggleLowerThird => {
+                if let Some(id) = self.lower_third_window {
+                    return window::close(id);
+                }
+                let (id, spawn_window) = window::open(window::Settings {
+                    position: Position::Centered,
+                    exit_on_close_request: true,
+                    decorations: false,
+                    transparent: true,
+                    ..Default::default()
+                });
+                self.windows.push(id);
+                self.lower_third_window = Some(id);
+                _ = self.set_window_title("Lumina Lower Third".to_owned(), id);
+                Task::batch([
+                    spawn_window.map(|id| cosmic::Action::App(Message::WindowOpened(id))),
+                    self.update_lower_third(true),
+                ])
+            }
+            Message::ToggleStageDisplay => {
+                if let Some(stage_display) = &self.stage_display {
+                    return window::close(stage_display.window);
+                }
+                let (id, spawn_window) = window::open(window::Settings {
+                    position: Position::Centered,
+                    exit_on_close_request: true,
+                    decorations: false,
+                    ..Default::default()
+                });
+                self.windows.push(id);
+                self.stage_display = Some(StageDisplay::new(id));
+                self.update_stage_display();
+                _ = self.set_window_title("Lumina Stage Display".to_owned(), id);
+                spawn_window.map(|id| cosmic::Action::App(Message::WindowOpened(id)))
+            }
+            Message::StageDisplay(message) => {
+                self.stage_display
+                    .as_mut()
+                    .map_or_else(Task::none, |stage_display| {
+                        stage_display
+                            .update(message)
+                            .map(|m| cosmic::Action::App(Message::StageDisplay(m)))
+                    })
+            }
+            Message::EditItemNotes(action) => {
+                let edited = matches!(action, text_editor::Action::Edit(_));
+                self.item_notes.perform(action);
+                if !edited {
+                    return Task::none();
+                }
+                let notes = ui::editor_text(&self.item_notes);
+                if let Some(item) = Arc::make_mut(&mut self.service)
+                    .get_mut(self.presenter.current_item_index)
+                {
+                    item.notes = notes;
+                }
+                self.presenter.update_items(Arc::clone(&self.service));
+                self.update_notes();
+                Task::none()
+            }
+            Message::EditSlideNotes(action) => {
+                let edited = matches!(action, text_editor::Action::Edit(_));
+                self.slide_notes.perform(action);
+                if !edited {
+                    return Task::none();
+                }
+                let notes = ui::editor_text(&self.slide_notes);
+                let slide_index = self.presenter.current_slide_index;
+                if let Some(item) = Arc::make_mut(&mut self.service)
+                    .get_mut(self.presenter.current_item_index)
+                {
+                    if let Some(slide) = item.slides.get_mut(slide_index) {
+                        *slide = std::mem::take(slide).set_notes(&notes);
+                    }
+                    if let ServiceItemKind::Content(slide) = &mut item.kind
+                        && slide_index == 0
+                    {
+                        *slide = std::mem::take(slide).set_notes(&notes);
+                    }
+                }
+                self.presenter.current_slide =
+                    std::mem::take(&mut self.presenter.current_slide).set_notes(notes);
+                self.presenter.update_items(Arc::clone(&self.service));
+                self.update_notes();
+                Task::none()
+            }
+            Message::UpdateLowerThird(text) => {
+                self.lower_third_text = text;
+                Task::none()
+            }
+            Message::SetLowerThirdBackground(index) => {
+                if let Some(background) = KeyColor::ALL.get(index) {
+                    let lower_third = LowerThird {
+                        background: *background,
+                        ..self.settings.lower_third
+                    };
+                    return self.set_lower_third(lower_third);
+                }
+                Task::none()
+            }
+            Message::SetLowerThirdFontSize(size) => {
+                if let Ok(font_size) = size.parse::<u8>() {
+                    let lower_third = LowerThird {
+                        font_size,
+                        ..self.settings.lower_third
+                    };
+                    return self.set_lower_third(lower_third);
+                }
+                Task::none()
+            }
+            Message::SetLowerThirdArea(index) => {
+                if let Some((margins, text_alignment)) = LOWER_THIRD_AREAS.get(index) {
+                    let lower_third = LowerThird {
+                        margins: *margins,
+                        text_alignment: *text_alignment,
+                        ..self.settings.lower_third
+                    };
+                    return self.set_lower_third(lower_third);
+                }
+                Task::none()
+            }
+            Message::SetRemoteEnabled(enabled) => {
+                self.set_remote_control(RemoteControl {
+                    enabled,
+                    ..self.settings.remote_control
+                })
+            }
+            Message::EditRemotePort(port) => {
+                self.remote_port = port;
+                Task::none()
+            }
+            Message::SetRemotePort(port) => {
+                if let Ok(port) = port.parse::<u16>() {
+                    return self.set_remote_control(RemoteControl {
+                        port,
+                        ..self.settings.remote_control
+                    });
+                }
+                Task::none()
+            }
+            Message::SetRemoteLan(allow_lan) => self.set_remote_control(RemoteControl {
+                allow_lan,
+                ..self.settings.remote_control
+            }),
+            Message::NewRemotePin => self.set_remote_control(RemoteControl {
+                pin: RemoteControl::new_pin(),
+                ..self.settings.remote_control
+            }),
+            Message::ChooseLogo => Task::perform(logo_dialog(), |res| match res {
+                Ok(logo) => cosmic::Action::App(Message::SetLogo(Some(logo))),
+                Err(e) => {
+                    error!(?e, "There was an error choosing a logo");
+                    cosmic::Action::None
+                }
+            }),
+            Message::SetLogo(logo) => {
+                if let Some(config_handler) = self.config_handler.as_ref()
+                    && let Err(e) = self.settings.set_logo(config_handler, logo.clone())
+                {
+                    error!(?e, "Can't write to disk logo");
+                }
+                self.presenter.logo = logo;
+                Task::none()
+            }
+            Message::Rebind(command) => {
+                self.rebinding = command;
+                Task::none()
+            }
+            Message::ClearKeys(command) => {
+                let mut keymap = self.settings.keymap.clone();
+                keymap.clear(command);
+                self.set_keymap(keymap);
+                Task::none()
+            }
+            Message::ResetKeymap => {
+                self.set_keymap(Keymap::default());
+                Task::none()
+            }
+            Message::SetMidiEnabled(enabled) => self.set_midi(MidiControl {
+                enabled,
+                ..self.settings.midi.clone()
+            }),
+            Message::Midi(event) => {
+                if self.midi_learning {
+                    if matches!(
+                        event,
+                        core::midi::Event::Note { .. }
+                            | core::midi::Event::Controller { pressed: true, .. }
+                    ) {
+                        self.midi_learning = false;
+                        let action =
+                            core::midi::COMMANDS.get(self.midi_action).map_or_else(
+                                || {
+                                    core::midi::Action::Verse(
+                                        self.midi_verse.trim().to_string(),
+                                    )
+                                },
+                                |command| core::midi::Action::Command(*command),
+                            );
+                        let mut midi = self.settings.midi.clone();
+                        midi.bind(event.trigger(), action);
+                        return self.set_midi(midi);
+                    }
+                    return Task::none();
+                }
+                match self.settings.midi.action(event).cloned() {
+                    Some(core::midi::Action::Command(command)) => {
+                        self.run_command(command)
+                    }
+                    Some(core::midi::Action::Verse(label)) => self
+                        .update(Message::Present(presenter::Message::JumpToVerse(label))),
+                    None => Task::none(),
+                }
+            }
+            Message::SetMidiAction(index) => {
+                self.midi_action = index;
+                Task::none()
+            }
+            Message::EditMidiVerse(label) => {
+                self.midi_verse = label;
+                Task::none()
+            }
+            Message::LearnMidi(learning) => {
+                self.midi_learning = learning;
+                Task::none()
+            }
+            Message::RemoveMidiBinding(index) => {
+                let mut midi = self.settings.midi.clone();
+                if index < midi.bindings.len() {
+                    midi.bindings.remove(index);
+                }
+                self.set_midi(midi)
+            }
             Message::ShowGeniusToken => {
                 self.genius_token_hidden = !self.genius_token_hidden;
                 Task::none()
@@ -1921,6 +2758,7 @@ impl cosmic::Application for App {
 
         let cosmic::cosmic_theme::Spacing {
             space_none,
+            space_xxs,
             space_s,
             space_l,
             space_xl,
@@ -2019,6 +2857,23 @@ impl cosmic::Application for App {
             );
         }
 
+        if !self.service.is_empty() {
+            slide_preview = slide_preview.push(
+                row![
+                    text_editor(&self.item_notes)
+                        .placeholder("Item notes")
+                        .on_action(Message::EditItemNotes)
+                        .height(100),
+                    text_editor(&self.slide_notes)
+                        .placeholder("Slide notes")
+                        .on_action(Message::EditSlideNotes)
+                        .height(100),
+                ]
+                .spacing(space_s)
+                .padding([space_s, space_none]),
+            );
+        }
+
         let slide_preview = slide_preview.apply(container).center_y(Length::Fill);
 
         let service_list = Container::new(self.service_list())
@@ -2167,10 +3022,22 @@ impl cosmic::Application for App {
             .spacing(space_s)
             .apply(container)
             .padding([space_none, space_none, space_none, space_s]);
+            let mode_buttons = presenter::OutputMode::ALL[1..].iter().map(|mode| {
+                button::standard(mode.label())
+                    .on_press(Message::Present(presenter::Message::ToggleMode(*mode)))
+                    .class(if self.presenter.mode == *mode {
+                        theme::Button::Suggested
+                    } else {
+                        theme::Button::HeaderBar
+                    })
+                    .into()
+            });
             row![
                 grid_button,
                 list_button,
                 space::horizontal(),
+                cosmic::widget::Row::with_children(mode_buttons).spacing(space_xxs),
+                space::horizontal(),
                 preview_size_slider
             ]
             .align_y(Vertical::Center)
@@ -2218,7 +3085,24 @@ impl cosmic::Application for App {
     }
 
     // View for presentation
-    fn view_window(&self, _id: window::Id) -> Element<Message> {
+    fn view_window(&self, id: window::Id) -> Element<Message> {
+        if self.lower_third_window == Some(id) {
+            return lower_third::view(
+                self.lower_third_text.as_ref(),
+                self.settings.lower_third,
+            );
+        }
+        if let Some(stage_display) = &self.stage_display
+            && stage_display.window == id
+        {
+            return stage_display
+                .view(
+                    &self.presenter.current_slide,
+                    self.presenter.upcoming_slide(),
+                    self.presenter.notes(),
+                )
+                .map(Message::StageDisplay);
+        }
         self.presenter.view().map(Message::Present)
     }
 }
@@ -2261,6 +3145,270 @@ where
             .map_or_else(Task::none, |id| self.set_window_title(window_title, id))
     }
 
+    fn update_lower_third(&mut self, force: bool) -> Task<Message> {
+        if self.lower_third_window.is_none() {
+            return Task::none();
+        }
+        // The lower third goes blank along with the slide text.
+        let text = match self.presenter.mode {
+            presenter::OutputMode::Black
+            | presenter::OutputMode::ClearText
+            | presenter::OutputMode::Logo => String::new(),
+            presenter::OutputMode::Live | presenter::OutputMode::Freeze => {
+                self.presenter.current_slide.text()
+            }
+        };
+        if !force && text == self.lower_third_source {
+            return Task::none();
+        }
+        self.lower_third_source.clone_from(&text);
+        let slide = self.presenter.current_slide.clone().set_text(text);
+        let profile = self.settings.lower_third;
+        let fontdb = Arc::clone(&self.fontdb);
+        Task::perform(
+            async move { lower_third::render(&slide, profile, &fontdb) },
+            |text| cosmic::Action::App(Message::UpdateLowerThird(text)),
+        )
+    }
+
+    fn update_stage_display(&mut self) {
+        if let Some(stage_display) = &mut self.stage_display {
+            let presenter = &self.presenter;
+            stage_display.set_position(
+                (presenter.current_item_index, presenter.current_slide_index),
+                || presenter.verse_label(),
+            );
+        }
+    }
+
+    /// Windows that show the service but aren't the main presentation.
+    fn is_output_window(&self, id: window::Id) -> bool {
+        self.lower_third_window == Some(id)
+            || self
+                .stage_display
+                .as_ref()
+                .is_some_and(|stage_display| stage_display.window == id)
+    }
+
+    fn set_lower_third(&mut self, lower_third: LowerThird) -> Task<Message> {
+        if let Some(config_handler) = self.config_handler.as_ref()
+            && let Err(e) = self.settings.set_lower_third(config_handler, lower_third)
+        {
+            error!(?e, "Can't write to disk lower third settings");
+        }
+        self.settings.lower_third = lower_third;
+        self.update_lower_third(true)
+    }
+
+    /// Hands the song credits from the settings to the song editor, which
+    /// builds its own song slides.
+    fn set_credit_defaults(&mut self) {
+        self.song_editor.credit_defaults = self.settings.credit_defaults();
+    }
+
+    fn set_keymap(&mut self, keymap: Keymap) {
+        if let Some(config_handler) = self.config_handler.as_ref()
+            && let Err(e) = self.settings.set_keymap(config_handler, keymap.clone())
+        {
+            error!(?e, "Can't write to disk keymap");
+        }
+        self.menu_keys = menu_keys(&keymap);
+        self.settings.keymap = keymap;
+    }
+
+    /// Binds the next chord pressed to the command being rebound.
+    fn record_chord(&mut self, command: Command, key: &Key, modifiers: Modifiers) {
+        if *key == Key::Named(iced::keyboard::key::Named::Escape) && modifiers.is_empty()
+        {
+            self.rebinding = None;
+            return;
+        }
+        let Some(chord) = Chord::from_key(key, modifiers) else {
+            return;
+        };
+        let mut keymap = self.settings.keymap.clone();
+        if let Some(other) = keymap.conflict(command, &chord) {
+            self.footer_message = Some(format!(
+                "{chord} moved from {} to {}",
+                other.label(),
+                command.label()
+            ));
+        }
+        keymap.bind(command, chord);
+        self.set_keymap(keymap);
+        self.rebinding = None;
+    }
+
+    const fn scope_active(&self, scope: Scope) -> bool {
+        match scope {
+            Scope::Global => true,
+            Scope::Presenter => self.editor_mode.is_none(),
+            Scope::Library => self.library_open,
+            Scope::SongEditor => matches!(self.editor_mode, Some(EditorMode::Song)),
+        }
+    }
+
+    fn run_command(&mut self, command: Command) -> Task<Message> {
+        let message = match command {
+            Command::New => Message::New,
+            Command::Open => Message::Open,
+            Command::Save => Message::Save,
+            Command::SaveAs => Message::SaveAsDialog,
+            Command::OpenSettings => Message::OpenSettings,
+            Command::ToggleLowerThird => Message::ToggleLowerThird,
+            Command::ToggleStageDisplay => Message::ToggleStageDisplay,
+            Command::Search => Message::SearchFocus,
+            Command::NextSlide => Message::Present(presenter::Message::NextSlide),
+            Command::PreviousSlide => Message::Present(presenter::Message::PrevSlide),
+            Command::PlayPauseVideo => {
+                Message::Present(presenter::Message::PlayPauseVideo)
+            }
+            Command::Black => Message::Present(presenter::Message::ToggleMode(
+                presenter::OutputMode::Black,
+            )),
+            Command::ClearText => Message::Present(presenter::Message::ToggleMode(
+                presenter::OutputMode::ClearText,
+            )),
+            Command::Freeze => Message::Present(presenter::Message::ToggleMode(
+                presenter::OutputMode::Freeze,
+            )),
+            Command::Logo => Message::Present(presenter::Message::ToggleMode(
+                presenter::OutputMode::Logo,
+            )),
+            Command::Live if self.presenter.mode != presenter::OutputMode::Live => {
+                Message::Present(presenter::Message::ToggleMode(self.presenter.mode))
+            }
+            Command::Live => return Task::none(),
+            Command::ToggleLibrary => Message::LibraryToggle,
+            Command::AddLibraryItem => Message::Library(library::Message::AddItem),
+            Command::DeleteLibraryItem => Message::Library(library::Message::DeleteItem),
+            Command::ImportSongs => Message::Library(library::Message::ImportSongs),
+            Command::CloseEditor => Message::EditorToggle(false),
+            Command::SearchSongsOnline => {
+                Message::SongEditor(song_editor::Message::ToggleSongDialog)
+            }
+            Command::PlayPauseSongAudio => {
+                Message::SongEditor(song_editor::Message::PlayPauseAudio)
+            }
+        };
+        self.update(message)
+    }
+
+    fn set_remote_control(&mut self, remote_control: RemoteControl) -> Task<Message> {
+        if let Some(config_handler) = self.config_handler.as_ref()
+            && let Err(e) = self
+                .settings
+                .set_remote_control(config_handler, remote_control)
+        {
+            error!(?e, "Can't write to disk remote control settings");
+        }
+        self.settings.remote_control = remote_control;
+        self.start_remote()
+    }
+
+    /// Restarts the remote control server with the current settings and
+    /// forwards what its clients send to the presenter. The first time it
+    /// starts it makes the PIN other devices pair with.
+    fn start_remote(&mut self) -> Task<Message> {
+        self.remote = None;
+        let RemoteControl {
+            enabled,
+            port,
+            allow_lan,
+            pin,
+        } = self.settings.remote_control;
+        if !enabled {
+            return Task::none();
+        }
+        if pin == 0 {
+            return self.set_remote_control(RemoteControl {
+                pin: RemoteControl::new_pin(),
+                ..self.settings.remote_control
+            });
+        }
+        match remote::Remote::start(port, allow_lan, pin) {
+            Ok((remote, commands)) => {
+                self.remote = Some(remote);
+                self.update_remote();
+                Task::stream(tokio_stream::StreamExt::map(
+                    tokio_stream::wrappers::UnboundedReceiverStream::new(commands),
+                    |message| cosmic::Action::App(Message::Present(message)),
+                ))
+            }
+            Err(e) => {
+                error!(?e, "couldn't start the remote control server");
+                self.footer_message = Some(format!("Remote control failed: {e}"));
+                Task::none()
+            }
+        }
+    }
+
+    fn set_midi(&mut self, midi: MidiControl) -> Task<Message> {
+        if let Some(config_handler) = self.config_handler.as_ref()
+            && let Err(e) = self.settings.set_midi(config_handler, midi.clone())
+        {
+            error!(?e, "Can't write to disk midi settings");
+        }
+        let restart = midi.enabled != self.settings.midi.enabled;
+        self.settings.midi = midi;
+        if restart {
+            self.start_midi()
+        } else {
+            Task::none()
+        }
+    }
+
+    /// Reconnects to the MIDI inputs and runs what their bindings map to.
+    fn start_midi(&mut self) -> Task<Message> {
+        self.midi = None;
+        if !self.settings.midi.enabled {
+            self.midi_learning = false;
+            return Task::none();
+        }
+        match midi::Midi::start() {
+            Ok((midi, events)) => {
+                self.midi = Some(midi);
+                Task::stream(tokio_stream::StreamExt::map(
+                    tokio_stream::wrappers::UnboundedReceiverStream::new(events),
+                    |event| cosmic::Action::App(Message::Midi(event)),
+                ))
+            }
+            Err(e) => {
+                error!(?e, "couldn't listen to midi");
+                self.footer_message = Some(format!("MIDI failed: {e}"));
+                Task::none()
+            }
+        }
+    }
+
+    /// Reloads the notes editors when the live item or slide has notes
+    /// they aren't showing, like after moving to another slide.
+    fn update_notes(&mut self) {
+        let item_notes = self
+            .service
+            .get(self.presenter.current_item_index)
+            .map(|item| item.notes.as_str())
+            .unwrap_or_default();
+        if ui::editor_text(&self.item_notes) != item_notes {
+            self.item_notes = text_editor::Content::with_text(item_notes);
+        }
+        let slide_notes = self.presenter.current_slide.notes();
+        if ui::editor_text(&self.slide_notes) != slide_notes {
+            self.slide_notes = text_editor::Content::with_text(&slide_notes);
+        }
+    }
+
+    fn update_remote(&mut self) {
+        if let Some(remote) = &mut self.remote {
+            remote.publish(
+                &self.presenter.service,
+                self.presenter.current_item_index,
+                self.presenter.current_slide_index,
+                self.presenter.mode,
+            );
+        }
+    }
+
     fn show_window(&mut self) -> Task<Message> {
         let (id, spawn_window) = window::open(window::Settings {
             position: Position::Centered,
@@ -2296,9 +3444,10 @@ where
 
     fn process_key_press(&mut self, key: Key, modifiers: Modifiers) -> Task<Message> {
         // debug!(?key, ?modifiers);
-        // if self.editor_mode.is_some() {
-        //     return Task::none();
-        // }
+        if let Some(command) = self.rebinding {
+            self.record_chord(command, &key, modifiers);
+            return Task::none();
+        }
         if self.song_editor.editing() {
             return Task::none();
         }
@@ -2310,58 +3459,17 @@ where
                 _ => return Task::none(),
             }
         }
-        match (key, modifiers) {
-            (Key::Character(k), Modifiers::CTRL) if k == *"s" => {
-                self.update(Message::Save)
-            }
-            (Key::Character(k), Modifiers::CTRL) if k == *"o" => {
-                self.update(Message::Open)
-            }
-            (Key::Character(k), Modifiers::CTRL) if k == *"," => {
-                self.update(Message::OpenSettings)
-            }
-            (Key::Character(k), Modifiers::CTRL) if k == *"k" || k == *"f" => {
-                self.update(Message::SearchFocus)
-            }
-            (Key::Character(k), _) if k == *"/" => self.update(Message::SearchFocus),
-            (Key::Named(iced::keyboard::key::Named::ArrowRight), _) => {
-                if self.editor_mode.is_none() {
-                    self.update(Message::Present(presenter::Message::NextSlide))
-                } else {
-                    Task::none()
-                }
-            }
-            (Key::Named(iced::keyboard::key::Named::ArrowLeft), _) => {
-                if self.editor_mode.is_none() {
-                    self.update(Message::Present(presenter::Message::PrevSlide))
-                } else {
-                    Task::none()
-                }
-            }
-            (Key::Character(k), _) if k == *" " => {
-                if self.editor_mode.is_none() {
-                    self.update(Message::Present(presenter::Message::NextSlide))
-                } else {
-                    Task::none()
-                }
-            }
-            (Key::Character(k), _) if k == *"j" || k == *"l" => {
-                if self.editor_mode.is_none() {
-                    self.update(Message::Present(presenter::Message::NextSlide))
-                } else {
-                    Task::none()
-                }
-            }
-            (Key::Character(k), _) if k == *"k" || k == *"h" => {
-                if self.editor_mode.is_none() {
-                    self.update(Message::Present(presenter::Message::PrevSlide))
-                } else {
-                    Task::none()
-                }
-            }
-            // (Key::Character(k), _) if k == *"q" => self.update(Message::Quit),
-            _ => Task::none(),
+        if let Some(index) = self.typed_keys.iter().position(|typed| *typed == key) {
+            self.typed_keys.swap_remove(index);
+            return Task::none();
         }
+        let Some(chord) = Chord::from_key(&key, modifiers) else {
+            return Task::none();
+        };
+        self.settings
+            .keymap
+            .command(&chord, |scope| self.scope_active(scope))
+            .map_or_else(Task::none, |command| self.run_command(command))
     }
 
     #[allow(clippy::too_many_lines)]
@@ -2490,7 +3598,7 @@ where
                     let Ok(text) = str::from_utf8(&data) else {
                         return Message::None;
                     };
-                    let mut items = Vec::new();
+                    let mut paths = Vec::new();
                     for line in text.lines() {
                         let Ok(url) = url::Url::parse(line) else {
                             error!(?line, "problem parsing this file url");
@@ -2500,13 +3608,9 @@ where
                             error!(?url, "invalid file URL");
                             continue;
                         };
-                        let item = ServiceItem::try_from(path);
-                        match item {
-                            Ok(item) => items.push(item),
-                            Err(e) => error!(?e),
-                        }
+                        paths.push(path);
                     }
-                    Message::AddServiceItemsFiles(index, items)
+                    Message::DropFiles(index, paths)
                 }
                 _ => Message::None,
             })
@@ -2570,7 +3674,7 @@ where
                     let Ok(text) = str::from_utf8(&data) else {
                         return Message::None;
                     };
-                    let mut items = Vec::new();
+                    let mut paths = Vec::new();
                     for line in text.lines() {
                         let Ok(url) = url::Url::parse(line) else {
                             error!(?line, "problem parsing this file url");
@@ -2580,10 +3684,9 @@ where
                             error!(?url, "invalid file URL");
                             continue;
                         };
-                        ServiceItem::try_from(path)
-                            .map_or_else(|e| error!(?e), |item| items.push(item));
+                        paths.push(path);
                     }
-                    Message::AddServiceItemsFiles(last_index, items)
+                    Message::DropFiles(last_index, paths)
                 }
                 _ => Message::None,
             }),
@@ -2609,6 +3712,44 @@ fn add_library() -> Task<Message> {
     })
 }
 
+fn reload_service(
+    path: PathBuf,
+    credits: CreditDefaults,
+    fontdb: Arc<fontdb::Database>,
+) -> Task<Message> {
+    Task::perform(
+        async move {
+            let items = file::load_service_with_library(&path).await?;
+            Ok::<_, miette::Error>(
+                items
+                    .into_par_iter()
+                    .map(|item| {
+                        let mut item = item.with_credits(&credits, &fontdb);
+                        if matches!(item.kind, ServiceItemKind::Song(_)) {
+                            item.slides = item
+                                .slides
+                                .into_iter()
+                                .map(|slide| {
+                                    text_svg::text_svg_generator(slide.clone(), &fontdb)
+                                        .unwrap_or(slide)
+                                })
+                                .collect();
+                        }
+                        item
+                    })
+                    .collect(),
+            )
+        },
+        |res| match res {
+            Ok(items) => cosmic::Action::App(Message::ReloadService(items)),
+            Err(e) => {
+                error!("Couldn't reload the service: {e}");
+                cosmic::Action::None
+            }
+        },
+    )
+}
+
 async fn save_as_dialog() -> Result<PathBuf> {
     let dialog = save::Dialog::new();
 
@@ -2620,6 +3761,42 @@ async fn save_as_dialog() -> Result<PathBuf> {
     })?
 }
 
+fn menu_keys(keymap: &Keymap) -> HashMap<KeyBind, MenuAction> {
+    [
+        (Command::New, MenuAction::New),
+        (Command::Open, MenuAction::Open),
+        (Command::Save, MenuAction::Save),
+        (Command::SaveAs, MenuAction::SaveAs),
+        (Command::OpenSettings, MenuAction::OpenSettings),
+        (Command::ToggleLowerThird, MenuAction::ToggleLowerThird),
+        (Command::ToggleStageDisplay, MenuAction::ToggleStageDisplay),
+    ]
+    .into_iter()
+    .flat_map(|(command, action)| {
+        keymap
+            .chords(command)
+            .iter()
+            .map(move |chord| (chord.key_bind(), action))
+    })
+    .collect()
+}
+
+async fn logo_dialog() -> Result<PathBuf> {
+    let filter = cosmic::dialog::file_chooser::FileFilter::new("Images")
+        .extension("png")
+        .extension("jpg")
+        .extension("jpeg")
+        .extension("webp")
+        .extension("gif");
+    let dialog = open::Dialog::new().title("Choose a logo...").filter(filter);
+    open::file(dialog).await.into_diagnostic().map(|response| {
+        response
+            .url()
+            .to_file_path()
+            .map_err(|e| miette!("Can't convert to file path: {:?}", e))
+    })?
+}
+
 async fn open_dialog() -> Result<PathBuf> {
     let dialog = open::Dialog::new();
     open::file(dialog).await.into_diagnostic().map(|response| {
@@ -1,32 +1,380 @@
-use std::{fs::read_to_string, path::PathBuf};
+use std::fmt::{Display, Write};
+use std::{
+    fs::read_to_string,
+    path::{Path, PathBuf},
+};
 
-use crisp::types::{Symbol, Value};
+use crisp::types::{Keyword, Symbol, Value};
+use miette::{IntoDiagnostic, Result, miette};
 
 use crate::core::service_items::ServiceItem;
 
-pub fn parse_lisp(value: Value) -> Vec<ServiceItem> {
+/// Builds the text of a form like `(slide :notes "Hi" (text "Grace"))`
+/// one keyword at a time.
+#[derive(Clone, Debug)]
+pub struct Form {
+    text: String,
+}
+
+impl Form {
+    #[must_use]
+    pub fn new(name: &str) -> Self {
+        Self {
+            text: format!("({name}"),
+        }
+    }
+
+    /// Adds `:key value` where the value is already lisp, like a symbol,
+    /// a number or another form.
+    #[must_use]
+    pub fn atom(mut self, key: &str, value: impl Display) -> Self {
+        let _ = write!(self.text, " :{key} {value}");
+        self
+    }
+
+    #[must_use]
+    pub fn string(self, key: &str, value: impl AsRef<str>) -> Self {
+        self.atom(key, quote(value.as_ref()))
+    }
+
+    /// Adds a keyword without a value like `(obs :start-stream)`.
+    #[must_use]
+    pub fn flag(mut self, key: &str) -> Self {
+        let _ = write!(self.text, " :{key}");
+        self
+    }
+
+    /// Adds a value without a keyword.
+    #[must_use]
+    pub fn push(mut self, value: impl Display) -> Self {
+        let _ = write!(self.text, " {value}");
+        self
+    }
+
+    /// Adds a value on its own line, like the verses of a song.
+    #[must_use]
+    pub fn child(mut self, value: impl Display) -> Self {
+        let _ = write!(self.text, "\n  {value}");
+        self
+    }
+}
+
+impl Display for Form {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{})", self.text)
+    }
+}
+
+impl From<Form> for Value {
+    fn from(form: Form) -> Self {
+        crisp::reader::read(&form.to_string())
+    }
+}
+
+#[must_use]
+pub fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// The value following `:key` in a form.
+#[must_use]
+pub fn keyword<'a>(list: &'a [Value], key: &str) -> Option<&'a Value> {
+    list.iter()
+        .position(|v| v == &Value::Keyword(Keyword::from(key)))
+        .and_then(|position| list.get(position + 1))
+}
+
+#[must_use]
+pub fn has_keyword(list: &[Value], key: &str) -> bool {
+    list.contains(&Value::Keyword(Keyword::from(key)))
+}
+
+#[must_use]
+pub fn string(value: &Value) -> String {
+    unescape(&String::from(value), false)
+}
+
+/// Like [`string`], but hand written lyrics can also break lines with `\n`.
+#[must_use]
+pub fn lyric(value: &Value) -> String {
+    unescape(&String::from(value), true)
+}
+
+/// Reads the escapes [`quote`] writes, leaving any other backslash alone.
+fn unescape(text: &str, line_breaks: bool) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some(escaped @ ('\\' | '"')) => unescaped.push(escaped),
+            Some('n') if line_breaks => unescaped.push('\n'),
+            Some(other) => {
+                unescaped.push('\\');
+                unescaped.push(other);
+            }
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+#[must_use]
+pub fn symbol(value: &Value) -> Option<&str> {
+    match value {
+        Value::Symbol(Symbol(symbol)) => Some(symbol.as_str()),
+        _ => None,
+    }
+}
+
+#[must_use]
+pub fn boolean(value: &Value) -> bool {
+    String::from(value) == "true"
+}
+
+/// Numbers with a fraction are read from their text so that times like
+/// `1.5` keep their fraction.
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn float(value: &Value) -> f32 {
+    String::from(value)
+        .parse()
+        .unwrap_or_else(|_| i32::from(value) as f32)
+}
+
+/// Writes a service as lisp forms that [`load_lisp`] reads back the same.
+#[must_use]
+pub fn service_to_lisp(items: &[ServiceItem]) -> String {
+    items
+        .iter()
+        .map(|item| format!("{}\n", item.lisp_form()))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+pub fn parse_lisp(value: Value) -> Result<Vec<ServiceItem>> {
     match &value {
-        Value::List(vec) => match &vec[0] {
-            Value::Symbol(Symbol(s)) if s == "slide" || s == "song" => {
+        Value::List(vec) => match vec.first() {
+            Some(Value::Symbol(Symbol(s))) if s == "slide" || s == "song" => {
                 let item = ServiceItem::from(value.clone());
-                vec![item]
+                Ok(vec![item])
             }
-            Value::Symbol(Symbol(s)) if s == "load" => {
-                let Ok(path) = PathBuf::from(String::from(&vec[1])).canonicalize() else {
-                    return vec![ServiceItem::default()];
+            Some(Value::Symbol(Symbol(s))) if s == "load" => {
+                let Some(path) = vec.get(1) else {
+                    return Err(miette!("load is missing a file: {value:?}"));
                 };
-                let lisp = read_to_string(path).expect("oops");
-                let lisp_value = crisp::reader::read(&lisp);
-                match lisp_value {
-                    Value::List(value) => {
-                        value.into_iter().flat_map(parse_lisp).collect()
-                    }
-                    _ => panic!("Should not be"),
-                }
+                let path = PathBuf::from(string(path))
+                    .canonicalize()
+                    .into_diagnostic()?;
+                load_lisp(path)
             }
-            _ => todo!(),
+            _ => Err(miette!("Not a slide, song, or load form: {value:?}")),
         },
-        _ => todo!(),
+        _ => Err(miette!("Expected a list but found: {value:?}")),
+    }
+}
+
+pub fn load_lisp(path: impl AsRef<Path>) -> Result<Vec<ServiceItem>> {
+    let lisp = read_to_string(&path).into_diagnostic()?;
+    match crisp::reader::read(&lisp) {
+        // A file with a single form reads as just that form
+        value @ Value::List(_) if list_head(&value).is_some() => parse_lisp(value),
+        Value::List(value) => value
+            .into_iter()
+            .map(parse_lisp)
+            .collect::<Result<Vec<Vec<ServiceItem>>>>()
+            .map(|items| items.into_iter().flatten().collect()),
+        value => Err(miette!(
+            "{} doesn't hold a list of items: {value:?}",
+            path.as_ref().display()
+        )),
+    }
+}
+
+fn list_head(value: &Value) -> Option<&str> {
+    match value {
+        Value::List(list) => list.first().and_then(symbol),
+        _ => None,
+    }
+}
+
+pub fn lisp_includes(path: impl AsRef<Path>) -> Vec<PathBuf> {
+    let mut includes = vec![];
+    let mut pending = vec![path.as_ref().to_path_buf()];
+    while let Some(path) = pending.pop() {
+        let Ok(path) = path.canonicalize() else {
+            continue;
+        };
+        if includes.contains(&path) {
+            continue;
+        }
+        if let Ok(lisp) = read_to_string(&path)
+            && let Value::List(values) = crisp::reader::read(&lisp)
+        {
+            pending.extend(values.iter().filter_map(|value| match value {
+                Value::List(list)
+                    if list.first() == Some(&Value::Symbol(Symbol::from("load"))) =>
+                {
+                    list.get(1).map(|path| PathBuf::from(string(path)))
+                }
+                _ => None,
+            }));
+        }
+        includes.push(path);
+    }
+    includes
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    use super::*;
+    use crate::core::animation::{Animation, Easing};
+    use crate::core::kinds::ServiceItemKind;
+    use crate::core::slide::{TextAlignment, TextMargins};
+    use crate::core::songs::{Licence, SlideFit, VerseName, lisp_to_song};
+    use cosmic::iced::font::Weight;
+    use pretty_assertions::assert_eq;
+
+    fn read_items(lisp: &str) -> Vec<ServiceItem> {
+        let Value::List(forms) = crisp::reader::read(lisp) else {
+            panic!("Should be a list of forms");
+        };
+        forms
+            .into_iter()
+            .filter(|form| list_head(form) != Some("load"))
+            .map(ServiceItem::from)
+            .collect()
+    }
+
+    #[test]
+    fn test_presentation_round_trip() {
+        let lisp = read_to_string("./test_presentation.lisp")
+            .expect("Should read the test presentation");
+        let items = read_items(&lisp);
+        assert_eq!(items.len(), 5);
+
+        let written = service_to_lisp(&items);
+        let read_back = read_items(&written);
+        assert_eq!(read_back, items);
+        assert_eq!(service_to_lisp(&read_back), written);
+    }
+
+    #[test]
+    fn test_content_round_trip() {
+        let lisp = r##"(slide :title "Welcome" :item-notes "Smile"
+       :animation (slide-left :duration 300 :easing ease-in-out)
+       :font "Quicksand" :font-weight bold :text-alignment bottom-left
+       :margins (5 66 5 5) :color "#ffeedd" :stroke (3 "#000000")
+       :shadow (2 2 4 "#101010") :shrink-to-fit (30 120)
+       :audio "/tmp/walk-in.mp3" :loop true :start-time 2 :end-time 12
+       :pdf-index 2 :notes "Read slowly"
+       :actions ((obs :start-stream) (obs :stop-stream))
+  (text "Welcome \"home\" C:\\lumina\\new" :font-size 90))"##;
+        let item = ServiceItem::from(crisp::reader::read(lisp));
+        assert_eq!(item.title, "Welcome");
+        assert_eq!(item.notes, "Smile");
+        assert_eq!(
+            item.animation,
+            Some(Animation::SlideLeft {
+                duration: Some(Duration::from_millis(300)),
+                easing: Some(Easing::EaseInOut),
+            })
+        );
+        let ServiceItemKind::Content(slide) = &item.kind else {
+            panic!("Should be a content slide");
+        };
+        assert_eq!(slide.text(), r#"Welcome "home" C:\lumina\new"#);
+        assert_eq!(slide.font_size(), 90);
+        assert_eq!(
+            slide.font().map(|font| font.get_weight()),
+            Some(Weight::Bold)
+        );
+        assert_eq!(slide.text_alignment(), TextAlignment::BottomLeft);
+        assert_eq!(slide.text_margins(), TextMargins::LOWER_THIRD);
+        assert_eq!(
+            slide.text_color().map(|color| color.to_css_hex_string()),
+            Some("#ffeedd".to_string())
+        );
+        assert_eq!(slide.stroke().map(|stroke| stroke.size()), Some(3));
+        assert_eq!(slide.shadow().map(|shadow| shadow.spread), Some(4));
+        assert_eq!(slide.shrink_to_fit().map(|fit| fit.max), Some(120));
+        assert_eq!(slide.audio(), Some(PathBuf::from("/tmp/walk-in.mp3")));
+        assert!(slide.video_loop());
+        assert_eq!(slide.pdf_index(), 2);
+        assert_eq!(slide.notes(), "Read slowly");
+        assert_eq!(slide.actions().len(), 2);
+
+        let written = item.lisp_form().to_string();
+        assert_eq!(ServiceItem::from(crisp::reader::read(&written)), item);
+    }
+
+    #[test]
+    fn test_song_round_trip() {
+        let lisp = r##"(song :id 3 :title "Amazing Grace" :author "John Newton"
+      :ccli "22025" :copyright "Public Domain" :notes "Capo 2"
+      :songbooks ((songbook :name "Hymns of Faith" :entry "12"))
+      :slide-actions ((1 ((obs :start-stream))))
+      :font "Quicksand" :font-size 80 :font-weight semibold
+      :text-alignment top-center :margins (5 10 5 10) :shrink-to-fit (40 100)
+      :color "#ffffff" :stroke-size 2 :stroke-color "#000000"
+      :shadow-size 4 :shadow-offset (2 3) :shadow-color "#202020"
+      :credits (:title-slide true :licence overlay) :slide-fit (max-lines 2)
+      :animation (cross-fade :duration 500)
+      :verse-order (v1 c1 v2 c1)
+      :chords ((v1 "[G]Amazing grace"))
+      (v1 "Amazing grace how sweet the sound")
+      (c1 "My chains are gone")
+      (v2 "Twas grace that taught my heart to fear"))"##;
+        let Value::List(list) = crisp::reader::read(lisp) else {
+            panic!("Should be a song form");
+        };
+        let song = lisp_to_song(list);
+        assert_eq!(song.ccli.as_deref(), Some("22025"));
+        assert_eq!(song.notes.as_deref(), Some("Capo 2"));
+        assert_eq!(
+            song.songbooks.first().map(ToString::to_string).as_deref(),
+            Some("Hymns of Faith #12")
+        );
+        assert_eq!(song.font_weight, Some(Weight::Semibold));
+        assert_eq!(song.shadow_offset, Some((2, 3)));
+        assert_eq!(song.slide_fit, Some(SlideFit::MaxLines(2)));
+        assert_eq!(
+            song.credits.map(|credits| credits.licence),
+            Some(Licence::Overlay)
+        );
+        assert_eq!(
+            song.verses,
+            Some(vec![
+                VerseName::Verse { number: 1 },
+                VerseName::Chorus { number: 1 },
+                VerseName::Verse { number: 2 },
+                VerseName::Chorus { number: 1 },
+            ])
+        );
+        assert_eq!(song.verse_map.as_ref().map(HashMap::len), Some(3));
+        assert_eq!(song.chords.as_ref().map(HashMap::len), Some(1));
+
+        let item = ServiceItem::from(crisp::reader::read(lisp));
+        assert_eq!(item.notes, "Capo 2");
+        assert!(!item.slides.is_empty());
+        assert_eq!(item.slides[1].actions().len(), 1);
+        assert_eq!(item.animation, song.animation);
+
+        let written = item.lisp_form().to_string();
+        assert_eq!(written.matches(":notes").count(), 1);
+        assert_eq!(ServiceItem::from(crisp::reader::read(&written)), item);
+
+        let written = song.lisp_form().to_string();
+        let Value::List(list) = crisp::reader::read(&written) else {
+            panic!("Should be a song form");
+        };
+        assert_eq!(lisp_to_song(list).notes.as_deref(), Some("Capo 2"));
     }
 }
 
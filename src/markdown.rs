@@ -0,0 +1,249 @@
+use std::fs::read_to_string;
+use std::path::Path;
+
+use miette::{IntoDiagnostic, Result};
+
+use crate::core::kinds::ServiceItemKind;
+use crate::core::service_items::ServiceItem;
+use crate::core::slide::{SlideBuilder, TextAlignment};
+use crate::source::Source;
+
+/// The front matter settings every slide starts from.
+#[derive(Debug, Default)]
+struct Defaults<'a> {
+    font: Option<&'a str>,
+    font_size: Option<i32>,
+    background: Option<&'a str>,
+}
+
+struct Parser<'a> {
+    source: Source<'a>,
+}
+
+/// Reads a markdown file where every `---` separated section is a slide,
+/// a whole line image becomes its background and html comments become
+/// the speaker notes.
+pub fn load_markdown(path: impl AsRef<Path>) -> Result<Vec<ServiceItem>> {
+    let path = path.as_ref();
+    let markdown = read_to_string(path).into_diagnostic()?;
+    parse_markdown(&markdown, path)
+}
+
+pub fn parse_markdown(source: &str, path: impl AsRef<Path>) -> Result<Vec<ServiceItem>> {
+    let path = path.as_ref();
+    let parser = Parser {
+        source: Source::new(source, path),
+    };
+    let mut lines = source.lines().peekable();
+    let defaults = if lines.next_if(|line| line.trim() == "---").is_some() {
+        let mut front_matter = vec![];
+        loop {
+            let Some(line) = lines.next() else {
+                return Err(parser.source.error(
+                    source.lines().next().unwrap_or(source),
+                    "opened here",
+                    "The front matter is never closed with ---",
+                ));
+            };
+            if line.trim() == "---" {
+                break;
+            }
+            front_matter.push(line);
+        }
+        parser.defaults(&front_matter)?
+    } else {
+        Defaults::default()
+    };
+
+    let mut sections = vec![vec![]];
+    for line in lines {
+        if line.trim() == "---" {
+            sections.push(vec![]);
+        } else if let Some(section) = sections.last_mut() {
+            section.push(line);
+        }
+    }
+    sections
+        .iter()
+        .filter(|section| section.iter().any(|line| !line.trim().is_empty()))
+        .map(|section| parser.slide(section, &defaults))
+        .collect()
+}
+
+impl<'a> Parser<'a> {
+    fn defaults(&self, front_matter: &[&'a str]) -> Result<Defaults<'a>> {
+        let mut defaults = Defaults::default();
+        for line in front_matter {
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim().trim_matches(['"', '\'']);
+            match key.trim() {
+                "font" => defaults.font = Some(value),
+                "font_size" | "font-size" => {
+                    defaults.font_size = Some(value.parse().map_err(|_| {
+                        self.source.error(
+                            value,
+                            "not a number",
+                            "font_size needs to be a number",
+                        )
+                    })?);
+                }
+                "background" => defaults.background = Some(value),
+                _ => (),
+            }
+        }
+        Ok(defaults)
+    }
+
+    fn slide(&self, section: &[&'a str], defaults: &Defaults<'a>) -> Result<ServiceItem> {
+        let mut background = defaults.background;
+        let mut title = None;
+        let mut text = vec![];
+        let mut notes = vec![];
+        let mut lines = section.iter();
+        while let Some(line) = lines.next() {
+            let trimmed = line.trim();
+            if let Some(image) = image(trimmed) {
+                background = Some(image);
+                continue;
+            }
+            if let Some(comment) = trimmed.strip_prefix("<!--") {
+                let mut comment = comment;
+                loop {
+                    if let Some(end) = comment.find("-->") {
+                        notes.push(comment[..end].trim());
+                        break;
+                    }
+                    notes.push(comment.trim());
+                    let Some(next) = lines.next() else {
+                        break;
+                    };
+                    comment = next;
+                }
+                continue;
+            }
+            let heading = trimmed.trim_start_matches('#');
+            if heading.len() < trimmed.len() && heading.starts_with(' ') {
+                let heading = strip_emphasis(heading.trim());
+                let _ = title.get_or_insert_with(|| heading.clone());
+                text.push(heading);
+                continue;
+            }
+            let line = trimmed
+                .strip_prefix("- ")
+                .or_else(|| trimmed.strip_prefix("* "))
+                .map_or_else(|| trimmed.to_string(), |item| format!("• {item}"));
+            text.push(strip_emphasis(&line));
+        }
+
+        let text = text.join("\n").trim().to_string();
+        let notes = notes
+            .into_iter()
+            .filter(|note| !note.is_empty())
+            .collect::<Vec<&str>>()
+            .join("\n");
+        let background = background
+            .map(|background| self.source.background(background))
+            .transpose()?
+            .flatten()
+            .unwrap_or_default();
+        let slide = SlideBuilder::new()
+            .background(background)
+            .text(text.clone())
+            .notes(notes)
+            .font(defaults.font.unwrap_or("Quicksand"))
+            .font_size(defaults.font_size.unwrap_or_default())
+            .text_alignment(TextAlignment::MiddleCenter)
+            .video_loop(false)
+            .video_start_time(0.0)
+            .video_end_time(0.0)
+            .build()?;
+        Ok(ServiceItem {
+            title: title
+                .or_else(|| text.lines().next().map(String::from))
+                .unwrap_or_default(),
+            kind: ServiceItemKind::Content(slide.clone()),
+            slides: vec![slide],
+            ..Default::default()
+        })
+    }
+}
+
+/// The path of a line that is only an image like `![alt](path "title")`.
+fn image(line: &str) -> Option<&str> {
+    let (_, rest) = line.strip_prefix("![")?.split_once("](")?;
+    let path = rest.strip_suffix(')')?;
+    path.split_whitespace().next()
+}
+
+fn strip_emphasis(line: &str) -> String {
+    line.replace("**", "").replace("__", "").replace('`', "")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const ANNOUNCEMENTS: &str = r"---
+font: Quicksand
+font_size: 70
+---
+
+# Youth Night
+
+Friday at **7pm**
+- Bring a friend
+- Pizza provided
+
+<!-- Mention the permission slips -->
+
+---
+
+## Car Wash
+Saturday morning
+<!--
+Ask for volunteers
+with cars
+-->
+";
+
+    #[test]
+    fn test_parse_markdown() -> Result<()> {
+        let items = parse_markdown(ANNOUNCEMENTS, "/tmp/announcements.md")?;
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].title, "Youth Night");
+        assert_eq!(
+            items[0].slides[0].text(),
+            "Youth Night\n\nFriday at 7pm\n• Bring a friend\n• Pizza provided"
+        );
+        assert_eq!(items[0].slides[0].notes(), "Mention the permission slips");
+        assert_eq!(items[0].slides[0].font_size(), 70);
+        assert_eq!(items[1].title, "Car Wash");
+        assert_eq!(items[1].slides[0].text(), "Car Wash\nSaturday morning");
+        assert_eq!(items[1].slides[0].notes(), "Ask for volunteers\nwith cars");
+        Ok(())
+    }
+
+    #[test]
+    fn test_markdown_image() {
+        assert_eq!(image("![](~/pics/bg.png)"), Some("~/pics/bg.png"));
+        assert_eq!(image("![stage](bg.jpg \"Stage\")"), Some("bg.jpg"));
+        assert_eq!(image("Look at ![this](bg.jpg) picture"), None);
+    }
+
+    #[test]
+    fn test_markdown_errors() {
+        let markdown = "---\nfont_size: big\n---\n# Hi\n";
+        let error =
+            parse_markdown(markdown, "announcements.md").expect_err("big isn't a size");
+        let label = error
+            .labels()
+            .and_then(|mut labels| labels.next())
+            .expect("Should point at the size");
+        assert_eq!(label.offset(), markdown.find("big").unwrap_or_default());
+
+        assert!(parse_markdown("---\nfont: Quicksand\n# Hi\n", "a.md").is_err());
+    }
+}
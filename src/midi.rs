@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+
+use midir::{Ignore, MidiInput, MidiInputConnection};
+use miette::{IntoDiagnostic, Result};
+use tokio::sync::mpsc;
+use tracing::{debug, warn};
+
+use crate::core::midi::Event;
+
+const CLIENT_NAME: &str = "Lumina";
+
+/// Listens to every MIDI input that was plugged in when it started, plus a
+/// virtual port other programs can connect to. Dropping it disconnects.
+pub(crate) struct Midi {
+    pub ports: Vec<String>,
+    _connections: Vec<MidiInputConnection<()>>,
+}
+
+impl Midi {
+    pub fn start() -> Result<(Self, mpsc::UnboundedReceiver<Event>)> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let mut ports = vec![];
+        let mut connections = vec![];
+
+        let input = MidiInput::new(CLIENT_NAME).into_diagnostic()?;
+        for port in input.ports() {
+            let name = input.port_name(&port).into_diagnostic()?;
+            let mut input = MidiInput::new(CLIENT_NAME).into_diagnostic()?;
+            input.ignore(Ignore::All);
+            match input.connect(&port, &name, listener(sender.clone()), ()) {
+                Ok(connection) => {
+                    debug!(name, "Listening to MIDI port");
+                    ports.push(name);
+                    connections.push(connection);
+                }
+                Err(e) => warn!(name, %e, "Can't connect to MIDI port"),
+            }
+        }
+
+        #[cfg(unix)]
+        {
+            use midir::os::unix::VirtualInput;
+
+            let mut input = MidiInput::new(CLIENT_NAME).into_diagnostic()?;
+            input.ignore(Ignore::All);
+            match input.create_virtual(CLIENT_NAME, listener(sender), ()) {
+                Ok(connection) => {
+                    ports.push(format!("{CLIENT_NAME} (virtual)"));
+                    connections.push(connection);
+                }
+                Err(e) => warn!(%e, "Can't create the virtual MIDI port"),
+            }
+        }
+
+        Ok((
+            Self {
+                ports,
+                _connections: connections,
+            },
+            receiver,
+        ))
+    }
+}
+
+/// Sends the events from one port on, passing controllers along only when
+/// they are first pressed or released so a pedal held down or a wheel
+/// being turned doesn't repeat.
+fn listener(
+    sender: mpsc::UnboundedSender<Event>,
+) -> impl FnMut(u64, &[u8], &mut ()) + Send + 'static {
+    let mut controllers = HashMap::new();
+    move |_, bytes, ()| {
+        let Some(event) = Event::parse(bytes) else {
+            return;
+        };
+        if let Event::Controller {
+            channel,
+            controller,
+            pressed,
+        } = event
+            && controllers.insert((channel, controller), pressed) == Some(pressed)
+        {
+            return;
+        }
+        let _ = sender.send(event);
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod test {
+    use std::time::{Duration, Instant};
+
+    use midir::MidiOutput;
+    use midir::os::unix::VirtualOutput;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_virtual_port() {
+        let output = MidiOutput::new("Lumina Test").expect("Should have a sequencer");
+        let mut pedal = output
+            .create_virtual("Test Pedal")
+            .expect("Should create a virtual port");
+        let (midi, mut events) = Midi::start().expect("Should listen to midi");
+        assert!(midi.ports.iter().any(|port| port.contains("Test Pedal")));
+
+        pedal.send(&[0x90, 60, 100]).expect("Should send a note");
+        pedal
+            .send(&[0xb0, 67, 127])
+            .expect("Should press the pedal");
+        pedal.send(&[0xb0, 67, 120]).expect("Should hold the pedal");
+
+        let deadline = Instant::now() + Duration::from_secs(2);
+        let mut received = vec![];
+        while received.len() < 2 && Instant::now() < deadline {
+            match events.try_recv() {
+                Ok(event) => received.push(event),
+                Err(_) => std::thread::sleep(Duration::from_millis(10)),
+            }
+        }
+        std::thread::sleep(Duration::from_millis(100));
+        assert_eq!(
+            received,
+            vec![
+                Event::Note {
+                    channel: 1,
+                    note: 60
+                },
+                Event::Controller {
+                    channel: 1,
+                    controller: 67,
+                    pressed: true
+                },
+            ]
+        );
+        assert!(events.try_recv().is_err(), "A held pedal shouldn't repeat");
+    }
+}
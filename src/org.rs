@@ -0,0 +1,743 @@
+use std::fmt::Write as _;
+use std::fs::read_to_string;
+use std::path::Path;
+use std::sync::Arc;
+
+use miette::{IntoDiagnostic, Result};
+use sqlx::SqlitePool;
+use tracing::error;
+
+use crate::core::images::Image;
+use crate::core::kinds::ServiceItemKind;
+use crate::core::presentations::Presentation;
+use crate::core::service_items::ServiceItem;
+use crate::core::slide::{Background, SlideBuilder, TextAlignment};
+use crate::core::songs::{self, Song, VerseName};
+use crate::core::videos::Video;
+use crate::source::Source;
+
+#[derive(Debug)]
+struct Property<'a> {
+    key: String,
+    value: &'a str,
+}
+
+#[derive(Debug)]
+struct Section<'a> {
+    level: usize,
+    title: &'a str,
+    heading: &'a str,
+    properties: Vec<Property<'a>>,
+    text: Vec<&'a str>,
+    notes: Vec<&'a str>,
+}
+
+impl Section<'_> {
+    fn text(&self) -> String {
+        unescape(&self.text).trim().to_string()
+    }
+
+    fn notes(&self) -> String {
+        unescape(&self.notes).trim().to_string()
+    }
+}
+
+/// Text that org would read as a heading, keyword, comment or drawer,
+/// counting lines that are already escaped so those can be told apart.
+fn needs_escape(line: &str) -> bool {
+    let line = line.trim_start().trim_start_matches(',');
+    line.starts_with('*')
+        || line.starts_with('#')
+        || line.eq_ignore_ascii_case(":PROPERTIES:")
+}
+
+/// Puts a comma in front of text lines that would otherwise read as org,
+/// the same escape org uses inside blocks.
+fn escape(text: &str) -> String {
+    text.lines()
+        .map(|line| {
+            if needs_escape(line) {
+                let indent = line.len() - line.trim_start().len();
+                format!("{},{}", &line[..indent], &line[indent..])
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn unescape(lines: &[&str]) -> String {
+    lines
+        .iter()
+        .map(|line| {
+            let indent = line.len() - line.trim_start().len();
+            match line[indent..].strip_prefix(',') {
+                Some(rest) if needs_escape(rest) => format!("{}{rest}", &line[..indent]),
+                _ => (*line).to_string(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+struct Parser<'a> {
+    source: Source<'a>,
+}
+
+/// Reads an org file where each top heading is a service item, property
+/// drawers hold the backgrounds and fonts and notes blocks hold the
+/// speaker notes.
+pub fn load_org(path: impl AsRef<Path>) -> Result<Vec<ServiceItem>> {
+    let path = path.as_ref();
+    let org = read_to_string(path).into_diagnostic()?;
+    parse_org(&org, path)
+}
+
+pub fn parse_org(source: &str, path: impl AsRef<Path>) -> Result<Vec<ServiceItem>> {
+    let path = path.as_ref();
+    let parser = Parser {
+        source: Source::new(source, path),
+    };
+    let sections = parser.sections()?;
+    let mut items = vec![];
+    let mut sections = sections.into_iter().peekable();
+    while let Some(section) = sections.next() {
+        let mut children = vec![];
+        while let Some(child) = sections.next_if(|section| section.level > 1) {
+            children.push(child);
+        }
+        items.extend(parser.items(&section, &children)?);
+    }
+    Ok(items)
+}
+
+impl<'a> Parser<'a> {
+    #[allow(clippy::too_many_lines)]
+    fn sections(&self) -> Result<Vec<Section<'a>>> {
+        let mut sections: Vec<Section<'a>> = vec![];
+        let mut lines = self.source.text.lines().peekable();
+        while let Some(line) = lines.next() {
+            let trimmed = line.trim();
+            let level = line.chars().take_while(|c| *c == '*').count();
+            if level > 0 && line[level..].starts_with(' ') {
+                if level > 2 {
+                    return Err(self.source.error(
+                        line,
+                        "too deep",
+                        "Only items (*) and their slides or verses (**) can be headings",
+                    ));
+                }
+                if level == 2 && sections.is_empty() {
+                    return Err(self.source.error(
+                        line,
+                        "no item",
+                        "A sub heading needs an item heading above it",
+                    ));
+                }
+                let mut section = Section {
+                    level,
+                    title: line[level..].trim(),
+                    heading: line,
+                    properties: vec![],
+                    text: vec![],
+                    notes: vec![],
+                };
+                if let Some(drawer) =
+                    lines.next_if(|line| line.trim().eq_ignore_ascii_case(":PROPERTIES:"))
+                {
+                    loop {
+                        let Some(line) = lines.next() else {
+                            return Err(self.source.error(
+                                drawer,
+                                "opened here",
+                                "This property drawer is never closed with :END:",
+                            ));
+                        };
+                        let property = line.trim();
+                        if property.eq_ignore_ascii_case(":END:") {
+                            break;
+                        }
+                        let Some((key, value)) = property
+                            .strip_prefix(':')
+                            .and_then(|property| property.split_once(':'))
+                        else {
+                            return Err(self.source.error(
+                                line,
+                                "not a property",
+                                "Properties are written as :KEY: value",
+                            ));
+                        };
+                        section.properties.push(Property {
+                            key: key.to_uppercase(),
+                            value: value.trim(),
+                        });
+                    }
+                }
+                sections.push(section);
+                continue;
+            }
+
+            if trimmed.eq_ignore_ascii_case("#+begin_notes") {
+                let mut notes = vec![];
+                loop {
+                    let Some(note) = lines.next() else {
+                        return Err(self.source.error(
+                            line,
+                            "opened here",
+                            "This notes block is never closed with #+end_notes",
+                        ));
+                    };
+                    if note.trim().eq_ignore_ascii_case("#+end_notes") {
+                        break;
+                    }
+                    notes.push(note);
+                }
+                let Some(section) = sections.last_mut() else {
+                    return Err(self.source.error(
+                        line,
+                        "no heading",
+                        "Notes need a heading above them to belong to",
+                    ));
+                };
+                section.notes.extend(notes);
+                continue;
+            }
+
+            // keywords like #+TITLE and comments
+            if trimmed.starts_with("#+") || trimmed == "#" || trimmed.starts_with("# ") {
+                continue;
+            }
+
+            match sections.last_mut() {
+                Some(section) => section.text.push(line),
+                None if trimmed.is_empty() => (),
+                None => {
+                    return Err(self.source.error(
+                        line,
+                        "no heading",
+                        "Text needs a heading above it to belong to",
+                    ));
+                }
+            }
+        }
+        Ok(sections)
+    }
+
+    fn property<'b>(sections: &[&'b Section<'a>], key: &str) -> Option<&'b str> {
+        sections.iter().find_map(|section| {
+            section
+                .properties
+                .iter()
+                .find(|property| property.key == key)
+                .map(|property| property.value)
+        })
+    }
+
+    fn background(&self, sections: &[&Section<'a>]) -> Result<Option<Background>> {
+        Self::property(sections, "BACKGROUND")
+            .map_or(Ok(None), |background| self.source.background(background))
+    }
+
+    fn font_size(&self, sections: &[&Section<'a>]) -> Result<Option<i32>> {
+        Self::property(sections, "FONT_SIZE")
+            .map(|size| {
+                size.parse().map_err(|_| {
+                    self.source.error(
+                        size,
+                        "not a number",
+                        "FONT_SIZE needs to be a number",
+                    )
+                })
+            })
+            .transpose()
+    }
+
+    fn items(
+        &self,
+        section: &Section<'a>,
+        children: &[Section<'a>],
+    ) -> Result<Vec<ServiceItem>> {
+        let kind = Self::property(&[section], "KIND").unwrap_or(
+            if Self::property(&[section], "SONG").is_some() {
+                "song"
+            } else {
+                "slide"
+            },
+        );
+        let media = |kind: &str| {
+            Self::property(&[section], "BACKGROUND")
+                .map(|path| self.source.path(path))
+                .ok_or_else(|| {
+                    self.source.error(
+                        section.heading,
+                        "no background",
+                        format!("A {kind} item needs a BACKGROUND property"),
+                    )
+                })
+        };
+        let mut item = match kind.to_lowercase().as_str() {
+            "song" => self.song(section, children)?,
+            "image" => ServiceItem::from(&Image {
+                title: section.title.to_string(),
+                ..Image::from(media(kind)?)
+            }),
+            "video" => ServiceItem::from(&Video {
+                title: section.title.to_string(),
+                ..Video::from(media(kind)?)
+            }),
+            "presentation" => ServiceItem::from(&Presentation {
+                title: section.title.to_string(),
+                ..Presentation::from(media(kind)?)
+            }),
+            "slide" => {
+                let mut items = vec![];
+                if !section.text().is_empty() || children.is_empty() {
+                    items.push(self.slide(section, &[section])?);
+                }
+                for child in children {
+                    items.push(self.slide(child, &[child, section])?);
+                }
+                return Ok(items);
+            }
+            _ => {
+                return Err(self.source.error(
+                    kind,
+                    "unknown kind",
+                    "KIND needs to be one of slide, song, image, video or presentation",
+                ));
+            }
+        };
+        item.notes = std::iter::once(section)
+            .chain(children)
+            .map(Section::notes)
+            .filter(|notes| !notes.is_empty())
+            .collect::<Vec<String>>()
+            .join("\n\n");
+        Ok(vec![item])
+    }
+
+    fn slide(
+        &self,
+        section: &Section<'a>,
+        sections: &[&Section<'a>],
+    ) -> Result<ServiceItem> {
+        let mut slide = SlideBuilder::new()
+            .background(self.background(sections)?.unwrap_or_default())
+            .text(section.text())
+            .notes(section.notes())
+            .font(Self::property(sections, "FONT").unwrap_or("Quicksand"))
+            .font_size(self.font_size(sections)?.unwrap_or_default())
+            .text_alignment(TextAlignment::MiddleCenter)
+            .video_loop(false)
+            .video_start_time(0.0)
+            .video_end_time(0.0);
+        if let Some(audio) = Self::property(sections, "AUDIO") {
+            slide = slide.audio(self.source.path(audio));
+        }
+        let slide = slide.build()?;
+        Ok(ServiceItem {
+            title: section.title.to_string(),
+            kind: ServiceItemKind::Content(slide.clone()),
+            slides: vec![slide],
+            ..Default::default()
+        })
+    }
+
+    fn verse_name(&self, label: &'a str) -> Result<VerseName> {
+        if label.eq_ignore_ascii_case("blank") {
+            return Ok(VerseName::Blank);
+        }
+        VerseName::from_label(label).ok_or_else(|| {
+            self.source.error(
+                label,
+                "unknown verse",
+                "Verses are named like Verse 1, Chorus or Bridge 2",
+            )
+        })
+    }
+
+    fn song(
+        &self,
+        section: &Section<'a>,
+        children: &[Section<'a>],
+    ) -> Result<ServiceItem> {
+        let title = section.title.to_string();
+        if let Some(id) = Self::property(&[section], "SONG") {
+            let id = id.parse().map_err(|_| {
+                self.source.error(
+                    id,
+                    "not an id",
+                    "SONG needs to be the id of a library song",
+                )
+            })?;
+            return Ok(ServiceItem {
+                title: title.clone(),
+                database_id: id,
+                kind: ServiceItemKind::Song(Song {
+                    id,
+                    title,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            });
+        }
+
+        let mut song = Song {
+            title,
+            background: self.background(&[section])?,
+            font: Self::property(&[section], "FONT").map(String::from),
+            font_size: self.font_size(&[section])?,
+            audio: Self::property(&[section], "AUDIO")
+                .map(|audio| self.source.path(audio)),
+            ..Default::default()
+        };
+        let mut verses = vec![];
+        for child in children {
+            let verse = self.verse_name(child.title)?;
+            song.set_lyrics(&verse, child.text());
+            verses.push(verse);
+        }
+        if let Some(order) = Self::property(&[section], "VERSE_ORDER") {
+            verses = order
+                .split(',')
+                .map(|label| self.verse_name(label.trim()))
+                .collect::<Result<Vec<VerseName>>>()?;
+        }
+        song.verses = Some(verses);
+        Ok(ServiceItem::from(&song))
+    }
+}
+
+/// Songs in an org file can point at the library by id, those are left
+/// empty until they are looked up in the database.
+#[must_use]
+pub fn is_library_reference(item: &ServiceItem) -> bool {
+    item.database_id > 0
+        && matches!(&item.kind, ServiceItemKind::Song(song) if song.verse_map.is_none())
+}
+
+pub async fn resolve_library_songs(
+    items: Vec<ServiceItem>,
+    db: Arc<SqlitePool>,
+) -> Vec<ServiceItem> {
+    let mut resolved = Vec::with_capacity(items.len());
+    for item in items {
+        if !is_library_reference(&item) {
+            resolved.push(item);
+            continue;
+        }
+        match songs::get_song_from_db(item.database_id, Arc::clone(&db)).await {
+            Ok(song) => resolved.push(ServiceItem {
+                notes: item.notes,
+                ..ServiceItem::from(&song)
+            }),
+            Err(e) => {
+                error!(
+                    ?e,
+                    id = item.database_id,
+                    "Couldn't find the song in the library"
+                );
+                resolved.push(item);
+            }
+        }
+    }
+    resolved
+}
+
+fn write_properties(org: &mut String, properties: &[(&str, String)]) {
+    if properties.is_empty() {
+        return;
+    }
+    org.push_str(":PROPERTIES:\n");
+    for (key, value) in properties {
+        let _ = writeln!(org, ":{key}: {value}");
+    }
+    org.push_str(":END:\n");
+}
+
+fn write_notes(org: &mut String, notes: &[String]) {
+    let notes = notes
+        .iter()
+        .map(|notes| notes.trim())
+        .filter(|notes| !notes.is_empty())
+        .collect::<Vec<&str>>();
+    if notes.is_empty() {
+        return;
+    }
+    let _ = write!(
+        org,
+        "\n#+begin_notes\n{}\n#+end_notes\n",
+        escape(&notes.join("\n\n"))
+    );
+}
+
+/// Writes a service back out in the same shape `parse_org` reads.
+#[allow(clippy::too_many_lines)]
+#[must_use]
+pub fn service_to_org(items: &[ServiceItem]) -> String {
+    let mut org = String::new();
+    for item in items {
+        let title = item.title.lines().next().unwrap_or_default();
+        let _ = writeln!(org, "* {title}");
+        match &item.kind {
+            ServiceItemKind::Song(_) if item.database_id > 0 => {
+                write_properties(&mut org, &[("SONG", item.database_id.to_string())]);
+                write_notes(&mut org, std::slice::from_ref(&item.notes));
+            }
+            ServiceItemKind::Song(song) => {
+                let mut properties = vec![("KIND", "song".to_string())];
+                if let Some(background) = &song.background {
+                    properties
+                        .push(("BACKGROUND", background.path.display().to_string()));
+                }
+                if let Some(font) = &song.font {
+                    properties.push(("FONT", font.clone()));
+                }
+                if let Some(font_size) = song.font_size {
+                    properties.push(("FONT_SIZE", font_size.to_string()));
+                }
+                if let Some(audio) = &song.audio {
+                    properties.push(("AUDIO", audio.display().to_string()));
+                }
+                let verses = song.verses.clone().unwrap_or_default();
+                properties.push((
+                    "VERSE_ORDER",
+                    verses
+                        .iter()
+                        .map(VerseName::get_name)
+                        .collect::<Vec<String>>()
+                        .join(", "),
+                ));
+                write_properties(&mut org, &properties);
+                write_notes(&mut org, std::slice::from_ref(&item.notes));
+                let mut written = vec![];
+                for verse in verses {
+                    if verse == VerseName::Blank || written.contains(&verse) {
+                        continue;
+                    }
+                    written.push(verse);
+                    let _ = writeln!(
+                        org,
+                        "** {}\n{}",
+                        verse.get_name(),
+                        escape(&song.get_lyric(&verse).unwrap_or_default())
+                    );
+                }
+            }
+            ServiceItemKind::Image(image) => {
+                write_properties(
+                    &mut org,
+                    &[
+                        ("KIND", "image".to_string()),
+                        ("BACKGROUND", image.path.display().to_string()),
+                    ],
+                );
+                write_notes(&mut org, std::slice::from_ref(&item.notes));
+            }
+            ServiceItemKind::Video(video) => {
+                write_properties(
+                    &mut org,
+                    &[
+                        ("KIND", "video".to_string()),
+                        ("BACKGROUND", video.path.display().to_string()),
+                    ],
+                );
+                write_notes(&mut org, std::slice::from_ref(&item.notes));
+            }
+            ServiceItemKind::Presentation(presentation) => {
+                write_properties(
+                    &mut org,
+                    &[
+                        ("KIND", "presentation".to_string()),
+                        ("BACKGROUND", presentation.path.display().to_string()),
+                    ],
+                );
+                write_notes(&mut org, std::slice::from_ref(&item.notes));
+            }
+            ServiceItemKind::Content(slide) => {
+                let mut properties = vec![];
+                if !slide.background().path.as_os_str().is_empty() {
+                    properties.push((
+                        "BACKGROUND",
+                        slide.background().path.display().to_string(),
+                    ));
+                }
+                if let Some(font) = slide.font() {
+                    properties.push(("FONT", font.get_name()));
+                }
+                if slide.font_size() > 0 {
+                    properties.push(("FONT_SIZE", slide.font_size().to_string()));
+                }
+                if let Some(audio) = slide.audio() {
+                    properties.push(("AUDIO", audio.display().to_string()));
+                }
+                write_properties(&mut org, &properties);
+                if !slide.text().is_empty() {
+                    let _ = writeln!(org, "{}", escape(&slide.text()));
+                }
+                write_notes(&mut org, &[slide.notes(), item.notes.clone()]);
+            }
+        }
+        org.push('\n');
+    }
+    org
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::core::slide::Slide;
+    use pretty_assertions::assert_eq;
+
+    const SERVICE: &str = r"#+TITLE: Sunday
+
+* Welcome
+:PROPERTIES:
+:FONT: Quicksand
+:FONT_SIZE: 80
+:END:
+Welcome to church
+
+#+begin_notes
+Mention the potluck
+#+end_notes
+
+* Death Was Arrested
+:PROPERTIES:
+:SONG: 7
+:END:
+#+begin_notes
+Capo 2
+#+end_notes
+
+* Amazing Grace
+:PROPERTIES:
+:KIND: song
+:VERSE_ORDER: Verse 1, Chorus, Verse 2, Chorus
+:END:
+** Verse 1
+Amazing grace how sweet the sound
+** Verse 2
+Twas grace that taught my heart to fear
+** Chorus
+My chains are gone
+
+* Sermon
+:PROPERTIES:
+:FONT_SIZE: 60
+:END:
+** Point one
+Grace finds us
+#+begin_notes
+Tell the story about the lost keys
+#+end_notes
+** Point two
+Grace keeps us
+";
+
+    #[test]
+    fn test_parse_org() -> Result<()> {
+        let items = parse_org(SERVICE, "/tmp/service.org")?;
+        let titles = items
+            .iter()
+            .map(|item| item.title.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(
+            titles,
+            vec![
+                "Welcome",
+                "Death Was Arrested",
+                "Amazing Grace",
+                "Point one",
+                "Point two"
+            ]
+        );
+
+        assert_eq!(items[0].slides[0].text(), "Welcome to church");
+        assert_eq!(items[0].slides[0].notes(), "Mention the potluck");
+        assert_eq!(items[0].slides[0].font_size(), 80);
+
+        assert!(is_library_reference(&items[1]));
+        assert_eq!(items[1].database_id, 7);
+        assert_eq!(items[1].notes, "Capo 2");
+
+        let ServiceItemKind::Song(song) = &items[2].kind else {
+            panic!("Amazing Grace should be a song");
+        };
+        assert_eq!(song.verses.as_ref().map(Vec::len), Some(4));
+        assert_eq!(
+            song.get_lyric(&VerseName::Chorus { number: 1 }),
+            Some("My chains are gone".into())
+        );
+
+        assert_eq!(items[3].slides[0].font_size(), 60);
+        assert_eq!(
+            items[3].slides[0].notes(),
+            "Tell the story about the lost keys"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_org_round_trip() -> Result<()> {
+        let items = parse_org(SERVICE, "/tmp/service.org")?;
+        let org = service_to_org(&items);
+        let reparsed = parse_org(&org, "/tmp/service.org")?;
+        assert_eq!(items.len(), reparsed.len());
+        for (item, reparsed) in items.iter().zip(&reparsed) {
+            assert_eq!(item.title, reparsed.title);
+            assert_eq!(item.notes, reparsed.notes);
+            assert_eq!(item.database_id, reparsed.database_id);
+            assert_eq!(item.slides.len(), reparsed.slides.len());
+            for (slide, reparsed) in item.slides.iter().zip(&reparsed.slides) {
+                assert_eq!(slide.text(), reparsed.text());
+                assert_eq!(slide.notes(), reparsed.notes());
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_escaped_text() -> Result<()> {
+        let slide = Slide::default()
+            .set_text(
+                "* not a heading\n# not a comment\n#+not_a_keyword\n,* already escaped",
+            )
+            .set_notes("#+end_notes\n** still notes");
+        let items = vec![ServiceItem {
+            title: "Welcome".into(),
+            kind: ServiceItemKind::Content(slide.clone()),
+            slides: vec![slide],
+            ..Default::default()
+        }];
+        let org = service_to_org(&items);
+        assert!(org.contains("\n,* not a heading\n,# not a comment\n"));
+        let reparsed = parse_org(&org, "/tmp/service.org")?;
+        assert_eq!(reparsed.len(), 1);
+        assert_eq!(reparsed[0].slides[0].text(), items[0].slides[0].text());
+        assert_eq!(reparsed[0].slides[0].notes(), items[0].slides[0].notes());
+        Ok(())
+    }
+
+    #[test]
+    fn test_org_errors() {
+        let org = "* Welcome\n:PROPERTIES:\n:FONT_SIZE: huge\n:END:\n";
+        let error = parse_org(org, "service.org").expect_err("huge isn't a size");
+        let label = error
+            .labels()
+            .and_then(|mut labels| labels.next())
+            .expect("Should point at the size");
+        assert_eq!(label.offset(), org.find("huge").unwrap_or_default());
+        assert_eq!(label.len(), 4);
+
+        let org = "* Welcome\n#+begin_notes\nnever closed\n";
+        assert!(parse_org(org, "service.org").is_err());
+
+        let org = "* Song\n:PROPERTIES:\n:KIND: song\n:END:\n** Verse Z\nla la\n";
+        assert!(parse_org(org, "service.org").is_err());
+
+        let org = "stray text\n* Welcome\n";
+        assert!(parse_org(org, "service.org").is_err());
+    }
+}
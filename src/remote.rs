@@ -0,0 +1,726 @@
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener as StdTcpListener};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use cosmic::iced::futures::{SinkExt, StreamExt};
+use miette::{IntoDiagnostic, Result, miette};
+use serde::Serialize;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{mpsc, oneshot, watch};
+use tokio_tungstenite::WebSocketStream;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+use tokio_tungstenite::tungstenite::handshake::derive_accept_key;
+use tokio_tungstenite::tungstenite::protocol::Role;
+use tracing::{debug, error};
+
+use crate::core::kinds::ServiceItemKind;
+use crate::core::service_items::ServiceItem;
+use crate::core::slide::{BackgroundKind, Slide};
+use crate::core::thumbnail;
+use crate::ui::presenter::{self, OutputMode};
+
+const MAX_REQUEST_HEAD: usize = 8192;
+/// Wrong PINs a client can send before it has to wait to try again.
+const MAX_PIN_FAILURES: u32 = 5;
+const PIN_LOCKOUT: Duration = Duration::from_secs(60);
+const PAGE: &str = include_str!("../res/remote.html");
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct ItemOutline {
+    pub title: String,
+    pub kind: String,
+    pub slides: Vec<String>,
+    #[serde(skip)]
+    thumbnails: Vec<Thumbnail>,
+}
+
+/// The pictures a slide's thumbnail is stacked from. Only these files are
+/// ever served, never a path a client asks for.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct Thumbnail {
+    background: Option<PathBuf>,
+    text: Option<PathBuf>,
+}
+
+impl From<&Slide> for Thumbnail {
+    fn from(slide: &Slide) -> Self {
+        let path = &slide.background().path;
+        let background = match slide.background().kind {
+            BackgroundKind::Image if path.file_name().is_some() => Some(path.clone()),
+            BackgroundKind::Video if path.file_name().is_some() => {
+                Some(thumbnail::bg_path_from_video(path))
+            }
+            _ => None,
+        };
+        Self {
+            background,
+            text: slide.text_svg.as_ref().and_then(|svg| svg.path.clone()),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Layer {
+    Background,
+    Text,
+}
+
+/// What is on the screen right now.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct Live {
+    pub item: usize,
+    pub slide: usize,
+    pub title: String,
+    pub text: String,
+    pub mode: OutputMode,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct State {
+    pub live: Live,
+    pub service: Vec<ItemOutline>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "event", rename_all = "kebab-case")]
+enum Event<'a> {
+    Service { items: &'a [ItemOutline] },
+    Live(&'a Live),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Command {
+    Next,
+    Prev,
+    Activate(usize, usize),
+    PlayPause,
+    Mode(OutputMode),
+}
+
+impl From<Command> for presenter::Message {
+    fn from(command: Command) -> Self {
+        match command {
+            Command::Next => Self::NextSlide,
+            Command::Prev => Self::PrevSlide,
+            Command::Activate(item, slide) => Self::ActivateSlide(item, slide),
+            Command::PlayPause => Self::PlayPauseVideo,
+            Command::Mode(mode) => Self::ToggleMode(mode),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Route {
+    Page,
+    Thumbnail(usize, usize, Layer),
+    State,
+    Service,
+    Live,
+    Events,
+    Command(Command),
+    MethodNotAllowed,
+    NotFound,
+}
+
+fn route(method: &str, path: &str) -> Route {
+    let path = path.split_once('?').map_or(path, |(path, _)| path);
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    let (route, expected) = match segments.as_slice() {
+        [] => (Route::Page, "GET"),
+        ["thumbnail", item, slide, layer] => {
+            let layer = match *layer {
+                "background" => Layer::Background,
+                "text" => Layer::Text,
+                _ => return Route::NotFound,
+            };
+            match (item.parse(), slide.parse()) {
+                (Ok(item), Ok(slide)) => (Route::Thumbnail(item, slide, layer), "GET"),
+                _ => return Route::NotFound,
+            }
+        }
+        ["state"] => (Route::State, "GET"),
+        ["service"] => (Route::Service, "GET"),
+        ["live"] => (Route::Live, "GET"),
+        ["events"] => (Route::Events, "GET"),
+        ["next"] => (Route::Command(Command::Next), "POST"),
+        ["prev"] => (Route::Command(Command::Prev), "POST"),
+        ["play-pause"] => (Route::Command(Command::PlayPause), "POST"),
+        ["blackout"] => (Route::Command(Command::Mode(OutputMode::Black)), "POST"),
+        ["mode", name] => match OutputMode::ALL
+            .into_iter()
+            .find(|mode| mode.name() == *name)
+        {
+            Some(mode) => (Route::Command(Command::Mode(mode)), "POST"),
+            None => return Route::NotFound,
+        },
+        ["slide", item, slide] => match (item.parse(), slide.parse()) {
+            (Ok(item), Ok(slide)) => {
+                (Route::Command(Command::Activate(item, slide)), "POST")
+            }
+            _ => return Route::NotFound,
+        },
+        _ => return Route::NotFound,
+    };
+    if method == expected {
+        route
+    } else {
+        Route::MethodNotAllowed
+    }
+}
+
+/// The wrong PINs sent from an address.
+#[derive(Clone, Copy, Debug, Default)]
+struct PinFailures {
+    count: u32,
+    locked_until: Option<Instant>,
+}
+
+type Failures = Arc<Mutex<HashMap<IpAddr, PinFailures>>>;
+
+/// A running remote control server. Dropping it stops the server and
+/// waits for it to let go of its port.
+pub(crate) struct Remote {
+    pub address: SocketAddr,
+    state: watch::Sender<State>,
+    service: Option<Arc<Vec<ServiceItem>>>,
+    stop: Option<oneshot::Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Drop for Remote {
+    fn drop(&mut self) {
+        drop(self.stop.take());
+        if let Some(thread) = self.thread.take()
+            && thread.join().is_err()
+        {
+            error!("the remote control server panicked");
+        }
+    }
+}
+
+impl Remote {
+    /// Binds the server and returns the presenter messages sent by
+    /// clients. Only clients that send the PIN can drive the presenter.
+    pub(crate) fn start(
+        port: u16,
+        allow_lan: bool,
+        pin: u32,
+    ) -> Result<(Self, mpsc::UnboundedReceiver<presenter::Message>)> {
+        let host = if allow_lan {
+            Ipv4Addr::UNSPECIFIED
+        } else {
+            Ipv4Addr::LOCALHOST
+        };
+        let listener = StdTcpListener::bind((host, port)).into_diagnostic()?;
+        listener.set_nonblocking(true).into_diagnostic()?;
+        let address = listener.local_addr().into_diagnostic()?;
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .into_diagnostic()?;
+
+        let (state, state_receiver) = watch::channel(State::default());
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let (stop, stopped) = oneshot::channel();
+        let thread = thread::spawn(move || {
+            runtime.block_on(async move {
+                match TcpListener::from_std(listener) {
+                    Ok(listener) => {
+                        serve(listener, state_receiver, sender, pin, stopped).await;
+                    }
+                    Err(e) => error!(?e, "couldn't start the remote control server"),
+                }
+            });
+        });
+        debug!(?address, "remote control server started");
+
+        Ok((
+            Self {
+                address,
+                state,
+                service: None,
+                stop: Some(stop),
+                thread: Some(thread),
+            },
+            receiver,
+        ))
+    }
+
+    /// Tells connected clients about the live slide and, when it changed,
+    /// the service.
+    pub(crate) fn publish(
+        &mut self,
+        service: &Arc<Vec<ServiceItem>>,
+        item: usize,
+        slide: usize,
+        mode: OutputMode,
+    ) {
+        let service_changed = self
+            .service
+            .as_ref()
+            .is_none_or(|old| !Arc::ptr_eq(old, service));
+        if service_changed {
+            self.service = Some(Arc::clone(service));
+        }
+        let live = Live {
+            item,
+            slide,
+            title: service
+                .get(item)
+                .map(|item| item.title.clone())
+                .unwrap_or_default(),
+            text: service
+                .get(item)
+                .and_then(|item| item.slides.get(slide))
+                .map(Slide::text)
+                .unwrap_or_default(),
+            mode,
+        };
+        self.state.send_if_modified(|state| {
+            let mut modified = false;
+            if service_changed {
+                let outline = outline(service);
+                if state.service != outline {
+                    state.service = outline;
+                    modified = true;
+                }
+            }
+            if state.live != live {
+                state.live = live;
+                modified = true;
+            }
+            modified
+        });
+    }
+}
+
+fn outline(service: &[ServiceItem]) -> Vec<ItemOutline> {
+    service
+        .iter()
+        .map(|item| ItemOutline {
+            title: item.title.clone(),
+            kind: match item.kind {
+                ServiceItemKind::Song(_) => "song",
+                ServiceItemKind::Video(_) => "video",
+                ServiceItemKind::Image(_) => "image",
+                ServiceItemKind::Presentation(_) => "presentation",
+                ServiceItemKind::Content(_) => "content",
+            }
+            .to_string(),
+            slides: item.slides.iter().map(Slide::text).collect(),
+            thumbnails: item.slides.iter().map(Thumbnail::from).collect(),
+        })
+        .collect()
+}
+
+async fn serve(
+    listener: TcpListener,
+    state: watch::Receiver<State>,
+    commands: mpsc::UnboundedSender<presenter::Message>,
+    pin: u32,
+    mut stopped: oneshot::Receiver<()>,
+) {
+    let failures = Failures::default();
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, address)) => {
+                    debug!(?address, "remote control connection");
+                    let state = state.clone();
+                    let commands = commands.clone();
+                    let pin = Pin {
+                        pin,
+                        client: address.ip(),
+                        failures: Arc::clone(&failures),
+                    };
+                    tokio::spawn(async move {
+                        if let Err(e) = respond(stream, state, commands, pin).await {
+                            debug!(?e, "remote control connection failed");
+                        }
+                    });
+                }
+                Err(e) => error!(?e, "couldn't accept a remote control connection"),
+            },
+            _ = &mut stopped => {
+                debug!("remote control server stopped");
+                break;
+            }
+        }
+    }
+}
+
+/// Checks the PIN a client sent, locking it out for a while after too
+/// many wrong ones so the PIN can't be guessed.
+struct Pin {
+    pin: u32,
+    client: IpAddr,
+    failures: Failures,
+}
+
+impl Pin {
+    fn check(&self, sent: Option<&str>) -> PinCheck {
+        let Ok(mut failures) = self.failures.lock() else {
+            return PinCheck::LockedOut;
+        };
+        let now = Instant::now();
+        let client = failures.entry(self.client).or_default();
+        if client.locked_until.is_some_and(|until| now < until) {
+            return PinCheck::LockedOut;
+        }
+        if sent.and_then(|sent| sent.parse().ok()) == Some(self.pin) {
+            failures.remove(&self.client);
+            return PinCheck::Right;
+        }
+        client.count += 1;
+        if client.count >= MAX_PIN_FAILURES {
+            client.locked_until = Some(now + PIN_LOCKOUT);
+        }
+        PinCheck::Wrong
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PinCheck {
+    Right,
+    Wrong,
+    LockedOut,
+}
+
+async fn respond(
+    mut stream: TcpStream,
+    state: watch::Receiver<State>,
+    commands: mpsc::UnboundedSender<presenter::Message>,
+    pin: Pin,
+) -> Result<()> {
+    let head = read_head(&mut stream).await?;
+    let mut request_line = head.lines().next().unwrap_or_default().split_whitespace();
+    let (Some(method), Some(path)) = (request_line.next(), request_line.next()) else {
+        return reply(&mut stream, "400 Bad Request", "text/plain", "").await;
+    };
+    // Browsers send the page's origin, so a page from anywhere else
+    // can't drive the presenter or follow it through this one's socket.
+    if let Some(origin) = header(&head, "origin")
+        && header(&head, "host").is_none_or(|host| origin != format!("http://{host}"))
+    {
+        return reply(&mut stream, "403 Forbidden", "text/plain", "").await;
+    }
+
+    match route(method, path) {
+        Route::Page => {
+            reply(&mut stream, "200 OK", "text/html; charset=utf-8", PAGE).await
+        }
+        Route::Thumbnail(item, slide, layer) => {
+            let path = state
+                .borrow()
+                .service
+                .get(item)
+                .and_then(|item| item.thumbnails.get(slide))
+                .and_then(|thumbnail| match layer {
+                    Layer::Background => thumbnail.background.clone(),
+                    Layer::Text => thumbnail.text.clone(),
+                });
+            match path {
+                Some(path) => match tokio::fs::read(&path).await {
+                    Ok(image) => {
+                        reply(&mut stream, "200 OK", image_type(&path), image).await
+                    }
+                    Err(_) => reply(&mut stream, "404 Not Found", "text/plain", "").await,
+                },
+                None => reply(&mut stream, "404 Not Found", "text/plain", "").await,
+            }
+        }
+        Route::State => {
+            let body = serde_json::to_string(&*state.borrow()).into_diagnostic()?;
+            reply(&mut stream, "200 OK", "application/json", &body).await
+        }
+        Route::Service => {
+            let body =
+                serde_json::to_string(&state.borrow().service).into_diagnostic()?;
+            reply(&mut stream, "200 OK", "application/json", &body).await
+        }
+        Route::Live => {
+            let body = serde_json::to_string(&state.borrow().live).into_diagnostic()?;
+            reply(&mut stream, "200 OK", "application/json", &body).await
+        }
+        Route::Command(command) => match pin.check(header(&head, "x-lumina-pin")) {
+            PinCheck::Right if commands.send(command.into()).is_ok() => {
+                reply(&mut stream, "204 No Content", "text/plain", "").await
+            }
+            PinCheck::Right => {
+                reply(&mut stream, "503 Service Unavailable", "text/plain", "").await
+            }
+            PinCheck::Wrong => {
+                reply(&mut stream, "401 Unauthorized", "text/plain", "").await
+            }
+            PinCheck::LockedOut => {
+                reply(&mut stream, "429 Too Many Requests", "text/plain", "").await
+            }
+        },
+        Route::Events => {
+            let Some(key) = header(&head, "sec-websocket-key") else {
+                return reply(&mut stream, "400 Bad Request", "text/plain", "").await;
+            };
+            let accept = derive_accept_key(key.as_bytes());
+            stream
+                .write_all(
+                    format!(
+                        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {accept}\r\n\r\n"
+                    )
+                    .as_bytes(),
+                )
+                .await
+                .into_diagnostic()?;
+            let socket =
+                WebSocketStream::from_raw_socket(stream, Role::Server, None).await;
+            send_events(socket, state).await
+        }
+        Route::MethodNotAllowed => {
+            reply(&mut stream, "405 Method Not Allowed", "text/plain", "").await
+        }
+        Route::NotFound => reply(&mut stream, "404 Not Found", "text/plain", "").await,
+    }
+}
+
+/// The value of a header of the request, matching its name in any case.
+fn header<'a>(head: &'a str, name: &str) -> Option<&'a str> {
+    head.lines().skip(1).find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        key.trim().eq_ignore_ascii_case(name).then(|| value.trim())
+    })
+}
+
+async fn read_head(stream: &mut TcpStream) -> Result<String> {
+    let mut head = Vec::new();
+    let mut buffer = [0; 1024];
+    while !head.windows(4).any(|window| window == b"\r\n\r\n") {
+        if head.len() > MAX_REQUEST_HEAD {
+            return Err(miette!("The request is too long"));
+        }
+        let read = stream.read(&mut buffer).await.into_diagnostic()?;
+        if read == 0 {
+            return Err(miette!("The connection closed before the request ended"));
+        }
+        head.extend_from_slice(&buffer[..read]);
+    }
+    String::from_utf8(head).into_diagnostic()
+}
+
+fn image_type(path: &Path) -> &'static str {
+    match path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_lowercase)
+        .as_deref()
+    {
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("svg") => "image/svg+xml",
+        _ => "application/octet-stream",
+    }
+}
+
+async fn reply(
+    stream: &mut TcpStream,
+    status: &str,
+    content_type: &str,
+    body: impl AsRef<[u8]>,
+) -> Result<()> {
+    let body = body.as_ref();
+    let head = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    );
+    stream.write_all(head.as_bytes()).await.into_diagnostic()?;
+    stream.write_all(body).await.into_diagnostic()?;
+    stream.shutdown().await.into_diagnostic()
+}
+
+async fn send_events(
+    socket: WebSocketStream<TcpStream>,
+    mut state: watch::Receiver<State>,
+) -> Result<()> {
+    let (mut sink, mut incoming) = socket.split();
+    let mut sent = state.borrow_and_update().clone();
+    sink.send(event(&Event::Service {
+        items: &sent.service,
+    })?)
+    .await
+    .into_diagnostic()?;
+    sink.send(event(&Event::Live(&sent.live))?)
+        .await
+        .into_diagnostic()?;
+
+    loop {
+        tokio::select! {
+            changed = state.changed() => {
+                if changed.is_err() {
+                    break;
+                }
+                let current = state.borrow_and_update().clone();
+                if current.service != sent.service {
+                    sink.send(event(&Event::Service { items: &current.service })?)
+                        .await
+                        .into_diagnostic()?;
+                }
+                if current.live != sent.live {
+                    sink.send(event(&Event::Live(&current.live))?)
+                        .await
+                        .into_diagnostic()?;
+                }
+                sent = current;
+            }
+            message = incoming.next() => match message {
+                Some(Ok(WsMessage::Close(_))) | None => break,
+                Some(Err(e)) => return Err(e).into_diagnostic(),
+                Some(Ok(_)) => {}
+            }
+        }
+    }
+    sink.close().await.into_diagnostic()
+}
+
+fn event(event: &Event) -> Result<WsMessage> {
+    serde_json::to_string(event)
+        .map(WsMessage::text)
+        .into_diagnostic()
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn request(address: SocketAddr, request: &str) -> String {
+        request_with(address, request, "")
+    }
+
+    fn request_with(address: SocketAddr, request: &str, headers: &str) -> String {
+        let host = format!("localhost:{}", address.port());
+        let mut stream = TcpStream::connect(address).expect("Should connect");
+        stream
+            .write_all(
+                format!("{request} HTTP/1.1\r\nHost: {host}\r\n{headers}\r\n").as_bytes(),
+            )
+            .expect("Should send the request");
+        let mut response = String::new();
+        stream
+            .read_to_string(&mut response)
+            .expect("Should read the response");
+        response
+    }
+
+    #[test]
+    fn test_routes() {
+        assert_eq!(route("GET", "/state"), Route::State);
+        assert_eq!(route("GET", "/live?since=3"), Route::Live);
+        assert_eq!(route("POST", "/next"), Route::Command(Command::Next));
+        assert_eq!(
+            route("POST", "/slide/2/5"),
+            Route::Command(Command::Activate(2, 5))
+        );
+        assert_eq!(route("GET", "/next"), Route::MethodNotAllowed);
+        assert_eq!(route("OPTIONS", "/prev"), Route::MethodNotAllowed);
+        assert_eq!(route("POST", "/slide/two/5"), Route::NotFound);
+        assert_eq!(route("GET", "/nowhere"), Route::NotFound);
+        assert_eq!(route("GET", "/"), Route::Page);
+        assert_eq!(
+            route("GET", "/thumbnail/1/4/text"),
+            Route::Thumbnail(1, 4, Layer::Text)
+        );
+        assert_eq!(route("GET", "/thumbnail/1/4/../../etc"), Route::NotFound);
+        assert_eq!(
+            route("POST", "/blackout"),
+            Route::Command(Command::Mode(OutputMode::Black))
+        );
+        assert_eq!(
+            route("POST", "/mode/clear-text"),
+            Route::Command(Command::Mode(OutputMode::ClearText))
+        );
+        assert_eq!(route("POST", "/mode/sideways"), Route::NotFound);
+    }
+
+    #[test]
+    fn test_local_server() {
+        let (mut remote, mut commands) =
+            Remote::start(0, false, 123_456).expect("Should start on localhost");
+        let service = Arc::new(vec![
+            ServiceItem::from(crisp::reader::read(r#"(slide (text "Welcome"))"#)),
+            ServiceItem::from(crisp::reader::read(r#"(slide (text "Amazing grace"))"#)),
+        ]);
+        remote.publish(&service, 1, 0, OutputMode::Live);
+
+        let response = request(remote.address, "GET /live");
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.ends_with(
+            r#"{"item":1,"slide":0,"title":"Amazing grace","text":"Amazing grace","mode":"live"}"#
+        ));
+        let response = request(remote.address, "GET /service");
+        assert!(response.contains(r#""slides":["Welcome"]"#));
+
+        let response = request(remote.address, "POST /slide/0/0");
+        assert!(response.starts_with("HTTP/1.1 401"));
+        let response = request_with(
+            remote.address,
+            "POST /slide/0/0",
+            "X-Lumina-Pin: 654321\r\n",
+        );
+        assert!(response.starts_with("HTTP/1.1 401"));
+        let response = request_with(
+            remote.address,
+            "POST /next",
+            "X-Lumina-Pin: 123456\r\nOrigin: http://example.com\r\n",
+        );
+        assert!(response.starts_with("HTTP/1.1 403"));
+        let response = request_with(
+            remote.address,
+            "GET /events",
+            "Origin: http://example.com\r\nUpgrade: websocket\r\n",
+        );
+        assert!(response.starts_with("HTTP/1.1 403"));
+        let response = request_with(
+            remote.address,
+            "POST /slide/0/0",
+            &format!(
+                "X-Lumina-Pin: 123456\r\nOrigin: http://localhost:{}\r\n",
+                remote.address.port()
+            ),
+        );
+        assert!(response.starts_with("HTTP/1.1 204 No Content"));
+        assert!(!response.contains("Access-Control-Allow-Origin"));
+        assert!(matches!(
+            commands.blocking_recv(),
+            Some(presenter::Message::ActivateSlide(0, 0))
+        ));
+        let response = request(remote.address, "GET /");
+        assert!(response.contains("text/html"));
+        let response = request(remote.address, "GET /thumbnail/0/0/background");
+        assert!(response.starts_with("HTTP/1.1 404"));
+        let response = request(remote.address, "GET /play-pause");
+        assert!(response.starts_with("HTTP/1.1 405"));
+
+        for _ in 0..MAX_PIN_FAILURES {
+            let response =
+                request_with(remote.address, "POST /next", "X-Lumina-Pin: 111111\r\n");
+            assert!(response.starts_with("HTTP/1.1 401"));
+        }
+        let response =
+            request_with(remote.address, "POST /next", "X-Lumina-Pin: 123456\r\n");
+        assert!(response.starts_with("HTTP/1.1 429"));
+
+        let port = remote.address.port();
+        drop(remote);
+        let (remote, _commands) =
+            Remote::start(port, false, 123_456).expect("Should start on the same port");
+        let response = request(remote.address, "GET /live");
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+    }
+}
@@ -0,0 +1,77 @@
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use miette::{LabeledSpan, NamedSource, Report, Result, miette};
+use tracing::error;
+
+use crate::core::slide::{Background, ParseError};
+
+/// A service file being parsed, for pointing errors at the text they
+/// came from and finding the files it names.
+pub struct Source<'a> {
+    pub text: &'a str,
+    name: String,
+    directory: PathBuf,
+}
+
+impl<'a> Source<'a> {
+    #[must_use]
+    pub fn new(text: &'a str, path: &Path) -> Self {
+        Self {
+            text,
+            name: path.display().to_string(),
+            directory: path.parent().map(Path::to_path_buf).unwrap_or_default(),
+        }
+    }
+
+    /// Where a slice of the source text sits in it.
+    fn span(&self, text: &str) -> Range<usize> {
+        let start = text.as_ptr() as usize - self.text.as_ptr() as usize;
+        start..start + text.len()
+    }
+
+    /// An error labelling `text`, which has to be a slice of the source.
+    pub fn error(&self, text: &str, label: &str, message: impl AsRef<str>) -> Report {
+        miette!(
+            labels = vec![LabeledSpan::at(self.span(text), label)],
+            "{}",
+            message.as_ref()
+        )
+        .with_source_code(NamedSource::new(&self.name, self.text.to_string()))
+    }
+
+    /// Files are found from the home directory for `~/` and from the
+    /// source's directory when they are relative.
+    #[must_use]
+    pub fn path(&self, path: &str) -> PathBuf {
+        let path = path.trim_start_matches("file://");
+        if let Some(rest) = path.strip_prefix("~/")
+            && let Some(home) = dirs::home_dir()
+        {
+            return home.join(rest);
+        }
+        let path = PathBuf::from(path);
+        if path.is_relative() {
+            self.directory.join(path)
+        } else {
+            path
+        }
+    }
+
+    /// Files that can't be a background are an error, ones that can but
+    /// fail to load are only logged.
+    pub fn background(&self, background: &str) -> Result<Option<Background>> {
+        match Background::try_from(self.path(background)) {
+            Ok(background) => Ok(Some(background)),
+            Err(ParseError::NonBackgroundFile) => Err(self.error(
+                background,
+                "unknown kind",
+                "Backgrounds need to be an image, video, pdf or html file",
+            )),
+            Err(e) => {
+                error!("Couldn't load background {background}: {e}");
+                Ok(None)
+            }
+        }
+    }
+}
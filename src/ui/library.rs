@@ -1,8 +1,10 @@
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::Arc;
 
 use cosmic::dialog::file_chooser::open::Dialog;
+use cosmic::dialog::file_chooser::save;
 use cosmic::iced::alignment::Vertical;
 use cosmic::iced::clipboard::dnd::DndAction;
 use cosmic::iced::core::text::{Ellipsize, EllipsizeHeightLimit};
@@ -30,6 +32,8 @@ use crate::core::content::Content;
 use crate::core::images::{self, Image};
 use crate::core::kinds::ServiceItemKind;
 use crate::core::model::{KindWrapper, LibraryKind, Model, Sort, SortDirection};
+use crate::core::openlp::Skipped;
+use crate::core::openlyrics;
 use crate::core::presentations::{self, Presentation};
 use crate::core::service_items::ServiceItem;
 use crate::core::songs::{self, Song, insert_song};
@@ -91,6 +95,9 @@ pub enum Action {
     Task(Task<Message>),
     ToService(ServiceItem),
     CreateSong,
+    /// Something the user should know about, like songs that were
+    /// skipped on import.
+    Notify(String),
     None,
 }
 
@@ -121,6 +128,11 @@ pub enum Message {
     AddFiles(Vec<ServiceItemKind>),
     ReaddSongs(Vec<Song>),
     AddSong,
+    ImportSongs,
+    AddImportedSongs(Option<(Vec<Song>, Vec<Skipped>)>),
+    SkippedSongs(Vec<Skipped>),
+    ExportSong(i32),
+    ExportSongs,
     AddImages(Option<Vec<Image>>),
     AddVideos(Option<Vec<Video>>),
     AddPresentations(Option<Vec<Presentation>>),
@@ -186,6 +198,11 @@ impl<'a> Library {
         }
     }
 
+    #[must_use]
+    pub fn db(&self) -> Arc<SqlitePool> {
+        Arc::clone(&self.db)
+    }
+
     #[must_use]
     pub fn get_song(&self, index: i32) -> Option<&Song> {
         self.song_library.get_item(index)
@@ -261,6 +278,57 @@ impl<'a> Library {
             Message::AddSong => {
                 return Action::CreateSong;
             }
+            Message::ImportSongs => {
+                return Action::Task(Task::perform(
+                    import_songs(self.song_library.items.clone()),
+                    Message::AddImportedSongs,
+                ));
+            }
+            Message::ExportSong(index) => {
+                self.context_menu = None;
+                if let Some(song) = self.song_library.get_item(index).cloned() {
+                    return Action::Task(Task::perform(export_songs(vec![song]), |()| {
+                        Message::None
+                    }));
+                }
+            }
+            Message::ExportSongs => {
+                return Action::Task(Task::perform(
+                    export_songs(self.song_library.items.clone()),
+                    |()| Message::None,
+                ));
+            }
+            Message::AddImportedSongs(None) => (),
+            Message::AddImportedSongs(Some((songs, skipped))) => {
+                debug!(count = songs.len(), "importing songs");
+                let task = Task::perform(
+                    songs::add_songs(
+                        songs,
+                        self.song_library.items.clone(),
+                        Arc::clone(&self.db),
+                    ),
+                    |res| match res {
+                        Ok(songs) => Message::ReaddSongs(songs),
+                        Err(e) => {
+                            error!(?e, "couldn't import songs");
+                            Message::None
+                        }
+                    },
+                );
+                if skipped.is_empty() {
+                    return Action::Task(task);
+                }
+                return Action::Task(
+                    task.chain(Task::done(Message::SkippedSongs(skipped))),
+                );
+            }
+            Message::SkippedSongs(skipped) => {
+                let songs = skipped.iter().map(ToString::to_string).join(", ");
+                return Action::Notify(match skipped.len() {
+                    1 => format!("Skipped {songs}"),
+                    count => format!("Skipped {count} songs: {songs}"),
+                });
+            }
             Message::AddSongFromEditor(song) => {
                 let after_task = Task::done(Message::AccessItem(Some((
                     LibraryKind::Song,
@@ -968,8 +1036,24 @@ impl<'a> Library {
                 .on_input(Message::SearchLibrary),
             };
 
+            let import_button: Element<Message> = if model.kind == LibraryKind::Song {
+                rowm!(
+                    button::icon(icon::from_name("document-import-symbolic"))
+                        .icon_size(theme::spacing().space_l)
+                        .tooltip("Import songs")
+                        .on_press(Message::ImportSongs),
+                    button::icon(icon::from_name("document-export-symbolic"))
+                        .icon_size(theme::spacing().space_l)
+                        .tooltip("Export all songs")
+                        .on_press(Message::ExportSongs)
+                )
+                .into()
+            } else {
+                Space::new().into()
+            };
             let library_toolbar = rowm!(
                 search_bar,
+                import_button,
                 button::icon(icon::from_name("list-add-symbolic"))
                     .icon_size(theme::spacing().space_l)
                     .on_press(Message::AddItem)
@@ -1149,15 +1233,20 @@ impl<'a> Library {
                     .on_press(message)
             };
 
-            let menu_items = column![
+            let mut menu_items = column![
                 menu_item("Open", Message::AccessItem(Some((library, id)))),
                 menu_item("Delete", Message::DeleteItem),
-            ]
-            .spacing(theme::spacing().space_s)
-            .apply(container)
-            .width(300)
-            .padding(theme::spacing().space_s)
-            .class(theme::Container::Dropdown);
+            ];
+            if library == LibraryKind::Song {
+                menu_items =
+                    menu_items.push(menu_item("Export", Message::ExportSong(id)));
+            }
+            let menu_items = menu_items
+                .spacing(theme::spacing().space_s)
+                .apply(container)
+                .width(300)
+                .padding(theme::spacing().space_s)
+                .class(theme::Container::Dropdown);
             let context_menu = popover(item)
                 .position(popover::Position::Point(self.context_point))
                 .on_close(Message::OpenContext(None))
@@ -1492,6 +1581,48 @@ async fn add_presentations() -> Option<Vec<Presentation>> {
     )
 }
 
+async fn import_songs(existing: Vec<Song>) -> Option<(Vec<Song>, Vec<Skipped>)> {
+    let paths = Dialog::new()
+        .title("import songs")
+        .open_files()
+        .await
+        .ok()?;
+    let paths: Vec<PathBuf> = paths
+        .urls()
+        .iter()
+        .filter_map(|url| url.to_file_path().ok())
+        .collect();
+    Some(songs::import_files(&paths, &existing).await)
+}
+
+/// A single song is saved as OpenLyrics xml, more than one is bundled
+/// into a tar of xml files.
+async fn export_songs(songs: Vec<Song>) {
+    let file_name = match songs.as_slice() {
+        [song] => format!("{}.xml", song.title),
+        _ => "songs.tar".into(),
+    };
+    let dialog = save::Dialog::new()
+        .title("export songs")
+        .file_name(file_name);
+    let Some(path) = save::file(dialog)
+        .await
+        .ok()
+        .and_then(|response| response.url().and_then(|url| url.to_file_path().ok()))
+    else {
+        return;
+    };
+    let result = match songs.as_slice() {
+        [song] => openlyrics::export_song(song, &path),
+        songs => openlyrics::export_songs(songs, &path).map(|count| {
+            debug!(count, ?path, "exported songs");
+        }),
+    };
+    if let Err(e) = result {
+        error!(?e, ?path, "couldn't export songs");
+    }
+}
+
 pub async fn add_db() -> Result<SqlitePool> {
     let mut data = dirs::data_local_dir().expect("Should always find a data dir");
     data.push("lumina");
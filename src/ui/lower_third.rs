@@ -0,0 +1,70 @@
+use std::sync::Arc;
+
+use cosmic::iced::{ContentFit, Length, Size};
+use cosmic::widget::{Image, Space, container};
+use cosmic::{Apply, Element};
+use resvg::usvg::fontdb;
+
+use crate::core::settings::LowerThird;
+use crate::core::slide::Slide;
+use crate::ui::text_svg::{Font, TextSvg};
+
+/// Renders just the text of the slide the way the lower third profile
+/// wants it, the backgrounds are left out so the stream can key them.
+#[must_use]
+pub fn render(
+    slide: &Slide,
+    profile: LowerThird,
+    fontdb: &Arc<fontdb::Database>,
+) -> Option<TextSvg> {
+    if slide.text().trim().is_empty() {
+        return None;
+    }
+    let font = slide
+        .font()
+        .unwrap_or_else(|| Font::from("Quicksand"))
+        .size(profile.font_size);
+    let text_svg = TextSvg::new(slide.text())
+        .font(font)
+        .alignment(profile.text_alignment)
+        .margins(profile.margins)
+        .fill(slide.text_color().unwrap_or_else(|| "#fff".into()));
+    let text_svg = if let Some(stroke) = slide.stroke() {
+        text_svg.stroke(stroke)
+    } else {
+        text_svg
+    };
+    let text_svg = if let Some(shadow) = slide.shadow() {
+        text_svg.shadow(shadow)
+    } else {
+        text_svg
+    };
+    Some(
+        text_svg
+            .fontdb(Arc::clone(fontdb))
+            .build(Size::new(1920.0, 1080.0), None),
+    )
+}
+
+pub fn view<'a, Message: 'a>(
+    text: Option<&TextSvg>,
+    profile: LowerThird,
+) -> Element<'a, Message> {
+    let background = profile.background.color();
+    text.and_then(|text| text.handle.clone())
+        .map_or_else(
+            || Element::from(Space::new().width(Length::Fill).height(Length::Fill)),
+            |handle| {
+                Image::new(handle)
+                    .content_fit(ContentFit::Contain)
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .into()
+            },
+        )
+        .apply(container)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .style(move |_| container::background(background))
+        .into()
+}
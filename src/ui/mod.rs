@@ -1,8 +1,11 @@
+use cosmic::widget::text_editor;
+
 use crate::core::model::LibraryKind;
 
 // pub mod double_ended_slider;
 pub mod image_editor;
 pub mod library;
+pub mod lower_third;
 pub mod presentation_editor;
 pub mod presenter;
 // pub mod service;
@@ -11,6 +14,7 @@ pub mod image_loader;
 pub mod scroll_operations;
 pub mod slide_editor;
 pub mod song_editor;
+pub mod stage_display;
 pub mod text_svg;
 pub mod video_editor;
 pub mod widgets;
@@ -33,3 +37,9 @@ impl From<LibraryKind> for EditorMode {
         }
     }
 }
+
+/// The text of an editor without the line break it always ends with.
+#[must_use]
+pub fn editor_text(content: &text_editor::Content) -> String {
+    content.text().trim_end_matches('\n').to_string()
+}
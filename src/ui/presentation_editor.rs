@@ -4,6 +4,7 @@ use std::ops::RangeBounds;
 use std::path::{Path, PathBuf};
 
 use crate::core::presentations::{PresKind, Presentation};
+use crate::ui::editor_text;
 use crate::ui::widgets::loaded_image::loaded_image;
 use cosmic::dialog::file_chooser::FileFilter;
 use cosmic::dialog::file_chooser::open::Dialog;
@@ -14,7 +15,7 @@ use cosmic::widget::image::Handle;
 use cosmic::widget::space::{self, horizontal};
 use cosmic::widget::{
     self, Space, button, container, context_menu, icon, menu, mouse_area, scrollable,
-    text, text_input,
+    text, text_editor, text_input,
 };
 use cosmic::{Element, Task, theme};
 use miette::{IntoDiagnostic, Result, miette};
@@ -30,6 +31,7 @@ pub struct PresentationEditor {
     page_count: Option<i32>,
     current_slide_index: Option<i32>,
     title: String,
+    notes: text_editor::Content,
     editing: bool,
     hovered_slide: Option<i32>,
     context_menu_id: Option<i32>,
@@ -47,6 +49,7 @@ pub enum Message {
     ChangePresentation(Presentation),
     Update(Presentation),
     ChangeTitle(String),
+    ChangeNotes(text_editor::Action),
     PickPresentation,
     Edit(bool),
     NextPage,
@@ -80,11 +83,12 @@ impl menu::Action for MenuAction {
 
 impl PresentationEditor {
     #[must_use]
-    pub const fn new() -> Self {
+    pub fn new() -> Self {
         Self {
             presentation: None,
             document: None,
             title: String::new(),
+            notes: text_editor::Content::new(),
             editing: false,
             current_slide: None,
             current_slide_index: None,
@@ -129,6 +133,16 @@ impl PresentationEditor {
                     return self.update(Message::Update(presentation));
                 }
             }
+            Message::ChangeNotes(action) => {
+                let edited = matches!(action, text_editor::Action::Edit(_));
+                self.notes.perform(action);
+                if edited && let Some(presentation) = &self.presentation {
+                    let mut presentation = presentation.clone();
+                    let notes = editor_text(&self.notes);
+                    presentation.notes = (!notes.is_empty()).then_some(notes);
+                    return self.update(Message::Update(presentation));
+                }
+            }
             Message::Edit(edit) => {
                 debug!(edit);
                 self.editing = edit;
@@ -372,7 +386,11 @@ impl PresentationEditor {
             space::horizontal(),
             button::standard("Next Page").on_press(Message::NextPage),
         ];
-        let column = column![self.toolbar(), main_row, control_buttons]
+        let notes = text_editor(&self.notes)
+            .placeholder("Notes...")
+            .on_action(Message::ChangeNotes)
+            .height(100);
+        let column = column![self.toolbar(), main_row, control_buttons, notes]
             .spacing(theme::active().cosmic().space_l());
         column.into()
     }
@@ -443,6 +461,9 @@ impl PresentationEditor {
     fn update_entire_presentation(&mut self, presentation: &Presentation) {
         self.presentation = Some(presentation.clone());
         self.title.clone_from(&presentation.title);
+        self.notes = text_editor::Content::with_text(
+            presentation.notes.as_deref().unwrap_or_default(),
+        );
         self.document =
             Document::open(presentation.path.to_str().unwrap_or_default()).ok();
         self.page_count = self.document.as_ref().and_then(|doc| doc.page_count().ok());
@@ -501,6 +522,7 @@ impl PresentationEditor {
                     },
                     _ => current_presentation.kind.clone(),
                 },
+                notes: current_presentation.notes.clone(),
                 created_at: current_presentation.created_at,
                 accessed_at: current_presentation.accessed_at,
             };
@@ -515,6 +537,7 @@ impl PresentationEditor {
                     },
                     _ => current_presentation.kind.clone(),
                 },
+                notes: current_presentation.notes.clone(),
                 created_at: current_presentation.created_at,
                 accessed_at: current_presentation.accessed_at,
             };
@@ -543,6 +566,7 @@ impl PresentationEditor {
                     },
                     _ => current_presentation.kind.clone(),
                 },
+                notes: current_presentation.notes.clone(),
                 created_at: current_presentation.created_at,
                 accessed_at: current_presentation.accessed_at,
             };
@@ -557,6 +581,7 @@ impl PresentationEditor {
                     },
                     _ => current_presentation.kind.clone(),
                 },
+                notes: current_presentation.notes.clone(),
                 created_at: current_presentation.created_at,
                 accessed_at: current_presentation.accessed_at,
             };
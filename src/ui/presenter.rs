@@ -27,13 +27,16 @@ use iced_video_player::{Position, Video, VideoPlayer, gst_pbutils};
 use obws::Client;
 use obws::responses::scenes::Scene;
 use rodio::{Decoder, MixerDeviceSink, Player, Source};
+use serde::Serialize;
 use tracing::{debug, error, info, warn};
 use url::Url;
 
+use crate::core::animation::Easing;
 use crate::core::kinds::ServiceItemKind;
 use crate::core::service_items::ServiceItem;
 use crate::core::slide::Slide;
 use crate::core::slide_actions::{self, ObsAction};
+use crate::core::songs::Song;
 use crate::ui::gst_video::{self, VideoSettings};
 use crate::ui::image_loader::ImageLoader;
 use crate::ui::scroll_operations::{self, focus_target};
@@ -44,6 +47,75 @@ use crate::{BackgroundKind, ViewMode};
 
 // const REFERENCE_WIDTH: f32 = 1920.0;
 static DEFAULT_SLIDE: LazyLock<Slide> = LazyLock::new(Slide::default);
+const MODE_ANIMATION: crate::core::animation::Animation =
+    crate::core::animation::Animation::CrossFade {
+        duration: Some(Duration::from_millis(300)),
+        easing: Some(Easing::EaseInOut),
+    };
+
+/// What the live output shows in place of, or on top of, the current slide.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OutputMode {
+    #[default]
+    Live,
+    Black,
+    ClearText,
+    Freeze,
+    Logo,
+}
+
+impl OutputMode {
+    pub const ALL: [Self; 5] = [
+        Self::Live,
+        Self::Black,
+        Self::ClearText,
+        Self::Freeze,
+        Self::Logo,
+    ];
+
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Live => "live",
+            Self::Black => "black",
+            Self::ClearText => "clear-text",
+            Self::Freeze => "freeze",
+            Self::Logo => "logo",
+        }
+    }
+
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Live => "Live",
+            Self::Black => "Black",
+            Self::ClearText => "Clear Text",
+            Self::Freeze => "Freeze",
+            Self::Logo => "Logo",
+        }
+    }
+
+    const fn text_opacity(self) -> f32 {
+        if matches!(self, Self::ClearText) {
+            0.0
+        } else {
+            1.0
+        }
+    }
+
+    const fn black_opacity(self) -> f32 {
+        if matches!(self, Self::Black | Self::Logo) {
+            1.0
+        } else {
+            0.0
+        }
+    }
+
+    const fn logo_opacity(self) -> f32 {
+        if matches!(self, Self::Logo) { 1.0 } else { 0.0 }
+    }
+}
 
 // #[derive(Default, Clone, Debug)]
 #[allow(dead_code)]
@@ -68,7 +140,6 @@ pub(crate) struct Presenter {
     scroll_id: Id,
     active_slide_id: Id,
     current_font: Font,
-    slide_action_map: Option<HashMap<(usize, usize), Vec<slide_actions::Action>>>,
     obs_client: Option<Arc<Client>>,
     context_menu_id: Option<(usize, usize)>,
     context_point: Point,
@@ -79,6 +150,14 @@ pub(crate) struct Presenter {
     animator: Option<Animation<bool>>,
     now: Instant,
     pub view_mode: ViewMode,
+    live_item: Option<usize>,
+    live_song: Option<Song>,
+    pub mode: OutputMode,
+    previous_mode: OutputMode,
+    mode_animator: Option<Animation<bool>>,
+    frozen_slide: Option<Slide>,
+    paused_for_freeze: bool,
+    pub logo: Option<PathBuf>,
 }
 
 #[allow(dead_code)]
@@ -87,6 +166,7 @@ pub(crate) enum Action {
     NextSlide,
     PrevSlide,
     ChangeSlide(usize, usize),
+    SetSlideActions(usize, usize, Vec<slide_actions::Action>),
     None,
 }
 
@@ -97,6 +177,7 @@ pub(crate) enum Message {
     NextSlide,
     PrevSlide,
     ActivateSlide(usize, usize),
+    JumpToVerse(String),
     EndVideo,
     StartVideo,
     VideoPos(f64),
@@ -114,6 +195,7 @@ pub(crate) enum Message {
     AddObsClient(Arc<Client>),
     AssignSlideAction(slide_actions::Action),
     PlayPauseVideo,
+    ToggleMode(OutputMode),
     CloseContextMenu,
     ChangePreviewSize(f64),
     Tick(Instant),
@@ -229,7 +311,6 @@ impl Presenter {
             scroll_id: Id::unique(),
             active_slide_id: Id::unique(),
             current_font: cosmic::font::default(),
-            slide_action_map: None,
             obs_client: None,
             context_menu_id: None,
             context_point: Point::ORIGIN,
@@ -238,6 +319,14 @@ impl Presenter {
             preview_size: 100.0,
             animation: None,
             animator: None,
+            live_item: None,
+            live_song: None,
+            mode: OutputMode::Live,
+            previous_mode: OutputMode::Live,
+            mode_animator: None,
+            frozen_slide: None,
+            paused_for_freeze: false,
+            logo: None,
             now: Instant::now(),
             view_mode: ViewMode::Row,
         }
@@ -267,6 +356,14 @@ impl Presenter {
                     .get(item_index)
                     .and_then(|item| item.slides.get(slide_index))
                 {
+                    if self.live_item != Some(item_index) {
+                        self.live_item = Some(item_index);
+                        if let Some(ServiceItemKind::Song(song)) =
+                            self.service.get(item_index).map(|item| &item.kind)
+                        {
+                            self.live_song = Some(song.clone());
+                        }
+                    }
                     self.current_item_index = item_index;
                     self.current_slide_index = slide_index;
                     return self.change_slide(slide.clone());
@@ -306,66 +403,29 @@ impl Presenter {
                 };
                 let new_scene = &scenes[scene_index];
                 debug!(?scenes, ?new_scene, "updating obs actions");
-                if let Some(map) = self.slide_action_map.as_mut() {
-                    if let Some(actions) = map.get_mut(&slide_id) {
-                        let mut altered_actions = vec![];
-                        for action in actions.iter_mut() {
-                            match action {
-                                slide_actions::Action::Obs {
-                                    action: ObsAction::Scene { .. },
-                                } => altered_actions.push(slide_actions::Action::Obs {
-                                    action: ObsAction::Scene {
-                                        scene: new_scene.clone(),
-                                    },
-                                }),
-                                _ => altered_actions.push(action.to_owned()),
-                            }
+                let mut actions = self.slide_actions(slide_id);
+                actions.retain(|action| {
+                    !matches!(
+                        action,
+                        slide_actions::Action::Obs {
+                            action: ObsAction::Scene { .. },
                         }
-                        *actions = altered_actions;
-                        debug!("updating the obs scene {:?}", new_scene);
-                    } else if map
-                        .insert(
-                            slide_id,
-                            vec![slide_actions::Action::Obs {
-                                action: ObsAction::Scene {
-                                    scene: new_scene.clone(),
-                                },
-                            }],
-                        )
-                        .is_none()
-                    {
-                        debug!("adding the obs scene {:?}", new_scene);
-                    } else {
-                        debug!("updating the obs scene {:?}", new_scene);
-                    }
-                } else {
-                    let mut map = HashMap::new();
-                    map.insert(
-                        slide_id,
-                        vec![slide_actions::Action::Obs {
-                            action: ObsAction::Scene {
-                                scene: new_scene.clone(),
-                            },
-                        }],
-                    );
-                    self.slide_action_map = Some(map);
-                }
+                    )
+                });
+                actions.push(slide_actions::Action::Obs {
+                    action: ObsAction::Scene {
+                        scene: new_scene.clone(),
+                    },
+                });
+                return Action::SetSlideActions(slide_id.0, slide_id.1, actions);
             }
             Message::AssignSlideAction(action) => {
                 let slide_id = self.context_menu_id.expect(
                     "In this match we should always already have a context menu id",
                 );
-                if let Some(map) = self.slide_action_map.as_mut() {
-                    if let Some(actions) = map.get_mut(&slide_id) {
-                        actions.push(action);
-                    } else {
-                        map.insert(slide_id, vec![action]);
-                    }
-                } else {
-                    let mut map = HashMap::new();
-                    map.insert(slide_id, vec![action]);
-                    self.slide_action_map = Some(map);
-                }
+                let mut actions = self.slide_actions(slide_id);
+                actions.push(action);
+                return Action::SetSlideActions(slide_id.0, slide_id.1, actions);
             }
             Message::ChangePreviewSize(size) => {
                 self.preview_size = size as f32;
@@ -407,6 +467,36 @@ impl Presenter {
                     video.set_looping(self.current_slide.video_loop());
                 }
             }
+            Message::JumpToVerse(label) => {
+                if let Some(slide) = self.verse_slide(&label) {
+                    return self
+                        .update(Message::ActivateSlide(self.current_item_index, slide));
+                }
+            }
+            Message::ToggleMode(mode) => {
+                let mode = if self.mode == mode {
+                    OutputMode::Live
+                } else {
+                    mode
+                };
+                if mode == OutputMode::Freeze {
+                    self.frozen_slide = Some(self.current_slide.clone());
+                    if let Some(video) = &mut self.presentation_video
+                        && !video.paused()
+                    {
+                        video.set_paused(true);
+                        self.paused_for_freeze = true;
+                    }
+                } else if self.paused_for_freeze {
+                    if let Some(video) = &mut self.presentation_video {
+                        video.set_paused(false);
+                    }
+                    self.paused_for_freeze = false;
+                }
+                self.previous_mode = self.mode;
+                self.mode = mode;
+                self.mode_animator = Some(MODE_ANIMATION.get_animator(self.now));
+            }
             Message::PlayPauseVideo => {
                 if let Some(video) = &mut self.preview_video {
                     video.set_paused(!video.paused());
@@ -508,7 +598,89 @@ impl Presenter {
         Action::None
     }
 
+    /// The live output, with the current mode faded over the slide.
     pub fn view(&self) -> Element<Message> {
+        let progress = self.mode_progress();
+        let blend = |layer: fn(OutputMode) -> f32| {
+            let from = layer(self.previous_mode);
+            (layer(self.mode) - from).mul_add(progress, from)
+        };
+        let black_opacity = blend(OutputMode::black_opacity);
+        let logo_opacity = blend(OutputMode::logo_opacity);
+
+        let mut layers = stack(vec![
+            self.live_slide_view()
+                .text_opacity(blend(OutputMode::text_opacity))
+                .into(),
+        ]);
+        if black_opacity > 0.0 {
+            layers = layers.push(
+                Container::new(Space::new())
+                    .style(move |_| {
+                        container::background(Background::Color(
+                            Color::BLACK.scale_alpha(black_opacity),
+                        ))
+                    })
+                    .width(Length::Fill)
+                    .height(Length::Fill),
+            );
+        }
+        if logo_opacity > 0.0
+            && let Some(logo) = &self.logo
+        {
+            layers = layers.push(
+                cosmic_image(logo)
+                    .content_fit(ContentFit::Contain)
+                    .opacity(logo_opacity)
+                    .width(Length::Fill)
+                    .height(Length::Fill),
+            );
+        }
+        layers.into()
+    }
+
+    fn live_slide_view(
+        &self,
+    ) -> widgets::slide::Slide<'_, Message, cosmic::Theme, cosmic::Renderer> {
+        if let Some(frozen_slide) = &self.frozen_slide {
+            if self.mode == OutputMode::Freeze {
+                let same_background =
+                    frozen_slide.background() == self.current_slide.background();
+                return slide(
+                    frozen_slide,
+                    None,
+                    None,
+                    same_background.then(|| self.live_video()).flatten(),
+                    crate::ui::widgets::slide::SlideSettings {
+                        delegate: !same_background,
+                        hide_mouse: false,
+                        animation: None,
+                        now: self.now,
+                    },
+                );
+            }
+            let progress = self.mode_progress();
+            if self.previous_mode == OutputMode::Freeze && progress < 1.0 {
+                return slide(
+                    &self.current_slide,
+                    Some(frozen_slide),
+                    None,
+                    self.live_video(),
+                    crate::ui::widgets::slide::SlideSettings {
+                        delegate: false,
+                        hide_mouse: false,
+                        animation: Some(&MODE_ANIMATION),
+                        now: self.now,
+                    },
+                )
+                .animate(AnimationState::Running {
+                    direction: crate::ui::widgets::slide::Direction::Forward,
+                    new_slide_progress: progress,
+                    prev_slide_progress: 1.0 - progress,
+                });
+            }
+        }
+
         let settings = crate::ui::widgets::slide::SlideSettings {
             delegate: false,
             hide_mouse: false,
@@ -516,26 +688,11 @@ impl Presenter {
             now: self.now,
         };
 
-        let video: Option<Element<Message>> =
-            self.presentation_video.as_ref().map(|video| {
-                VideoPlayer::new(video)
-                    .mouse_hidden(settings.hide_mouse)
-                    .width(Length::Fill)
-                    .height(Length::Fill)
-                    .on_end_of_stream(Message::EndVideo)
-                    .on_new_frame(Message::VideoFrame)
-                    .on_missing_plugin(Message::MissingPlugin)
-                    .on_warning(|w| Message::Error(w.to_string()))
-                    .on_error(|e| Message::Error(e.to_string()))
-                    .content_fit(ContentFit::Contain)
-                    .into()
-            });
-
         slide(
             &self.current_slide,
             self.old_slide.as_ref(),
             self.next_slide.as_ref(),
-            video,
+            self.live_video(),
             settings,
         )
         .animate({
@@ -555,7 +712,28 @@ impl Presenter {
                 AnimationState::Idle
             }
         })
-        .into()
+    }
+
+    fn mode_progress(&self) -> f32 {
+        self.mode_animator
+            .as_ref()
+            .map_or(1.0, |animator| animator.interpolate(0.0, 1.0, self.now))
+    }
+
+    fn live_video(&self) -> Option<Element<Message>> {
+        self.presentation_video.as_ref().map(|video| {
+            VideoPlayer::new(video)
+                .mouse_hidden(false)
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .on_end_of_stream(Message::EndVideo)
+                .on_new_frame(Message::VideoFrame)
+                .on_missing_plugin(Message::MissingPlugin)
+                .on_warning(|w| Message::Error(w.to_string()))
+                .on_error(|e| Message::Error(e.to_string()))
+                .content_fit(self.background_fit())
+                .into()
+        })
     }
 
     pub fn view_preview(&self) -> Element<Message> {
@@ -575,7 +753,7 @@ impl Presenter {
                 .on_missing_plugin(Message::MissingPlugin)
                 .on_warning(|w| Message::Error(w.to_string()))
                 .on_error(|e| Message::Error(e.to_string()))
-                .content_fit(ContentFit::Contain)
+                .content_fit(self.background_fit())
                 .into()
         });
 
@@ -1007,31 +1185,63 @@ impl Presenter {
         self.total_slides = total_slides;
     }
 
+    pub fn reload_items(&mut self, items: Arc<Vec<ServiceItem>>) -> Action {
+        self.update_items(items);
+        let item_index = self
+            .current_item_index
+            .min(self.service.len().saturating_sub(1));
+        let Some(item) = self.service.get(item_index) else {
+            self.current_item_index = 0;
+            self.current_slide_index = 0;
+            return Action::None;
+        };
+        let slide_index = self
+            .current_slide_index
+            .min(item.slides.len().saturating_sub(1));
+        let slide = item.slides.get(slide_index).cloned();
+        self.current_item_index = item_index;
+        self.current_slide_index = slide_index;
+        match slide {
+            Some(slide) if slide != self.current_slide => self.change_slide(slide),
+            _ => Action::None,
+        }
+    }
+
+    fn background_fit(&self) -> ContentFit {
+        self.current_slide.background().content_fit()
+    }
+
+    fn slide_actions(
+        &self,
+        (item_index, slide_index): (usize, usize),
+    ) -> Vec<slide_actions::Action> {
+        self.service
+            .get(item_index)
+            .and_then(|item| item.slides.get(slide_index))
+            .map(|slide| slide.actions().to_vec())
+            .unwrap_or_default()
+    }
+
     pub fn run_slide_actions(&self) -> Task<Message> {
         let mut tasks = vec![];
 
-        if let Some(map) = &self.slide_action_map
-            && let Some(actions) =
-                map.get(&(self.current_item_index, self.current_slide_index))
-        {
-            for action in actions {
-                match action {
-                    slide_actions::Action::Obs { action } => {
-                        debug!("found obs slide actions");
-                        if let Some(obs) = &self.obs_client {
-                            let obs = Arc::clone(obs);
-                            let action = action.to_owned();
-                            let task = Task::perform(
-                                async move { action.run(obs).await },
-                                |res| {
-                                    debug!(?res);
-                                    Message::None
-                                },
-                            );
-                            tasks.push(task);
-                        }
+        for action in self.current_slide.actions() {
+            match action {
+                slide_actions::Action::Obs { action } => {
+                    debug!("found obs slide actions");
+                    if let Some(obs) = &self.obs_client {
+                        let obs = Arc::clone(obs);
+                        let action = action.to_owned();
+                        let task =
+                            Task::perform(async move { action.run(obs).await }, |res| {
+                                debug!(?res);
+                                Message::None
+                            });
+                        tasks.push(task);
                     }
-                    slide_actions::Action::Other => todo!(),
+                }
+                slide_actions::Action::Other => {
+                    debug!("skipping an unknown slide action");
                 }
             }
         }
@@ -1067,6 +1277,12 @@ impl Presenter {
         }
     }
 
+    /// The song that went live since the last time this was called, for
+    /// the usage log.
+    pub fn take_live_song(&mut self) -> Option<Song> {
+        self.live_song.take()
+    }
+
     #[allow(clippy::too_many_lines)]
     fn change_slide(&mut self, slide: Slide) -> Action {
         let slide_text = slide.text();
@@ -1137,7 +1353,7 @@ impl Presenter {
             ViewMode::Detail => todo!(),
         };
 
-        if self.slide_action_map.is_some() {
+        if !self.current_slide.actions().is_empty() {
             debug!("Found slide actions, running them");
             tasks.push(self.run_slide_actions());
         }
@@ -1202,6 +1418,69 @@ impl Presenter {
         }
         None
     }
+
+    /// The slide that comes after the current one, even when it's in the
+    /// next item.
+    pub(crate) fn upcoming_slide(&self) -> Option<&Slide> {
+        let (item, slide) = self.next_slide()?;
+        self.service.get(item)?.slides.get(slide)
+    }
+
+    /// Speaker notes for the live slide followed by the notes for its item.
+    pub(crate) fn notes(&self) -> String {
+        let item_notes = self
+            .service
+            .get(self.current_item_index)
+            .map(|item| item.notes.clone())
+            .unwrap_or_default();
+        [self.current_slide.notes(), item_notes]
+            .into_iter()
+            .filter(|notes| !notes.trim().is_empty())
+            .collect::<Vec<String>>()
+            .join("\n\n")
+    }
+
+    /// The name of the verse on the current slide if a song is live.
+    pub(crate) fn verse_label(&self) -> Option<String> {
+        let item = self.service.get(self.current_item_index)?;
+        if !matches!(item.kind, ServiceItemKind::Song(_)) {
+            return None;
+        }
+        item.slides.get(self.current_slide_index).map(Slide::verse)
+    }
+
+    fn verse_slide(&self, label: &str) -> Option<usize> {
+        let item = self.service.get(self.current_item_index)?;
+        if !matches!(item.kind, ServiceItemKind::Song(_)) {
+            return None;
+        }
+        let names: Vec<String> = item.slides.iter().map(Slide::verse).collect();
+        verse_start(&names, label, self.current_slide_index)
+    }
+}
+
+/// The first slide of the next verse after `current` whose name is `label`,
+/// or starts with it like `Chorus 2` does `Chorus`, wrapping to the start.
+fn verse_start(names: &[String], label: &str, current: usize) -> Option<usize> {
+    let label = label.trim().to_lowercase();
+    let starts: Vec<usize> = names
+        .iter()
+        .enumerate()
+        .filter(|(index, name)| {
+            let lowercase = name.to_lowercase();
+            (lowercase == label
+                || lowercase
+                    .strip_prefix(&label)
+                    .is_some_and(|rest| rest.starts_with(' ')))
+                && (*index == 0 || names[index - 1] != **name)
+        })
+        .map(|(index, _)| index)
+        .collect();
+    starts
+        .iter()
+        .find(|index| **index > current)
+        .or_else(|| starts.first())
+        .copied()
 }
 
 pub struct SlideSettings<'a> {
@@ -1234,7 +1513,7 @@ pub(crate) fn slide_view<'a>(
                     stack.push(slide.background().image_allocation.as_ref().map_or_else(
                         || {
                             cosmic_image(&slide.background().path)
-                                .content_fit(ContentFit::Contain)
+                                .content_fit(slide.background().content_fit())
                                 .width(width)
                                 .height(Length::Fill)
                                 .apply(container)
@@ -1245,7 +1524,7 @@ pub(crate) fn slide_view<'a>(
                             loaded_image(
                                 allocation.handle(),
                                 cosmic_image(allocation.handle())
-                                    .content_fit(ContentFit::Contain)
+                                    .content_fit(slide.background().content_fit())
                                     .width(width)
                                     .height(Length::Fill),
                             )
@@ -1270,7 +1549,7 @@ pub(crate) fn slide_view<'a>(
                                 .on_missing_plugin(Message::MissingPlugin)
                                 .on_warning(|w| Message::Error(w.to_string()))
                                 .on_error(|e| Message::Error(e.to_string()))
-                                .content_fit(ContentFit::Contain),
+                                .content_fit(slide.background().content_fit()),
                         )
                         .center(Length::Fill)
                         .clip(true),
@@ -1286,7 +1565,7 @@ pub(crate) fn slide_view<'a>(
                             loaded_image(
                                 allocation.handle(),
                                 cosmic_image(allocation.handle())
-                                    .content_fit(ContentFit::Contain)
+                                    .content_fit(slide.background().content_fit())
                                     .width(width)
                                     .height(Length::Fill),
                             )
@@ -1425,6 +1704,22 @@ mod test {
     use pretty_assertions::assert_eq;
     use sqlx::types::chrono::Local;
 
+    #[test]
+    fn test_verse_start() {
+        let names = [
+            "Verse 1", "Verse 1", "Chorus 1", "Chorus 1", "Verse 2", "Chorus 1",
+            "Bridge 1",
+        ]
+        .map(String::from);
+        assert_eq!(verse_start(&names, "chorus", 0), Some(2));
+        assert_eq!(verse_start(&names, "Chorus 1", 3), Some(5));
+        assert_eq!(verse_start(&names, "Chorus", 6), Some(2));
+        assert_eq!(verse_start(&names, "Verse 2", 0), Some(4));
+        assert_eq!(verse_start(&names, "Bridge 1", 6), Some(6));
+        assert_eq!(verse_start(&names, "Verse 3", 0), None);
+        assert_eq!(verse_start(&names, "Ver", 0), None);
+    }
+
     #[test]
     fn test_next_slide() {
         let service = test_service();
@@ -1512,6 +1807,7 @@ mod test {
                 starting_index: 0,
                 ending_index: 67,
             },
+            notes: None,
             created_at: Local::now(),
             accessed_at: Local::now(),
         }
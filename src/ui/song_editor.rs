@@ -44,15 +44,50 @@ use tracing::{debug, error};
 
 use crate::core::animation::Animation;
 use crate::core::service_items::ServiceTrait;
-use crate::core::slide::{Slide, TextAlignment};
+use crate::core::slide::{Slide, TextAlignment, TextMargins};
 use crate::core::song_search::{self, OnlineSong};
-use crate::core::songs::{Song, VerseName};
+use crate::core::songs::{CreditDefaults, SlideFit, Song, SongCredits, VerseName};
+use crate::ui::editor_text;
 use crate::ui::slide_editor::SlideEditor;
-use crate::ui::text_svg;
+use crate::ui::text_svg::{self, ShrinkToFit};
 use crate::ui::widgets::draggable;
 use crate::ui::widgets::verse_editor::{self, VerseEditor};
 use crate::{Background, BackgroundKind};
 
+const CREDIT_OPTIONS: [&str; 7] = [
+    "Use Default",
+    SongCredits::LABELS[0],
+    SongCredits::LABELS[1],
+    SongCredits::LABELS[2],
+    SongCredits::LABELS[3],
+    SongCredits::LABELS[4],
+    SongCredits::LABELS[5],
+];
+
+const SLIDE_FITS: [SlideFit; 7] = [
+    SlideFit::BlankLines,
+    SlideFit::MaxLines(2),
+    SlideFit::MaxLines(3),
+    SlideFit::MaxLines(4),
+    SlideFit::MaxLines(6),
+    SlideFit::MaxLines(8),
+    SlideFit::Fit,
+];
+
+const FIT_SIZES: [&str; 10] = [
+    "20", "30", "40", "50", "60", "80", "100", "120", "150", "200",
+];
+
+const SLIDE_FIT_LABELS: [&str; 7] = [
+    "Blank Lines",
+    "2 Lines",
+    "3 Lines",
+    "4 Lines",
+    "6 Lines",
+    "8 Lines",
+    "Fit to Slide",
+];
+
 // This should get refactored into holding a state machine
 // then each state of what is being edited can be caught by the compiler
 #[allow(clippy::struct_excessive_bools)]
@@ -72,6 +107,7 @@ pub struct SongEditor {
     font_size: usize,
     verse_order: String,
     pub lyrics: text_editor::Content,
+    notes: text_editor::Content,
     editing: bool,
     editing_verse_order: bool,
     background: Option<Background>,
@@ -96,6 +132,7 @@ pub struct SongEditor {
     search_input: String,
     search_results: Option<Vec<OnlineSong>>,
     pub genius_token: Option<String>,
+    pub credit_defaults: CreditDefaults,
     hovered_online_song: Option<usize>,
     pub state: State,
 }
@@ -119,6 +156,7 @@ pub enum Message {
     ChangeTitle(String),
     ChangeVerseOrder(String),
     ChangeLyrics(text_editor::Action),
+    ChangeNotes(text_editor::Action),
     ChangeBackground(Result<PathBuf, SongError>),
     UpdateSlides(Vec<Slide>),
     UpdateSlide((usize, Slide)),
@@ -146,6 +184,7 @@ pub enum Message {
     RemoveVerse(usize),
     ToggleAlignmentTools,
     SetTextAlignment(TextAlignment),
+    SetTextMargins(TextMargins),
     ToggleShadowTools,
     UpdateShadowColor(ColorPickerUpdate),
     UpdateShadowSize(usize),
@@ -153,6 +192,12 @@ pub enum Message {
     UpdateShadowOffsetY(usize),
     SelectAnimation(usize),
     ToggleAnimation(bool),
+    SelectCredits(usize),
+    SelectSlideFit(usize),
+    PinBreaks,
+    ToggleShrinkToFit(bool),
+    SelectShrinkMin(usize),
+    SelectShrinkMax(usize),
     ChangeFontWeight,
     SearchUpdate(String),
     SearchSong(String),
@@ -273,6 +318,7 @@ impl SongEditor {
             font_sizes: combo_box::State::new(font_sizes),
             verse_order: String::new(),
             lyrics: text_editor::Content::new(),
+            notes: text_editor::Content::new(),
             editing: false,
             author: String::new(),
             audio: PathBuf::new(),
@@ -385,6 +431,7 @@ impl SongEditor {
             search_input: String::new(),
             search_results: None,
             genius_token,
+            credit_defaults: CreditDefaults::default(),
             hovered_online_song: None,
             state: State::Idle,
         }
@@ -478,6 +525,9 @@ impl SongEditor {
                 if let Some(lyrics) = &song.lyrics {
                     self.lyrics = text_editor::Content::with_text(lyrics);
                 }
+                self.notes = text_editor::Content::with_text(
+                    song.notes.as_deref().unwrap_or_default(),
+                );
                 self.background_video(song.background.as_ref());
                 self.background = song.background.clone();
                 self.song_slides = None;
@@ -489,7 +539,8 @@ impl SongEditor {
                         .collect()
                 });
 
-                let Ok(song_slides) = song.to_slides() else {
+                let Ok(song_slides) = song.slides(&self.credit_defaults, &self.font_db)
+                else {
                     return Action::None;
                 };
 
@@ -621,6 +672,16 @@ impl SongEditor {
                     return Action::Task(self.update_song(&song));
                 }
             }
+            Message::ChangeNotes(action) => {
+                let edited = matches!(action, text_editor::Action::Edit(_));
+                self.notes.perform(action);
+                if edited && let Some(mut song) = self.song.clone() {
+                    let notes = editor_text(&self.notes);
+                    song.notes = (!notes.is_empty()).then_some(notes);
+                    self.song = Some(song.clone());
+                    return Action::UpdateSong(song);
+                }
+            }
             Message::Edit(edit) => {
                 debug!(edit);
                 self.editing = edit;
@@ -943,6 +1004,12 @@ impl SongEditor {
                     return Action::Task(self.update_song(&song));
                 }
             }
+            Message::SetTextMargins(margins) => {
+                if let Some(mut song) = self.song.clone() {
+                    song.text_margins = Some(margins);
+                    return Action::Task(self.update_song(&song));
+                }
+            }
             Message::UpdateShadowSize(index) => {
                 if let Some(song) = &mut self.song
                     && let Some(size_string) = self.shadow_sizes.get(index)
@@ -1037,6 +1104,71 @@ impl SongEditor {
                     return Action::Task(self.update_song(&song));
                 }
             }
+            Message::SelectCredits(index) => {
+                if let Some(mut song) = self.song.clone() {
+                    song.credits = index
+                        .checked_sub(1)
+                        .and_then(|index| SongCredits::ALL.get(index).copied());
+                    return Action::Task(self.update_song(&song));
+                }
+            }
+            Message::SelectSlideFit(index) => {
+                if let Some(mut song) = self.song.clone()
+                    && let Some(fit) = SLIDE_FITS.get(index)
+                {
+                    song.slide_fit = Some(*fit);
+                    return Action::Task(self.update_song(&song));
+                }
+            }
+            Message::ToggleShrinkToFit(toggled) => {
+                if let Some(mut song) = self.song.clone() {
+                    song.shrink_to_fit = toggled.then(|| ShrinkToFit {
+                        min: 40,
+                        max: song
+                            .font_size
+                            .and_then(|size| u8::try_from(size).ok())
+                            .unwrap_or(100)
+                            .max(40),
+                    });
+                    return Action::Task(self.update_song(&song));
+                }
+            }
+            Message::SelectShrinkMin(index) => {
+                if let Some(mut song) = self.song.clone()
+                    && let Some(fit) = song.shrink_to_fit.as_mut()
+                    && let Some(min) =
+                        FIT_SIZES.get(index).and_then(|size| size.parse().ok())
+                {
+                    fit.min = min;
+                    fit.max = fit.max.max(min);
+                    return Action::Task(self.update_song(&song));
+                }
+            }
+            Message::SelectShrinkMax(index) => {
+                if let Some(mut song) = self.song.clone()
+                    && let Some(fit) = song.shrink_to_fit.as_mut()
+                    && let Some(max) =
+                        FIT_SIZES.get(index).and_then(|size| size.parse().ok())
+                {
+                    fit.max = max;
+                    fit.min = fit.min.min(max);
+                    return Action::Task(self.update_song(&song));
+                }
+            }
+            Message::PinBreaks => {
+                if let Some(mut song) = self.song.clone() {
+                    song.pin_breaks(&self.font_db);
+                    self.verses = song.verse_map.as_ref().map(|map| {
+                        map.iter()
+                            .sorted()
+                            .map(|(verse_name, lyric)| {
+                                VerseEditor::new(*verse_name, lyric)
+                            })
+                            .collect()
+                    });
+                    return Action::Task(self.update_song(&song));
+                }
+            }
             Message::ToggleShadowTools => {
                 self.state = match self.state {
                     State::ShadowToolOpen => State::Idle,
@@ -1181,6 +1313,71 @@ impl SongEditor {
             )
             .gap(5.0),
             space::horizontal(),
+            text("Credits:"),
+            dropdown(
+                &CREDIT_OPTIONS,
+                Some(
+                    self.song
+                        .as_ref()
+                        .and_then(|song| song.credits)
+                        .and_then(|credits| {
+                            SongCredits::ALL.iter().position(|c| *c == credits)
+                        })
+                        .map_or(0, |index| index + 1)
+                ),
+                Message::SelectCredits
+            )
+            .gap(5.0),
+        ]
+        .spacing(space_s)
+        .align_y(Vertical::Center)
+        .apply(container)
+        .class(theme::Container::Card)
+        .padding(space_s);
+
+        let shrink_to_fit = self.song.as_ref().and_then(|song| song.shrink_to_fit);
+        let slide_fit_selector = row![
+            text("Split Slides:"),
+            dropdown(
+                &SLIDE_FIT_LABELS,
+                self.song.as_ref().and_then(|song| {
+                    let fit = song.slide_fit.unwrap_or_default();
+                    SLIDE_FITS.iter().position(|option| *option == fit)
+                }),
+                Message::SelectSlideFit
+            )
+            .gap(5.0),
+            checkbox(shrink_to_fit.is_some())
+                .on_toggle(Message::ToggleShrinkToFit)
+                .label("Shrink to Fit"),
+            text("Min:"),
+            dropdown(
+                &FIT_SIZES,
+                shrink_to_fit.and_then(|fit| {
+                    FIT_SIZES
+                        .iter()
+                        .position(|size| *size == fit.min.to_string())
+                }),
+                Message::SelectShrinkMin
+            )
+            .gap(5.0),
+            text("Max:"),
+            dropdown(
+                &FIT_SIZES,
+                shrink_to_fit.and_then(|fit| {
+                    FIT_SIZES
+                        .iter()
+                        .position(|size| *size == fit.max.to_string())
+                }),
+                Message::SelectShrinkMax
+            )
+            .gap(5.0),
+            space::horizontal(),
+            tooltip(
+                button::standard("Pin Breaks").on_press(Message::PinBreaks),
+                "Keep the current slide breaks as blank lines in the lyrics",
+                tooltip::Position::Bottom,
+            ),
         ]
         .spacing(space_s)
         .align_y(Vertical::Center)
@@ -1188,8 +1385,13 @@ impl SongEditor {
         .class(theme::Container::Card)
         .padding(space_s);
 
-        let slide_section =
-            column![audio_elements, slide_preview, animation_selector].spacing(space_s);
+        let slide_section = column![
+            audio_elements,
+            slide_preview,
+            animation_selector,
+            slide_fit_selector
+        ]
+        .spacing(space_s);
         let column = column![
             self.toolbar(),
             row![
@@ -1567,7 +1769,16 @@ impl SongEditor {
         .padding(space_s)
         .class(theme::Container::Card);
 
-        column![top_input_row, verse_order, verse_toolbar]
+        let notes = column![
+            text::heading("Notes"),
+            text_editor(&self.notes)
+                .placeholder("Capo 2, let the band lead in...")
+                .on_action(Message::ChangeNotes)
+                .height(100)
+        ]
+        .spacing(space_s);
+
+        column![top_input_row, verse_order, verse_toolbar, notes]
             .spacing(space_m)
             .width(Length::Fill)
             .into()
@@ -1879,87 +2090,124 @@ impl SongEditor {
         .on_close(Message::ToggleAlignmentTools);
 
         let text_alignment_popup = if self.state == State::AlignmentToolOpen {
+            let margins = self
+                .song
+                .as_ref()
+                .and_then(|song| song.text_margins)
+                .unwrap_or_default();
+            let margin_input =
+                |label, value: u8, set: fn(TextMargins, u8) -> TextMargins| {
+                    column![
+                        text::body(label),
+                        text_input("0", value.to_string())
+                            .on_input(move |value| {
+                                Message::SetTextMargins(set(
+                                    margins,
+                                    value.parse::<u8>().unwrap_or_default().min(100),
+                                ))
+                            })
+                            .width(space_xxxl),
+                    ]
+                    .spacing(space_xxs)
+                };
+            let margin_tools = column![
+                text::heading("Margins (%)"),
+                row![
+                    margin_input("Left", margins.left, |margins, left| TextMargins {
+                        left,
+                        ..margins
+                    }),
+                    margin_input("Top", margins.top, |margins, top| TextMargins {
+                        top,
+                        ..margins
+                    }),
+                    margin_input("Right", margins.right, |margins, right| TextMargins {
+                        right,
+                        ..margins
+                    }),
+                    margin_input("Bottom", margins.bottom, |margins, bottom| {
+                        TextMargins { bottom, ..margins }
+                    }),
+                ]
+                .spacing(space_s),
+                row![
+                    button::standard("Full Screen")
+                        .on_press(Message::SetTextMargins(TextMargins::default())),
+                    button::standard("Lower Third")
+                        .on_press(Message::SetTextMargins(TextMargins::LOWER_THIRD)),
+                ]
+                .spacing(space_s),
+            ]
+            .spacing(space_s);
+            let alignment_grid = grid::grid()
+                .row_spacing(space_s)
+                .column_spacing(space_s)
+                .push_with(
+                    button::icon(icon::from_svg_bytes(BB_TL_ICON).symbolic(true))
+                        .class(theme::Button::Standard)
+                        .padding(space_s)
+                        .on_press(Message::SetTextAlignment(TextAlignment::TopLeft)),
+                    |a| a.column(0).row(0),
+                )
+                .push_with(
+                    button::icon(icon::from_svg_bytes(BB_TC_ICON).symbolic(true))
+                        .class(theme::Button::Standard)
+                        .padding(space_s)
+                        .on_press(Message::SetTextAlignment(TextAlignment::TopCenter)),
+                    |a| a.column(1).row(0),
+                )
+                .push_with(
+                    button::icon(icon::from_svg_bytes(BB_TR_ICON).symbolic(true))
+                        .class(theme::Button::Standard)
+                        .padding(space_s)
+                        .on_press(Message::SetTextAlignment(TextAlignment::TopRight)),
+                    |a| a.column(2).row(0),
+                )
+                .push_with(
+                    button::icon(icon::from_svg_bytes(BB_ML_ICON).symbolic(true))
+                        .class(theme::Button::Standard)
+                        .padding(space_s)
+                        .on_press(Message::SetTextAlignment(TextAlignment::MiddleLeft)),
+                    |a| a.column(0).row(1),
+                )
+                .push_with(
+                    button::icon(icon::from_svg_bytes(BB_MC_ICON).symbolic(true))
+                        .class(theme::Button::Standard)
+                        .padding(space_s)
+                        .on_press(Message::SetTextAlignment(TextAlignment::MiddleCenter)),
+                    |a| a.column(1).row(1),
+                )
+                .push_with(
+                    button::icon(icon::from_svg_bytes(BB_MR_ICON).symbolic(true))
+                        .class(theme::Button::Standard)
+                        .padding(space_s)
+                        .on_press(Message::SetTextAlignment(TextAlignment::MiddleRight)),
+                    |a| a.column(2).row(1),
+                )
+                .push_with(
+                    button::icon(icon::from_svg_bytes(BB_BL_ICON).symbolic(true))
+                        .class(theme::Button::Standard)
+                        .padding(space_s)
+                        .on_press(Message::SetTextAlignment(TextAlignment::BottomLeft)),
+                    |a| a.column(0).row(2),
+                )
+                .push_with(
+                    button::icon(icon::from_svg_bytes(BB_BC_ICON).symbolic(true))
+                        .class(theme::Button::Standard)
+                        .padding(space_s)
+                        .on_press(Message::SetTextAlignment(TextAlignment::BottomCenter)),
+                    |a| a.column(1).row(2),
+                )
+                .push_with(
+                    button::icon(icon::from_svg_bytes(BB_BR_ICON).symbolic(true))
+                        .class(theme::Button::Standard)
+                        .padding(space_s)
+                        .on_press(Message::SetTextAlignment(TextAlignment::BottomRight)),
+                    |a| a.column(2).row(2),
+                );
             text_alignment_popover.popup(
-                grid::grid()
-                    .row_spacing(space_s)
-                    .column_spacing(space_s)
-                    .push_with(
-                        button::icon(icon::from_svg_bytes(BB_TL_ICON).symbolic(true))
-                            .class(theme::Button::Standard)
-                            .padding(space_s)
-                            .on_press(Message::SetTextAlignment(TextAlignment::TopLeft)),
-                        |a| a.column(0).row(0),
-                    )
-                    .push_with(
-                        button::icon(icon::from_svg_bytes(BB_TC_ICON).symbolic(true))
-                            .class(theme::Button::Standard)
-                            .padding(space_s)
-                            .on_press(Message::SetTextAlignment(
-                                TextAlignment::TopCenter,
-                            )),
-                        |a| a.column(1).row(0),
-                    )
-                    .push_with(
-                        button::icon(icon::from_svg_bytes(BB_TR_ICON).symbolic(true))
-                            .class(theme::Button::Standard)
-                            .padding(space_s)
-                            .on_press(Message::SetTextAlignment(TextAlignment::TopRight)),
-                        |a| a.column(2).row(0),
-                    )
-                    .push_with(
-                        button::icon(icon::from_svg_bytes(BB_ML_ICON).symbolic(true))
-                            .class(theme::Button::Standard)
-                            .padding(space_s)
-                            .on_press(Message::SetTextAlignment(
-                                TextAlignment::MiddleLeft,
-                            )),
-                        |a| a.column(0).row(1),
-                    )
-                    .push_with(
-                        button::icon(icon::from_svg_bytes(BB_MC_ICON).symbolic(true))
-                            .class(theme::Button::Standard)
-                            .padding(space_s)
-                            .on_press(Message::SetTextAlignment(
-                                TextAlignment::MiddleCenter,
-                            )),
-                        |a| a.column(1).row(1),
-                    )
-                    .push_with(
-                        button::icon(icon::from_svg_bytes(BB_MR_ICON).symbolic(true))
-                            .class(theme::Button::Standard)
-                            .padding(space_s)
-                            .on_press(Message::SetTextAlignment(
-                                TextAlignment::MiddleRight,
-                            )),
-                        |a| a.column(2).row(1),
-                    )
-                    .push_with(
-                        button::icon(icon::from_svg_bytes(BB_BL_ICON).symbolic(true))
-                            .class(theme::Button::Standard)
-                            .padding(space_s)
-                            .on_press(Message::SetTextAlignment(
-                                TextAlignment::BottomLeft,
-                            )),
-                        |a| a.column(0).row(2),
-                    )
-                    .push_with(
-                        button::icon(icon::from_svg_bytes(BB_BC_ICON).symbolic(true))
-                            .class(theme::Button::Standard)
-                            .padding(space_s)
-                            .on_press(Message::SetTextAlignment(
-                                TextAlignment::BottomCenter,
-                            )),
-                        |a| a.column(1).row(2),
-                    )
-                    .push_with(
-                        button::icon(icon::from_svg_bytes(BB_BR_ICON).symbolic(true))
-                            .class(theme::Button::Standard)
-                            .padding(space_s)
-                            .on_press(Message::SetTextAlignment(
-                                TextAlignment::BottomRight,
-                            )),
-                        |a| a.column(2).row(2),
-                    )
+                column![alignment_grid, margin_tools]
+                    .spacing(space_m)
                     .apply(container)
                     .padding(space_s)
                     .class(theme::Container::custom(floating_container_style)),
@@ -2205,7 +2453,10 @@ impl SongEditor {
     fn update_song(&mut self, song: &Song) -> Task<Message> {
         // I think this implementation is faster
         let mut tasks = Vec::with_capacity(2);
-        if let Ok(slides) = song.to_slides() {
+        if let Ok(slides) = song.slides(&self.credit_defaults, &self.font_db) {
+            if let Some(song_slides) = self.song_slides.as_mut() {
+                song_slides.truncate(slides.len());
+            }
             let font_db = Arc::clone(&self.font_db);
             if let Some(handle) = &self.update_slide_handle {
                 handle.abort();
@@ -0,0 +1,216 @@
+use std::time::{Duration, Instant};
+
+use cosmic::iced::alignment::Horizontal;
+use cosmic::iced::{Alignment, Color, Length, window};
+use cosmic::widget::{button, column, container, dropdown, row, space, text};
+use cosmic::{Apply, Element, Task, theme};
+use sqlx::types::chrono::Local;
+
+use crate::core::slide::Slide;
+
+const TIMER_MINUTES: [u64; 9] = [1, 5, 10, 15, 20, 25, 30, 45, 60];
+const TIMER_LABELS: &[&str] = &[
+    "1 min", "5 min", "10 min", "15 min", "20 min", "25 min", "30 min", "45 min",
+    "60 min",
+];
+
+#[derive(Debug, Clone)]
+pub struct StageDisplay {
+    pub window: window::Id,
+    position: Option<(usize, usize)>,
+    verse_label: Option<String>,
+    countdown: Duration,
+    elapsed: Duration,
+    started: Option<Instant>,
+    now: Instant,
+    clock: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    StartTimer,
+    PauseTimer,
+    ResetTimer,
+    SelectMinutes(usize),
+}
+
+impl StageDisplay {
+    #[must_use]
+    pub fn new(window: window::Id) -> Self {
+        Self {
+            window,
+            position: None,
+            verse_label: None,
+            countdown: Duration::from_secs(5 * 60),
+            elapsed: Duration::ZERO,
+            started: None,
+            now: Instant::now(),
+            clock: Self::clock(),
+        }
+    }
+
+    fn clock() -> String {
+        Local::now().format("%-I:%M:%S %p").to_string()
+    }
+
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        match message {
+            Message::StartTimer => {
+                if self.started.is_none() {
+                    self.started = Some(self.now);
+                }
+            }
+            Message::PauseTimer => {
+                if let Some(started) = self.started.take() {
+                    self.elapsed += self.now.saturating_duration_since(started);
+                }
+            }
+            Message::ResetTimer => {
+                self.started = None;
+                self.elapsed = Duration::ZERO;
+            }
+            Message::SelectMinutes(index) => {
+                if let Some(minutes) = TIMER_MINUTES.get(index) {
+                    self.countdown = Duration::from_secs(minutes * 60);
+                }
+            }
+        }
+        Task::none()
+    }
+
+    pub fn tick(&mut self, now: Instant) {
+        self.now = now;
+        self.clock = Self::clock();
+    }
+
+    /// Only looks up the verse again when the live slide has moved since
+    /// songs can be slow to split.
+    pub fn set_position(
+        &mut self,
+        position: (usize, usize),
+        verse_label: impl FnOnce() -> Option<String>,
+    ) {
+        if self.position != Some(position) {
+            self.position = Some(position);
+            self.verse_label = verse_label();
+        }
+    }
+
+    /// Seconds left on the countdown, negative once it's run over.
+    fn remaining(&self) -> i64 {
+        let elapsed = self.elapsed
+            + self.started.map_or(Duration::ZERO, |started| {
+                self.now.saturating_duration_since(started)
+            });
+        let countdown = i64::try_from(self.countdown.as_secs()).unwrap_or(i64::MAX);
+        let elapsed = i64::try_from(elapsed.as_secs()).unwrap_or(i64::MAX);
+        countdown - elapsed
+    }
+
+    pub fn view<'a>(
+        &'a self,
+        current: &'a Slide,
+        next: Option<&'a Slide>,
+        notes: String,
+    ) -> Element<'a, Message> {
+        let space_l = theme::spacing().space_l;
+        let remaining = self.remaining();
+        let timer_color = if remaining < 0 {
+            Color::from_rgb8(0xe0, 0x1b, 0x24)
+        } else {
+            Color::WHITE
+        };
+        let timer = text::title1(format_countdown(remaining))
+            .class(theme::Text::Color(timer_color));
+        let timer_controls = row![
+            dropdown(
+                TIMER_LABELS,
+                TIMER_MINUTES.iter().position(|minutes| Duration::from_secs(
+                    minutes * 60
+                ) == self.countdown),
+                Message::SelectMinutes,
+            ),
+            if self.started.is_some() {
+                button::standard("Pause").on_press(Message::PauseTimer)
+            } else {
+                button::suggested("Start").on_press(Message::StartTimer)
+            },
+            button::standard("Reset").on_press(Message::ResetTimer),
+        ]
+        .spacing(theme::spacing().space_s);
+
+        let top = row![
+            text::title2(self.verse_label.clone().unwrap_or_default()),
+            space::horizontal(),
+            text::title1(self.clock.clone()),
+        ];
+
+        let current_text = text(current.text())
+            .size(64)
+            .width(Length::Fill)
+            .align_x(Horizontal::Center)
+            .class(theme::Text::Color(Color::WHITE));
+        let next_text = text(next.map(Slide::text).unwrap_or_default())
+            .size(36)
+            .width(Length::Fill)
+            .align_x(Horizontal::Center)
+            .class(theme::Text::Color(Color::from_rgb(0.6, 0.6, 0.6)));
+        let notes = text(notes)
+            .size(28)
+            .width(Length::Fill)
+            .class(theme::Text::Color(Color::from_rgb8(0xf6, 0xd3, 0x2d)));
+
+        let bottom =
+            row![timer_controls, space::horizontal(), timer].align_y(Alignment::Center);
+
+        column![
+            top,
+            container(current_text).height(Length::FillPortion(3)),
+            row![
+                container(next_text).width(Length::FillPortion(3)),
+                container(notes).width(Length::FillPortion(2)),
+            ]
+            .spacing(space_l)
+            .height(Length::FillPortion(2)),
+            bottom,
+        ]
+        .spacing(space_l)
+        .padding(space_l)
+        .apply(container)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .style(|_| container::background(Color::BLACK))
+        .into()
+    }
+}
+
+fn format_countdown(seconds: i64) -> String {
+    let sign = if seconds < 0 { "-" } else { "" };
+    let seconds = seconds.unsigned_abs();
+    format!("{sign}{}:{:02}", seconds / 60, seconds % 60)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_countdown() {
+        let mut stage = StageDisplay::new(window::Id::unique());
+        let start = Instant::now();
+        stage.tick(start);
+        let _ = stage.update(Message::SelectMinutes(1));
+        let _ = stage.update(Message::StartTimer);
+        stage.tick(start + Duration::from_secs(90));
+        assert_eq!(format_countdown(stage.remaining()), "3:30");
+        let _ = stage.update(Message::PauseTimer);
+        stage.tick(start + Duration::from_secs(600));
+        assert_eq!(format_countdown(stage.remaining()), "3:30");
+        let _ = stage.update(Message::StartTimer);
+        stage.tick(start + Duration::from_secs(900));
+        assert_eq!(format_countdown(stage.remaining()), "-1:30");
+        let _ = stage.update(Message::ResetTimer);
+        assert_eq!(format_countdown(stage.remaining()), "5:00");
+    }
+}
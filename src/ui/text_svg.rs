@@ -7,7 +7,7 @@ use std::sync::Arc;
 use cosmic::cosmic_theme::palette::rgb::Rgba;
 use cosmic::cosmic_theme::palette::{IntoColor, Srgb};
 use cosmic::iced::font::{Style, Weight};
-use cosmic::iced::{ContentFit, Length, Size};
+use cosmic::iced::{ContentFit, Length, Rectangle, Size};
 use cosmic::prelude::*;
 use cosmic::widget::image::Handle;
 use cosmic::widget::{Image, Space};
@@ -20,16 +20,30 @@ use serde::{Deserialize, Serialize};
 use tracing::error;
 
 use crate::TextAlignment;
-use crate::core::slide::Slide;
+use crate::core::openlyrics::escape;
+use crate::core::slide::{Slide, TextMargins};
+
+const LINE_SPACING: f32 = 10.0;
+const MARGIN: f32 = 10.0;
+/// The font size of the footer in 1080p units.
+const FOOTER_SIZE: f32 = 28.0;
+
+/// The slide that font sizes are in units of.
+const SLIDE_SIZE: Size = Size::new(1920.0, 1080.0);
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct TextSvg {
     text: String,
+    #[serde(default)]
+    footer: String,
     font: Font,
     shadow: Option<Shadow>,
     stroke: Option<Stroke>,
     fill: Color,
     alignment: TextAlignment,
+    shrink_to_fit: Option<ShrinkToFit>,
+    #[serde(default)]
+    margins: TextMargins,
     pub path: Option<PathBuf>,
     #[serde(skip)]
     pub handle: Option<Handle>,
@@ -41,11 +55,14 @@ pub struct TextSvg {
 impl PartialEq for TextSvg {
     fn eq(&self, other: &Self) -> bool {
         self.text == other.text
+            && self.footer == other.footer
             && self.font == other.font
             && self.shadow == other.shadow
             && self.stroke == other.stroke
             && self.fill == other.fill
             && self.alignment == other.alignment
+            && self.shrink_to_fit == other.shrink_to_fit
+            && self.margins == other.margins
             && self.handle == other.handle
             && self.path == other.path
     }
@@ -54,11 +71,14 @@ impl PartialEq for TextSvg {
 impl Hash for TextSvg {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.text.hash(state);
+        self.footer.hash(state);
         self.font.hash(state);
         self.shadow.hash(state);
         self.stroke.hash(state);
         self.fill.hash(state);
         self.alignment.hash(state);
+        self.shrink_to_fit.hash(state);
+        self.margins.hash(state);
         self.path.hash(state);
     }
 }
@@ -71,6 +91,14 @@ pub struct Font {
     size: u8,
 }
 
+/// Sizes the font to the largest that fits the text on the slide, but
+/// never smaller than `min` or bigger than `max`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ShrinkToFit {
+    pub min: u8,
+    pub max: u8,
+}
+
 #[derive(Clone, Debug, Default, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Shadow {
     pub offset_x: i16,
@@ -164,6 +192,24 @@ impl Font {
     }
 }
 
+impl Stroke {
+    #[must_use]
+    pub const fn size(&self) -> u16 {
+        self.size
+    }
+
+    #[must_use]
+    pub fn color(&self) -> Color {
+        self.color.clone()
+    }
+}
+
+impl From<Color> for Srgb {
+    fn from(color: Color) -> Self {
+        color.0
+    }
+}
+
 impl Hash for Color {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.to_css_hex_string().hash(state);
@@ -268,6 +314,13 @@ impl TextSvg {
         self
     }
 
+    /// A small line centered along the bottom of the slide.
+    #[must_use]
+    pub fn footer(mut self, footer: impl AsRef<str>) -> Self {
+        self.footer = footer.as_ref().to_string();
+        self
+    }
+
     #[must_use]
     pub fn fontdb(mut self, fontdb: Arc<fontdb::Database>) -> Self {
         self.fontdb = fontdb;
@@ -280,6 +333,41 @@ impl TextSvg {
         self
     }
 
+    #[must_use]
+    pub const fn margins(mut self, margins: TextMargins) -> Self {
+        self.margins = margins;
+        self
+    }
+
+    #[must_use]
+    pub const fn shrink_to_fit(mut self, shrink_to_fit: Option<ShrinkToFit>) -> Self {
+        self.shrink_to_fit = shrink_to_fit;
+        self
+    }
+
+    /// The biggest font size within the limits that keeps every line
+    /// inside the slide's margins, in the same 1080p units as `Font`.
+    #[allow(clippy::cast_precision_loss)]
+    fn fitted_font_size(&self, fit: ShrinkToFit, size: Size) -> f32 {
+        let font_scale = size.height / 1080.0;
+        let text_box = self.margins.rect(Rectangle::with_size(size));
+        let (line_height, width) = measure(&self.font, &self.text, &self.fontdb);
+        let extra_lines = self.text.lines().count().saturating_sub(1) as f32;
+        let by_height = extra_lines
+            .mul_add(-LINE_SPACING, 2.0f32.mul_add(-MARGIN, text_box.height))
+            / (font_scale * (extra_lines + line_height));
+        let by_width = if width > 0.0 {
+            2.0f32.mul_add(-MARGIN, text_box.width) / (font_scale * width)
+        } else {
+            f32::MAX
+        };
+        by_height
+            .min(by_width)
+            .floor()
+            .min(f32::from(fit.max))
+            .max(f32::from(fit.min))
+    }
+
     #[must_use]
     #[allow(clippy::cast_precision_loss)]
     #[allow(clippy::too_many_lines)]
@@ -289,55 +377,48 @@ impl TextSvg {
         let mut final_svg = String::with_capacity(1024);
 
         let font_scale = size.height / 1080.0;
-        let font_size = f32::from(self.font.size) * font_scale;
+        let font_size = self.shrink_to_fit.map_or_else(
+            || f32::from(self.font.size),
+            |fit| self.fitted_font_size(fit, size),
+        ) * font_scale;
         let total_lines = self.text.lines().count();
         let half_lines = total_lines as f32 / 2.0;
-        let line_spacing = 10.0;
-        let text_and_line_spacing = font_size + line_spacing;
+        let text_and_line_spacing = font_size + LINE_SPACING;
 
-        let center_y = (size.width / 2.0).to_string();
-        let x_width_padded = (size.width - 10.0).to_string();
+        let text_box = self.margins.rect(Rectangle::with_size(size));
+        let middle_position = text_box.center_y();
+        let bottom_position = (total_lines as f32)
+            .mul_add(-text_and_line_spacing, text_box.y + text_box.height);
+        let top_position = text_box.y + font_size;
+        let left = (text_box.x + MARGIN).to_string();
+        let center_x = text_box.center_x().to_string();
+        let right = (text_box.x + text_box.width - MARGIN).to_string();
 
         let (text_anchor, starting_y_position, text_x_position) = match self.alignment {
-            TextAlignment::TopLeft => ("start", font_size, "10"),
-            TextAlignment::TopCenter => ("middle", font_size, center_y.as_str()),
-            TextAlignment::TopRight => ("end", font_size, x_width_padded.as_str()),
+            TextAlignment::TopLeft => ("start", top_position, left.as_str()),
+            TextAlignment::TopCenter => ("middle", top_position, center_x.as_str()),
+            TextAlignment::TopRight => ("end", top_position, right.as_str()),
             TextAlignment::MiddleLeft => {
-                let middle_position = size.height / 2.0;
                 let position = half_lines
                     .mul_add(-text_and_line_spacing, middle_position)
                     + text_and_line_spacing / 2.0;
-                ("start", position, "10")
+                ("start", position, left.as_str())
             }
             TextAlignment::MiddleCenter => {
-                let middle_position = size.height / 2.0;
                 let position = half_lines
                     .mul_add(-text_and_line_spacing, middle_position)
                     + text_and_line_spacing / 2.0;
-                ("middle", position, center_y.as_str())
+                ("middle", position, center_x.as_str())
             }
             TextAlignment::MiddleRight => {
-                let middle_position = size.height / 2.0;
                 let position = half_lines
                     .mul_add(-text_and_line_spacing, middle_position)
                     + text_and_line_spacing / 2.0;
-                ("end", position, x_width_padded.as_str())
-            }
-            TextAlignment::BottomLeft => {
-                let position =
-                    (total_lines as f32).mul_add(-text_and_line_spacing, size.height);
-                ("start", position, "10")
-            }
-            TextAlignment::BottomCenter => {
-                let position =
-                    (total_lines as f32).mul_add(-text_and_line_spacing, size.height);
-                ("middle", position, center_y.as_str())
-            }
-            TextAlignment::BottomRight => {
-                let position =
-                    (total_lines as f32).mul_add(-text_and_line_spacing, size.height);
-                ("end", position, x_width_padded.as_str())
+                ("end", position, right.as_str())
             }
+            TextAlignment::BottomLeft => ("start", bottom_position, left.as_str()),
+            TextAlignment::BottomCenter => ("middle", bottom_position, center_x.as_str()),
+            TextAlignment::BottomRight => ("end", bottom_position, right.as_str()),
         };
 
         let font_style = match self.font.style {
@@ -380,7 +461,7 @@ impl TextSvg {
             text_anchor,
             font_style,
             font_weight,
-            self.font.name,
+            escape(&self.font.name),
             font_size,
             self.fill
         );
@@ -403,11 +484,32 @@ impl TextSvg {
                 final_svg,
                 "<tspan x=\"0\" y=\"{}\">{}</tspan>",
                 (index as f32).mul_add(text_and_line_spacing, starting_y_position),
-                text
+                escape(text)
+            );
+        }
+
+        final_svg.push_str("</text>");
+
+        if !self.footer.is_empty() {
+            let footer_size = FOOTER_SIZE * font_scale;
+            let _ = write!(
+                final_svg,
+                "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" font-style=\"{}\" font-weight=\"{}\" font-family=\"{}\" font-size=\"{}\" fill=\"{}\"",
+                text_box.center_x(),
+                text_box.y + text_box.height - footer_size,
+                font_style,
+                font_weight,
+                escape(&self.font.name),
+                footer_size,
+                self.fill
             );
+            if self.shadow.is_some() {
+                final_svg.push_str(" style=\"filter:url(#shadow);\"");
+            }
+            let _ = write!(final_svg, ">{}</text>", escape(&self.footer));
         }
 
-        final_svg.push_str("</text></svg>");
+        final_svg.push_str("</svg>");
 
         // final_svg.push_str(&format!(
         //     "<svg viewBox=\"0 0 {} {}\" xmlns=\"http://www.w3.org/2000/svg\"><defs>{}</defs><text x=\"50%\" y=\"50%\" dominant-baseline=\"middle\" text-anchor=\"middle\" font-weight=\"bold\" font-family=\"{}\" font-size=\"{}\" fill=\"{}\" {} style=\"filter:url(#shadow);\">{}</text></svg>",
@@ -508,6 +610,119 @@ pub fn stroke(size: u16, color: impl Into<Color>) -> Stroke {
     }
 }
 
+/// The height of a line and the width of the widest line of the text,
+/// both in ems. These come from the font's face in the fontdb, or a rough
+/// guess when the font isn't there.
+#[allow(clippy::cast_precision_loss)]
+fn measure(font: &Font, text: &str, fontdb: &fontdb::Database) -> (f32, f32) {
+    let weight = match font.weight {
+        Weight::Thin => 100,
+        Weight::ExtraLight => 200,
+        Weight::Light => 300,
+        Weight::Normal => 400,
+        Weight::Medium => 500,
+        Weight::Semibold => 600,
+        Weight::Bold => 700,
+        Weight::ExtraBold => 800,
+        Weight::Black => 900,
+    };
+    let style = match font.style {
+        Style::Normal => fontdb::Style::Normal,
+        Style::Italic => fontdb::Style::Italic,
+        Style::Oblique => fontdb::Style::Oblique,
+    };
+    let query = fontdb::Query {
+        families: &[fontdb::Family::Name(&font.name)],
+        weight: fontdb::Weight(weight),
+        stretch: fontdb::Stretch::Normal,
+        style,
+    };
+    fontdb
+        .query(&query)
+        .and_then(|id| {
+            fontdb.with_face_data(id, |data, index| {
+                let face = ttf_parser::Face::parse(data, index).ok()?;
+                let units = f32::from(face.units_per_em());
+                let line_height =
+                    (f32::from(face.ascender()) - f32::from(face.descender())) / units;
+                let width = text
+                    .lines()
+                    .map(|line| {
+                        line.chars()
+                            .map(|c| {
+                                face.glyph_index(c)
+                                    .and_then(|glyph| face.glyph_hor_advance(glyph))
+                                    .map_or(units / 2.0, f32::from)
+                            })
+                            .sum::<f32>()
+                    })
+                    .fold(0.0, f32::max)
+                    / units;
+                Some((line_height, width))
+            })
+        })
+        .flatten()
+        .unwrap_or_else(|| {
+            let widest = text.lines().map(|line| line.chars().count()).max();
+            (1.2, widest.unwrap_or_default() as f32 * 0.6)
+        })
+}
+
+/// How many lines of text in this font fit inside the margins of a slide
+/// when laid out the way `TextSvg::build` does it.
+#[must_use]
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub fn lines_that_fit(
+    font: &Font,
+    margins: TextMargins,
+    fontdb: &fontdb::Database,
+) -> usize {
+    let (line_height, _) = measure(font, "", fontdb);
+    let font_size = f32::from(font.size);
+    let text_box = margins.rect(Rectangle::with_size(SLIDE_SIZE));
+    let available = text_box.height - 2.0 * MARGIN - line_height * font_size;
+    if available <= 0.0 {
+        1
+    } else {
+        (available / (font_size + LINE_SPACING)) as usize + 1
+    }
+}
+
+/// Breaks a line that is too wide for the margins of a slide before the
+/// words that would run past them. A word too wide on its own keeps a
+/// line to itself.
+#[must_use]
+pub fn wrap_line(
+    line: &str,
+    font: &Font,
+    margins: TextMargins,
+    fontdb: &fontdb::Database,
+) -> Vec<String> {
+    let text_box = margins.rect(Rectangle::with_size(SLIDE_SIZE));
+    let available = 2.0f32.mul_add(-MARGIN, text_box.width);
+    let fits =
+        |text: &str| measure(font, text, fontdb).1 * f32::from(font.size) <= available;
+    if fits(line) {
+        return vec![line.to_string()];
+    }
+    let mut lines = vec![];
+    let mut current = String::new();
+    for word in line.split_whitespace() {
+        if current.is_empty() {
+            current.push_str(word);
+            continue;
+        }
+        let longer = format!("{current} {word}");
+        if fits(&longer) {
+            current = longer;
+        } else {
+            lines.push(std::mem::replace(&mut current, word.to_string()));
+        }
+    }
+    lines.push(current);
+    lines
+}
+
 pub fn color(color: impl AsRef<str>) -> Color {
     Color::from_hex_str(color)
 }
@@ -528,8 +743,17 @@ pub fn text_svg_generator(
 }
 
 pub fn text_svg_generator_with_cache(
+    slide: crate::core::slide::Slide,
+    fontdb: &Arc<fontdb::Database>,
+    cache: Option<PathBuf>,
+) -> Result<Slide> {
+    text_svg_generator_with_size(slide, fontdb, Size::new(1280.0, 720.0), cache)
+}
+
+pub fn text_svg_generator_with_size(
     mut slide: crate::core::slide::Slide,
     fontdb: &Arc<fontdb::Database>,
+    size: Size,
     cache: Option<PathBuf>,
 ) -> Result<Slide> {
     if slide.text().is_empty() {
@@ -537,7 +761,10 @@ pub fn text_svg_generator_with_cache(
     } else {
         let font = slide.font().unwrap_or_default();
         let text_svg = TextSvg::new(slide.text())
+            .footer(slide.footer())
             .alignment(slide.text_alignment())
+            .shrink_to_fit(slide.shrink_to_fit())
+            .margins(slide.text_margins())
             .fill(slide.text_color().unwrap_or_else(|| "#fff".into()));
         let text_svg = if let Some(stroke) = slide.stroke() {
             text_svg.stroke(stroke)
@@ -551,7 +778,7 @@ pub fn text_svg_generator_with_cache(
         };
         let text_svg = text_svg.font(font).fontdb(Arc::clone(fontdb));
         // debug!(fill = ?text_svg.fill, font = ?text_svg.font, stroke = ?text_svg.stroke, shadow = ?text_svg.shadow, text = ?text_svg.text);
-        let text_svg = text_svg.build(Size::new(1280.0, 720.0), cache);
+        let text_svg = text_svg.build(size, cache);
         slide.text_svg = Some(text_svg);
         Ok(slide)
     }
@@ -597,4 +824,66 @@ mod tests {
             }
         });
     }
+
+    #[test]
+    fn test_shrink_to_fit() {
+        let fontdb = Arc::new(Database::new());
+        let fit = ShrinkToFit { min: 40, max: 150 };
+        let size = Size::new(1920.0, 1080.0);
+        let text_svg = TextSvg::new("Amazing grace")
+            .font(Font::from("Quicksand").size(100))
+            .fontdb(Arc::clone(&fontdb));
+        assert!((text_svg.fitted_font_size(fit, size) - 150.0).abs() < f32::EPSILON);
+
+        let text_svg = text_svg
+            .text("Amazing grace how sweet the sound that saved a wretch like me");
+        let fitted = text_svg.fitted_font_size(fit, size);
+        assert!(fitted < 100.0 && fitted > 40.0);
+
+        let text_svg = text_svg.text("line\n".repeat(40));
+        assert!((text_svg.fitted_font_size(fit, size) - 40.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_escaped_text() {
+        let text_svg = TextSvg::new("Grace & Truth\n<Chorus>")
+            .footer("© Hillsong Music & Integrity | \"CCLI\" Song # 1")
+            .fontdb(Arc::new(Database::new()))
+            .build(Size::new(1920.0, 1080.0), None);
+        assert!(text_svg.handle.is_some());
+    }
+
+    #[test]
+    fn test_lines_that_fit() {
+        let fontdb = Database::new();
+        let font = Font::from("Quicksand").size(100);
+        assert_eq!(lines_that_fit(&font, TextMargins::default(), &fontdb), 9);
+        assert_eq!(lines_that_fit(&font, TextMargins::LOWER_THIRD, &fontdb), 2);
+        let font = font.size(250);
+        assert_eq!(lines_that_fit(&font, TextMargins::default(), &fontdb), 3);
+    }
+
+    #[test]
+    fn test_wrap_line() {
+        let fontdb = Database::new();
+        let font = Font::from("Quicksand").size(100);
+        let margins = TextMargins::default();
+        assert_eq!(
+            wrap_line("Amazing grace", &font, margins, &fontdb),
+            vec!["Amazing grace"]
+        );
+        let line = "Amazing grace how sweet the sound that saved a wretch like me";
+        let wrapped = wrap_line(line, &font, margins, &fontdb);
+        assert!(wrapped.len() > 1);
+        assert_eq!(wrapped.join(" "), line);
+        let width = margins.rect(Rectangle::with_size(SLIDE_SIZE)).width - 2.0 * MARGIN;
+        for line in &wrapped {
+            assert!(measure(&font, line, &fontdb).1 * 100.0 <= width);
+        }
+        let font = font.size(250);
+        assert_eq!(
+            wrap_line("Supercalifragilistic", &font, margins, &fontdb),
+            vec!["Supercalifragilistic"]
+        );
+    }
 }
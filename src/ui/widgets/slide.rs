@@ -53,6 +53,7 @@ where
     height: Length,
     content_fit: ContentFit,
     animation_state: AnimationState,
+    text_opacity: f32,
 }
 
 pub struct SlideSettings<'a> {
@@ -107,6 +108,7 @@ where
             height: Length::Fill,
             content_fit: ContentFit::Fill,
             animation_state: AnimationState::Idle,
+            text_opacity: 1.0,
         }
     }
 
@@ -130,6 +132,12 @@ where
         self
     }
 
+    /// Fades the text of every slide without touching the backgrounds.
+    pub(crate) fn text_opacity(mut self, text_opacity: f32) -> Self {
+        self.text_opacity = text_opacity;
+        self
+    }
+
     #[inline(always)]
     fn draw_background(
         &self,
@@ -146,6 +154,7 @@ where
     ) {
         let background = self.slide.background();
         if let Some(allocation) = background.image_allocation.as_ref() {
+            let image_bounds = fit_image(background, allocation.size(), bounds);
             renderer.with_layer(bounds, |renderer| {
                 renderer.draw_image(
                     iced_core::image::Image {
@@ -156,13 +165,16 @@ where
                         opacity,
                         snap: true,
                     },
-                    bounds,
+                    image_bounds,
                     clip_bounds,
                 )
             });
         } else {
             if let Some(handle) = &background.image_handle {
                 let _ = renderer.load_image(handle);
+                let image_bounds = renderer
+                    .measure_image(handle)
+                    .map_or(bounds, |size| fit_image(background, size, bounds));
                 renderer.with_layer(bounds, |renderer| {
                     renderer.draw_image(
                         iced_core::image::Image {
@@ -173,7 +185,7 @@ where
                             opacity,
                             snap: true,
                         },
-                        bounds,
+                        image_bounds,
                         clip_bounds,
                     )
                 });
@@ -182,6 +194,28 @@ where
     }
 }
 
+/// Where an image background is drawn for its fit. Without one it stretches
+/// over the whole slide.
+fn fit_image(
+    background: &crate::core::slide::Background,
+    size: Size<u32>,
+    bounds: Rectangle,
+) -> Rectangle {
+    if background.fit.is_none() {
+        return bounds;
+    }
+    #[allow(clippy::cast_precision_loss)]
+    let size = Size::new(size.width as f32, size.height as f32);
+    let fitted = background.content_fit().fit(size, bounds.size());
+    Rectangle::new(
+        Point::new(
+            bounds.center_x() - fitted.width / 2.0,
+            bounds.center_y() - fitted.height / 2.0,
+        ),
+        fitted,
+    )
+}
+
 impl<Message, Theme, Renderer> Widget<Message, Theme, Renderer>
     for Slide<'_, Message, Theme, Renderer>
 where
@@ -457,6 +491,11 @@ where
                         prev_slide.background().image_allocation.as_ref()
                 {
                     if prev_slide.background() != background {
+                        let prev_bounds = fit_image(
+                            prev_slide.background(),
+                            prev_allocation.size(),
+                            bounds,
+                        );
                         renderer.with_layer(bounds, |renderer| {
                             renderer.draw_image(
                                 iced_core::image::Image {
@@ -468,7 +507,7 @@ where
                                     opacity: prev_slide_opacity,
                                     snap: true,
                                 },
-                                bounds,
+                                prev_bounds,
                                 clip_bounds,
                             )
                         });
@@ -579,7 +618,7 @@ where
                         filter_method: iced_core::image::FilterMethod::Nearest,
                         rotation: iced_core::Radians(0.0),
                         border_radius: Radius::new(0.0),
-                        opacity: prev_slide_opacity,
+                        opacity: prev_slide_opacity * self.text_opacity,
                         snap: true,
                     },
                     Rectangle::new(prev_foreground_position, bounds.size()),
@@ -599,7 +638,7 @@ where
                         filter_method: iced_core::image::FilterMethod::Nearest,
                         rotation: iced_core::Radians(0.0),
                         border_radius: Radius::new(0.0),
-                        opacity: next_text_opacity,
+                        opacity: next_text_opacity * self.text_opacity,
                         snap: true,
                     },
                     Rectangle::new(next_foreground_position, next_foreground_size),
@@ -618,7 +657,7 @@ where
                         filter_method: iced_core::image::FilterMethod::Nearest,
                         rotation: iced_core::Radians(0.0),
                         border_radius: Radius::new(0.0),
-                        opacity: current_slide_opacity,
+                        opacity: current_slide_opacity * self.text_opacity,
                         snap: true,
                     },
                     Rectangle::new(current_foreground_position, current_foreground_size),